@@ -7,8 +7,37 @@ struct ContourSettings {
     line_color: vec3<f32>,
     line_thickness: f32,
     enabled: u32,
+    every_nth: u32,
+    tint_enabled: u32,
+    tint_opacity: f32,
+    tint_stop_count: u32,
+    // Linear RGB in xyz, elevation in meters in w, lowest first
+    tint_stops: array<vec4<f32>, MAX_TINT_STOPS>,
+    mask_enabled: u32,
+    // Cosine of the steepest buildable slope
+    mask_min_normal_y: f32,
+    // Line color ramp: linear RGB in xyz, position along the ramp (0-1) in w. A count of 0
+    // keeps line_color for every line
+    ramp_stop_count: u32,
+    ramp_stops: array<vec4<f32>, MAX_RAMP_STOPS>,
+    ramp_min: f32,
+    ramp_max: f32,
 }
 
+// Keep in sync with MAX_TINT_STOPS in hypsometric.rs
+const MAX_TINT_STOPS: u32 = 8u;
+// Keep in sync with MAX_RAMP_STOPS in contour_ramp.rs
+const MAX_RAMP_STOPS: u32 = 4u;
+
+// Buildability mask colors (linear RGB) and how much they cover the lit terrain
+const BUILDABLE_COLOR: vec3<f32> = vec3<f32>(0.05, 0.6, 0.05);
+const UNBUILDABLE_COLOR: vec3<f32> = vec3<f32>(0.7, 0.03, 0.02);
+const MASK_OPACITY: f32 = 0.75;
+
+// Keep in sync with MAJOR_LINE_SCALE and MINOR_LINE_ALPHA in contour_lines.rs
+const MAJOR_LINE_SCALE: f32 = 2.0;
+const MINOR_LINE_ALPHA: f32 = 0.6;
+
 // Material bindings - AsBindGroup with Material trait uses bind group 3
 // Matching shadplay pattern: material bindings go in group 3
 @group(3) @binding(100)
@@ -19,16 +48,64 @@ var placeholder_sampler: sampler;
 @group(3) @binding(102)
 var<uniform> contour_settings: ContourSettings;
 
+// Color of the elevation ramp at `height`, held flat below the first and above the last stop
+fn tint_color(height: f32) -> vec3<f32> {
+    let count = min(contour_settings.tint_stop_count, MAX_TINT_STOPS);
+    var color = contour_settings.tint_stops[0].rgb;
+    for (var i = 1u; i < count; i++) {
+        let low = contour_settings.tint_stops[i - 1u];
+        let high = contour_settings.tint_stops[i];
+        if height >= high.w {
+            color = high.rgb;
+        } else {
+            if height > low.w {
+                color = mix(low.rgb, high.rgb, (height - low.w) / (high.w - low.w));
+            }
+            break;
+        }
+    }
+    return color;
+}
+
+// Color of the contour line at `height` (the line's own elevation), matching
+// ContourState::line_color_at
+fn line_color_at(height: f32) -> vec3<f32> {
+    let count = min(contour_settings.ramp_stop_count, MAX_RAMP_STOPS);
+    if count == 0u {
+        return contour_settings.line_color;
+    }
+    let span = contour_settings.ramp_max - contour_settings.ramp_min;
+    let t = select(0.0, clamp((height - contour_settings.ramp_min) / span, 0.0, 1.0), span > 0.0);
+    var color = contour_settings.ramp_stops[0].rgb;
+    for (var i = 1u; i < count; i++) {
+        let low = contour_settings.ramp_stops[i - 1u];
+        let high = contour_settings.ramp_stops[i];
+        if t >= high.w {
+            color = high.rgb;
+        } else {
+            if t > low.w {
+                color = mix(low.rgb, high.rgb, (t - low.w) / (high.w - low.w));
+            }
+            break;
+        }
+    }
+    return color;
+}
+
 @fragment
 fn fragment(
     in: VertexOutput,
 ) -> @location(0) vec4<f32> {
-    // Early return if contour lines are disabled
-    if contour_settings.enabled == 0u {
+    let lines_enabled = contour_settings.enabled != 0u;
+    let tint_enabled = contour_settings.tint_enabled != 0u && contour_settings.tint_stop_count > 0u;
+    let mask_enabled = contour_settings.mask_enabled != 0u;
+    // Early return if no overlay layer is enabled
+    if !lines_enabled && !tint_enabled && !mask_enabled {
         return vec4<f32>(0.0, 0.0, 0.0, 0.0);
     }
 
-    // Get world position height (Y component)
+    // Meters of world elevation, the same scale the CPU contours use. The tests in
+    // marching_squares.rs mirror the level math below
     let world_height = in.world_position.y;
     
     // Compute normalized position within the contour interval (0-1 range)
@@ -48,15 +125,44 @@ fn fragment(
     // This converts world-space distance to screen-space (pixel) distance
     let screen_space_dist = dist_from_boundary / contour_fwidth;
     
+    // Every nth contour counted from zero elevation is a major line
+    let n = i32(contour_settings.every_nth);
+    let level = i32(round(world_height / contour_settings.interval));
+    let is_major = n > 0 && ((level % n) + n) % n == 0;
+    let thickness = select(
+        contour_settings.line_thickness,
+        contour_settings.line_thickness * MAJOR_LINE_SCALE,
+        is_major,
+    );
+    let opacity = select(MINOR_LINE_ALPHA, 1.0, is_major);
+    // Every pixel of a line takes the color of the band's contour level, not its own height
+    let line_color = line_color_at(f32(level) * contour_settings.interval);
+
     // Use smoothstep to create an anti-aliased line with constant screen-space thickness
     // line_thickness controls how many pixels wide the line appears
     // Smaller values = thinner lines, larger values = thicker lines
-    let line_factor = 1.0 - smoothstep(0.0, contour_settings.line_thickness, screen_space_dist);
+    let line_factor = select(
+        0.0,
+        (1.0 - smoothstep(0.0, thickness, screen_space_dist)) * opacity,
+        lines_enabled,
+    );
+
+    // Lines are drawn over the mask or tint, which in turn covers the lit terrain
+    if mask_enabled {
+        let buildable = normalize(in.world_normal).y >= contour_settings.mask_min_normal_y;
+        let mask = select(UNBUILDABLE_COLOR, BUILDABLE_COLOR, buildable);
+        let color = mix(mask, line_color, line_factor);
+        return vec4<f32>(color, max(MASK_OPACITY, line_factor));
+    }
+    if tint_enabled {
+        let color = mix(tint_color(world_height), line_color, line_factor);
+        return vec4<f32>(color, max(contour_settings.tint_opacity, line_factor));
+    }
     
     // Blend between transparent background and line color
     let color = mix(
         vec3<f32>(0.0, 0.0, 0.0),  // Background (transparent when alpha is 0)
-        contour_settings.line_color,
+        line_color,
         line_factor
     );
     
@@ -1,14 +1,23 @@
 use glam::Vec3;
 
 use crate::geometry::{
-	azimuth_of_tangent, circular_section_length, difference_in_azimuth, total_tangent_length,
+	AlignmentGeometry, GeometrySegment, azimuth_of_tangent, circular_section_length,
+	difference_in_azimuth, total_tangent_length,
 };
 use crate::path::{Alignment, PathSegment, TurnSegment, project_fraction_onto_span};
+use crate::tolerance::GeometryTolerances;
 
 pub const MIN_ARC_RADIUS: f32 = 1.0;
 pub const MAX_ARC_RADIUS: f32 = 2000.0;
-const TANGENT_EPSILON: f32 = 1.0e-3;
+/// Closest two consecutive control points may sit in plan, in meters. Anything closer leaves a
+/// zero-length leg whose direction is undefined.
+pub const MIN_VERTEX_SPACING: f32 = 0.1;
 const STRAIGHT_BOUNDARY_EPSILON: f32 = 1.0e-4;
+// Cant is converted to a superelevation ratio over the distance between rail centers.
+const CANT_REFERENCE_WIDTH_MM: f32 = 1500.0;
+const SIDE_FRICTION_FACTOR: f32 = 0.1;
+// Equilibrium cant for standard gauge: E = 11.8 * V² / R (mm, km/h, m)
+const EQUILIBRIUM_CANT_FACTOR: f32 = 11.8;
 
 // Convenience: compute the max allowable circular angle at a vertex, given its neighbors
 pub fn compute_max_angle(previous: Vec3, vertex: Vec3, next: Vec3) -> f32 {
@@ -17,8 +26,186 @@ pub fn compute_max_angle(previous: Vec3, vertex: Vec3, next: Vec3) -> f32 {
 	difference_in_azimuth(az_i, az_ip1)
 }
 
+// Minimum curve radius for a design speed and cant: R = V² / (127 * (e + f))
+pub fn min_radius_for_speed(design_speed_kmh: f32, max_cant_mm: f32) -> f32 {
+	let superelevation = max_cant_mm.max(0.0) / CANT_REFERENCE_WIDTH_MM;
+	design_speed_kmh.powi(2) / (127.0 * (superelevation + SIDE_FRICTION_FACTOR))
+}
+
+// Indices of turn segments whose radius is below the minimum for the design speed.
+// MIN_ARC_RADIUS remains the hard floor; this is a soft check for the designer.
+pub fn check_min_radius(
+	alignment: &Alignment,
+	design_speed_kmh: f32,
+	max_cant_mm: f32,
+) -> Vec<usize> {
+	let min_radius = min_radius_for_speed(design_speed_kmh, max_cant_mm);
+	alignment
+		.segments
+		.iter()
+		.enumerate()
+		.filter_map(|(i, segment)| {
+			let turn = segment.as_turn()?;
+			(turn.circular_section_radius < min_radius).then_some(i)
+		})
+		.collect()
+}
+
+// Length over which cant is run in at a gradient of `max_cant_gradient` mm per meter.
+pub fn cant_runoff_length(cant_mm: f32, max_cant_gradient: f32) -> f32 {
+	if max_cant_gradient <= 0.0 {
+		return f32::INFINITY;
+	}
+	cant_mm.max(0.0) / max_cant_gradient
+}
+
+// Equilibrium cant for the design speed, limited to the maximum allowed cant.
+pub fn applied_cant_mm(radius: f32, design_speed_kmh: f32, max_cant_mm: f32) -> f32 {
+	if radius <= 0.0 {
+		return max_cant_mm.max(0.0);
+	}
+	(EQUILIBRIUM_CANT_FACTOR * design_speed_kmh.powi(2) / radius).clamp(0.0, max_cant_mm.max(0.0))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CantRunoffShortfall {
+	/// Index into `AlignmentGeometry::segments`.
+	pub segment_index: usize,
+	pub required_length: f32,
+	pub clothoid_length: f32,
+}
+
+// Turns whose shorter clothoid can't run in their applied cant at the maximum gradient.
+pub fn check_cant_runoff(
+	geometry: &AlignmentGeometry,
+	design_speed_kmh: f32,
+	max_cant_mm: f32,
+	max_cant_gradient: f32,
+) -> Vec<CantRunoffShortfall> {
+	geometry
+		.segments
+		.iter()
+		.enumerate()
+		.filter_map(|(segment_index, segment)| {
+			let GeometrySegment::Turn(turn) = segment else {
+				return None;
+			};
+			let cant = applied_cant_mm(turn.circular_arc.radius(), design_speed_kmh, max_cant_mm);
+			let required_length = cant_runoff_length(cant, max_cant_gradient);
+			let clothoid_length = turn
+				.ingoing_clothoid
+				.length
+				.min(turn.outgoing_clothoid.length);
+			(clothoid_length < required_length).then_some(CantRunoffShortfall {
+				segment_index,
+				required_length,
+				clothoid_length,
+			})
+		})
+		.collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClothoidParameterWarning {
+	/// Index into `AlignmentGeometry::segments`.
+	pub segment_index: usize,
+	pub parameter: f64,
+	pub radius: f64,
+}
+
+// Rule of thumb for a well-proportioned transition: R/3 <= A <= R. Below that the transition
+// is too short to feel; above it the curve is mostly spiral.
+pub fn clothoid_parameter_in_range(parameter: f64, radius: f64) -> bool {
+	(radius / 3.0..=radius).contains(&parameter)
+}
+
+// Turns whose clothoid parameter falls outside the R/3..=R band.
+pub fn check_clothoid_parameter(geometry: &AlignmentGeometry) -> Vec<ClothoidParameterWarning> {
+	geometry
+		.segments
+		.iter()
+		.enumerate()
+		.filter_map(|(segment_index, segment)| {
+			let GeometrySegment::Turn(turn) = segment else {
+				return None;
+			};
+			let parameter = turn.clothoid_parameter();
+			let radius = f64::from(turn.circular_arc.radius());
+			(!clothoid_parameter_in_range(parameter, radius)).then_some(ClothoidParameterWarning {
+				segment_index,
+				parameter,
+				radius,
+			})
+		})
+		.collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TangentOverlap {
+	/// Leg of the tangent polygon, between control points `leg_index` and `leg_index + 1`.
+	pub leg_index: usize,
+	/// Tangent length the turns at either end of the leg take up together.
+	pub required_length: f32,
+	pub available_length: f32,
+}
+
+// Legs too short for the tangents of the turns at both ends. The transitions then overlap and
+// the path folds back on itself. Enforcing constraints shrinks turns to fit, but it stops at the
+// minimum radius and the full deflection, and bound endpoints can move after it has run.
+pub fn check_tangent_overlap(
+	alignment: &Alignment,
+	start: Vec3,
+	end: Vec3,
+	tolerances: &GeometryTolerances,
+) -> Vec<TangentOverlap> {
+	let control_points = alignment.control_points_with_endpoints(start, end);
+	let tangent_at = |point_index: usize| -> f32 {
+		let Some(turn) = point_index
+			.checked_sub(1)
+			.and_then(|segment_index| alignment.segments.get(segment_index))
+			.and_then(PathSegment::as_turn)
+		else {
+			return 0.0;
+		};
+		let (Some(previous), Some(next)) = (
+			control_points.get(point_index - 1),
+			control_points.get(point_index + 1),
+		) else {
+			return 0.0;
+		};
+		let diff_az = segment_turn_delta(*previous, turn.tangent_vertex, *next);
+		if tolerances.is_straight(diff_az) || tolerances.is_straight(std::f32::consts::PI - diff_az) {
+			return 0.0;
+		}
+		let angle = turn.arc_angle(diff_az);
+		let l_c = circular_section_length(turn.circular_section_radius, angle, diff_az);
+		let length = total_tangent_length(turn.circular_section_radius, angle, diff_az, l_c);
+		if length.is_finite() { length } else { 0.0 }
+	};
+	control_points
+		.windows(2)
+		.enumerate()
+		.filter_map(|(leg_index, leg)| {
+			let required_length = tangent_at(leg_index) + tangent_at(leg_index + 1);
+			let available_length = plan_offset(leg[0], leg[1]).length();
+			(required_length > available_length + tolerances.coincidence_distance).then_some(
+				TangentOverlap {
+					leg_index,
+					required_length,
+					available_length,
+				},
+			)
+		})
+		.collect()
+}
+
 // Clamp a turn's parameters to valid ranges based on geometry
-pub fn clamp_turn_parameters(turn: &mut TurnSegment, previous: Vec3, next: Vec3) {
+pub fn clamp_turn_parameters(
+	turn: &mut TurnSegment,
+	previous: Vec3,
+	next: Vec3,
+	tolerances: &GeometryTolerances,
+) {
 	if !turn.circular_section_radius.is_finite() || turn.circular_section_radius <= 0.0 {
 		turn.circular_section_radius = MIN_ARC_RADIUS;
 	} else if turn.circular_section_radius < MIN_ARC_RADIUS {
@@ -29,6 +216,11 @@ pub fn clamp_turn_parameters(turn: &mut TurnSegment, previous: Vec3, next: Vec3)
 		turn.circular_section_radius = MAX_ARC_RADIUS;
 	}
 
+	let az_i = azimuth_of_tangent(turn.tangent_vertex, previous);
+	let az_ip1 = azimuth_of_tangent(next, turn.tangent_vertex);
+	let diff_az = difference_in_azimuth(az_i, az_ip1);
+	turn.circular_section_angle = turn.arc_angle(diff_az);
+
 	if !turn.circular_section_angle.is_finite() || turn.circular_section_angle < 0.0 {
 		turn.circular_section_angle = 0.0;
 	}
@@ -37,22 +229,20 @@ pub fn clamp_turn_parameters(turn: &mut TurnSegment, previous: Vec3, next: Vec3)
 		turn.circular_section_angle = max_angle;
 	}
 
-	let az_i = azimuth_of_tangent(turn.tangent_vertex, previous);
-	let az_ip1 = azimuth_of_tangent(next, turn.tangent_vertex);
-	let diff_az = difference_in_azimuth(az_i, az_ip1);
 	let available_prev = previous.distance(turn.tangent_vertex);
 	let available_next = turn.tangent_vertex.distance(next);
-	let allowed = (available_prev.min(available_next) - TANGENT_EPSILON).max(0.0);
+	let allowed = (available_prev.min(available_next) - tolerances.coincidence_distance).max(0.0);
 
 	ensure_tangent_within_limit(turn, max_angle, diff_az, allowed);
 }
 
-pub fn enforce_alignment_constraints(alignment: &mut Alignment) {
+pub fn enforce_alignment_constraints(alignment: &mut Alignment, tolerances: &GeometryTolerances) {
 	if alignment.segments.is_empty() {
 		return;
 	}
 
-	enforce_straight_boundary_fractions(alignment);
+	enforce_min_vertex_spacing(alignment);
+	enforce_straight_boundary_fractions(alignment, tolerances);
 	let control_points = alignment.control_points();
 
 	for (i, segment) in alignment.segments.iter_mut().enumerate() {
@@ -61,13 +251,73 @@ pub fn enforce_alignment_constraints(alignment: &mut Alignment) {
 		};
 		let previous = control_points[i];
 		let next = control_points[i + 2];
-		clamp_turn_parameters(turn, previous, next);
+		clamp_turn_parameters(turn, previous, next, tolerances);
+	}
+
+	clamp_shared_edge_tangents(&mut alignment.segments, &control_points, tolerances);
+
+	// Clamping may have moved the radius or angle; keep a length or A spec in step with them
+	for (i, segment) in alignment.segments.iter_mut().enumerate() {
+		if let Some(turn) = segment.as_turn_mut() {
+			let diff_az = segment_turn_delta(
+				control_points[i],
+				turn.tangent_vertex,
+				control_points[i + 2],
+			);
+			turn.sync_transition(diff_az);
+		}
 	}
+}
+
+fn plan_offset(from: Vec3, to: Vec3) -> Vec3 {
+	Vec3::new(to.x - from.x, 0.0, to.z - from.z)
+}
 
-	clamp_shared_edge_tangents(&mut alignment.segments, &control_points);
+// Direction of travel into `control_points[index]`, taken from the last leg before it that is
+// long enough to have one. Falls back to the outgoing leg, then to +X.
+fn incoming_direction(control_points: &[Vec3], index: usize) -> Vec3 {
+	let previous = control_points[index - 1];
+	control_points[..index - 1]
+		.iter()
+		.rev()
+		.map(|earlier| plan_offset(*earlier, previous))
+		.chain(
+			control_points
+				.get(index + 1)
+				.map(|next| plan_offset(previous, *next)),
+		)
+		.find(|leg| leg.length() >= MIN_VERTEX_SPACING)
+		.map_or(Vec3::X, Vec3::normalize)
 }
 
-fn enforce_straight_boundary_fractions(alignment: &mut Alignment) {
+// Pushes a tangent vertex that sits on its previous control point `MIN_VERTEX_SPACING` on along
+// the incoming direction. The last vertex is instead pulled back off the alignment end, which
+// can't move.
+fn enforce_min_vertex_spacing(alignment: &mut Alignment) {
+	let segment_count = alignment.segments.len();
+	for i in 0..segment_count {
+		let control_points = alignment.control_points();
+		let Some(turn) = alignment.segments[i].as_turn_mut() else {
+			continue;
+		};
+		let (previous, vertex, next) = (
+			control_points[i],
+			turn.tangent_vertex,
+			control_points[i + 2],
+		);
+		let incoming = incoming_direction(&control_points, i + 1);
+		let nudged = if plan_offset(previous, vertex).length() < MIN_VERTEX_SPACING {
+			previous + incoming * MIN_VERTEX_SPACING
+		} else if i + 1 == segment_count && plan_offset(vertex, next).length() < MIN_VERTEX_SPACING {
+			next - incoming * MIN_VERTEX_SPACING
+		} else {
+			continue;
+		};
+		turn.tangent_vertex = nudged.with_y(vertex.y);
+	}
+}
+
+fn enforce_straight_boundary_fractions(alignment: &mut Alignment, tolerances: &GeometryTolerances) {
 	let control_points = alignment.control_points();
 
 	let mut run_start = 0;
@@ -93,7 +343,8 @@ fn enforce_straight_boundary_fractions(alignment: &mut Alignment) {
 		for offset in 0..run_len {
 			let segment_index = run_start + offset;
 			let current = control_points[segment_index + 1];
-			let projected_fraction = project_fraction_onto_span(current, section_start, section_end);
+			let projected_fraction =
+				project_fraction_onto_span(current, section_start, section_end, tolerances);
 			let remaining = run_len - offset - 1;
 			let max_for_current =
 				(max_fraction - STRAIGHT_BOUNDARY_EPSILON * remaining as f32).max(min_fraction);
@@ -108,7 +359,11 @@ fn enforce_straight_boundary_fractions(alignment: &mut Alignment) {
 	}
 }
 
-fn clamp_shared_edge_tangents(segments: &mut [PathSegment], control_points: &[Vec3]) {
+fn clamp_shared_edge_tangents(
+	segments: &mut [PathSegment],
+	control_points: &[Vec3],
+	tolerances: &GeometryTolerances,
+) {
 	if segments.len() < 2 {
 		return;
 	}
@@ -120,7 +375,7 @@ fn clamp_shared_edge_tangents(segments: &mut [PathSegment], control_points: &[Ve
 		let left_cp = control_points[edge_idx];
 		let right_cp = control_points[edge_idx + 1];
 		let distance_between_control_points = left_cp.distance(right_cp);
-		let allowed_sum = (distance_between_control_points - TANGENT_EPSILON).max(0.0);
+		let allowed_sum = (distance_between_control_points - tolerances.coincidence_distance).max(0.0);
 		if allowed_sum <= 0.0 {
 			continue;
 		}
@@ -238,7 +493,43 @@ fn ensure_tangent_within_limit(
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::path::StraightSegment;
+	use crate::path::{StraightSegment, TransitionSpec};
+	use std::f32::consts::FRAC_PI_2;
+
+	#[test]
+	fn tangent_overlap_flags_a_leg_too_short_for_both_turns() {
+		// Two right angles 20 m apart, each with a 50 m radius: the tangents need far more than 20 m.
+		let alignment = Alignment::from_vertices(
+			Vec3::ZERO,
+			Vec3::new(200.0, 0.0, 220.0),
+			vec![
+				(Vec3::new(100.0, 0.0, 0.0), 50.0, 0.5),
+				(Vec3::new(100.0, 0.0, 20.0), 50.0, 0.2),
+			],
+		);
+		let overlaps = check_tangent_overlap(
+			&alignment,
+			alignment.start,
+			alignment.end,
+			&GeometryTolerances::DEFAULT,
+		);
+		assert_eq!(overlaps.len(), 1);
+		assert_eq!(overlaps[0].leg_index, 1);
+		assert!((overlaps[0].available_length - 20.0).abs() < 1e-4);
+		assert!(overlaps[0].required_length > 20.0);
+
+		let mut enforced = alignment.clone();
+		enforce_alignment_constraints(&mut enforced, &GeometryTolerances::DEFAULT);
+		assert!(
+			check_tangent_overlap(
+				&enforced,
+				enforced.start,
+				enforced.end,
+				&GeometryTolerances::DEFAULT
+			)
+			.is_empty()
+		);
+	}
 
 	#[test]
 	fn straight_boundary_fraction_is_clamped_to_tangent_span() {
@@ -252,7 +543,7 @@ mod tests {
 			straight.fraction = 1.25;
 		}
 
-		enforce_alignment_constraints(&mut alignment);
+		enforce_alignment_constraints(&mut alignment, &GeometryTolerances::DEFAULT);
 
 		let boundary = alignment
 			.segment_control_point(0)
@@ -274,7 +565,7 @@ mod tests {
 			..Default::default()
 		};
 
-		enforce_alignment_constraints(&mut alignment);
+		enforce_alignment_constraints(&mut alignment, &GeometryTolerances::DEFAULT);
 
 		let first = alignment
 			.segment_control_point(0)
@@ -284,4 +575,121 @@ mod tests {
 			.expect("second straight control point should resolve");
 		assert!(first.x < second.x);
 	}
+
+	#[test]
+	fn cant_runoff_length_at_standard_gradients() {
+		// Common maximum cant gradients are 2.5 mm/m and 1 mm/m
+		assert!((cant_runoff_length(150.0, 2.5) - 60.0).abs() < 1e-4);
+		assert!((cant_runoff_length(100.0, 1.0) - 100.0).abs() < 1e-4);
+		assert!((cant_runoff_length(0.0, 2.5)).abs() < 1e-6);
+	}
+
+	#[test]
+	fn applied_cant_is_limited_to_max_cant() {
+		// 80 km/h on 1000 m: 11.8 * 6400 / 1000 = 75.52 mm
+		assert!((applied_cant_mm(1000.0, 80.0, 150.0) - 75.52).abs() < 1e-3);
+		assert!((applied_cant_mm(200.0, 120.0, 150.0) - 150.0).abs() < 1e-6);
+	}
+
+	#[test]
+	fn min_radius_for_speed_matches_standard_relation() {
+		// 100 km/h with 150 mm cant: e = 0.1, f = 0.1
+		let radius = min_radius_for_speed(100.0, 150.0);
+		assert!((radius - 10_000.0 / (127.0 * 0.2)).abs() < 1e-2);
+	}
+
+	#[test]
+	fn check_min_radius_flags_only_tight_turns() {
+		let mut tight = TurnSegment::new(Vec3::new(100.0, 0.0, 50.0));
+		tight.circular_section_radius = 200.0;
+		let mut gentle = TurnSegment::new(Vec3::new(200.0, 0.0, 0.0));
+		gentle.circular_section_radius = 500.0;
+		let alignment = Alignment {
+			start: Vec3::ZERO,
+			end: Vec3::new(300.0, 0.0, 50.0),
+			segments: vec![
+				PathSegment::Straight(StraightSegment::from_fraction(0.2)),
+				PathSegment::Turn(tight),
+				PathSegment::Turn(gentle),
+			],
+			..Default::default()
+		};
+
+		assert_eq!(check_min_radius(&alignment, 100.0, 150.0), vec![1]);
+	}
+
+	#[test]
+	fn coincident_tangent_vertices_are_pushed_apart() {
+		let shared = Vec3::new(100.0, 0.0, 50.0);
+		let mut alignment = Alignment {
+			start: Vec3::ZERO,
+			end: Vec3::new(300.0, 0.0, 0.0),
+			segments: vec![
+				PathSegment::Turn(TurnSegment::new(shared)),
+				PathSegment::Turn(TurnSegment::new(shared)),
+			],
+			..Default::default()
+		};
+		enforce_alignment_constraints(&mut alignment, &GeometryTolerances::DEFAULT);
+
+		let points = alignment.control_points();
+		for leg in points.windows(2) {
+			assert!(
+				plan_offset(leg[0], leg[1]).length() >= MIN_VERTEX_SPACING * 0.999,
+				"legs {leg:?} are too short"
+			);
+		}
+		// The second vertex moved on along the direction it was approached from
+		let moved = points[2] - points[1];
+		assert!(moved.normalize().dot(shared.normalize()) > 0.999);
+
+		let geometry =
+			crate::geometry::calculate_alignment_geometry(alignment.start, alignment.end, &alignment);
+		assert!(geometry.total_length().is_finite());
+		for (_, xz) in geometry.sample_by_station(1.0) {
+			assert!(xz.is_finite());
+		}
+	}
+
+	#[test]
+	fn transition_length_spec_sets_the_clothoid_length() {
+		let mut turn = TurnSegment::new(Vec3::new(1000.0, 0.0, 0.0));
+		turn.circular_section_radius = 100.0;
+		turn.transition = TransitionSpec::ByLength(20.0);
+		let mut alignment = Alignment {
+			start: Vec3::ZERO,
+			end: Vec3::new(1000.0, 0.0, 1000.0),
+			segments: vec![PathSegment::Turn(turn)],
+			..Default::default()
+		};
+		enforce_alignment_constraints(&mut alignment, &GeometryTolerances::DEFAULT);
+
+		let geometry =
+			crate::geometry::calculate_alignment_geometry(alignment.start, alignment.end, &alignment);
+		let Some(GeometrySegment::Turn(curve)) = geometry
+			.segments
+			.iter()
+			.find(|segment| matches!(segment, GeometrySegment::Turn(_)))
+		else {
+			panic!("expected a turn");
+		};
+		assert!((curve.ingoing_clothoid.length - 20.0).abs() < 1e-3);
+		// A quarter turn minus 2 * 20 m / 100 m of spiral deflection is left for the arc
+		let turn = alignment.segments[0].as_turn().unwrap();
+		assert!((turn.circular_section_angle - (FRAC_PI_2 - 0.2)).abs() < 1e-4);
+
+		let by_parameter = TurnSegment {
+			transition: TransitionSpec::ByParameter((100.0f32 * 20.0).sqrt()),
+			..*turn
+		};
+		assert!((by_parameter.arc_angle(FRAC_PI_2) - turn.circular_section_angle).abs() < 1e-4);
+	}
+
+	#[test]
+	fn clothoid_parameter_band_is_a_third_of_the_radius_to_the_radius() {
+		assert!(!clothoid_parameter_in_range(20.0, 300.0));
+		assert!(clothoid_parameter_in_range(100.0, 300.0));
+		assert!(clothoid_parameter_in_range(300.0, 300.0));
+		assert!(!clothoid_parameter_in_range(301.0, 300.0));
+	}
 }
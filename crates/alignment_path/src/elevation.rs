@@ -23,15 +23,92 @@ impl<H: HeightSampler> ElevationProfile for TerrainSampledProfile<'_, H> {
 	}
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Pvi {
 	pub station: f32,
 	pub elevation: f32,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct PviProfile {
 	pub points: Vec<Pvi>,
+	/// Length of the parabolic curve rounded over each interior PVI. Zero keeps sharp grade breaks.
+	/// Where the tangents either side are short, a curve is shortened to fit; see
+	/// [`Self::vertical_curve`].
+	#[serde(default, alias = "min_vertical_curve_length")]
+	pub vertical_curve_length: f32,
+}
+
+/// Symmetric parabola joining the incoming and outgoing grades at a PVI.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VerticalCurve {
+	/// Station where the curve leaves the incoming tangent (PVC).
+	pub start_station: f32,
+	pub start_elevation: f32,
+	pub length: f32,
+	pub incoming_grade: f32,
+	pub outgoing_grade: f32,
+}
+
+impl VerticalCurve {
+	pub fn end_station(&self) -> f32 {
+		self.start_station + self.length
+	}
+
+	pub fn contains(&self, station: f32) -> bool {
+		(self.start_station..=self.end_station()).contains(&station)
+	}
+
+	pub fn elevation_at(&self, station: f32) -> f32 {
+		let x = station - self.start_station;
+		let rate = (self.outgoing_grade - self.incoming_grade) / (2.0 * self.length);
+		rate.mul_add(x * x, self.incoming_grade.mul_add(x, self.start_elevation))
+	}
+}
+
+impl PviProfile {
+	/// The vertical curve over the PVI at `index`, if it is interior and a curve length is set.
+	/// Each half is capped at half of its adjacent tangent, so neighbouring curves never overlap.
+	pub fn vertical_curve(&self, index: usize) -> Option<VerticalCurve> {
+		if self.vertical_curve_length <= 0.0 || index == 0 {
+			return None;
+		}
+		let (prev, pvi, next) = (
+			self.points.get(index - 1)?,
+			&self.points[index],
+			self.points.get(index + 1)?,
+		);
+		let behind = pvi.station - prev.station;
+		let ahead = next.station - pvi.station;
+		if behind <= 0.0 || ahead <= 0.0 {
+			return None;
+		}
+		let half = (self.vertical_curve_length / 2.0)
+			.min(behind / 2.0)
+			.min(ahead / 2.0);
+		let incoming_grade = (pvi.elevation - prev.elevation) / behind;
+		let outgoing_grade = (next.elevation - pvi.elevation) / ahead;
+		Some(VerticalCurve {
+			start_station: pvi.station - half,
+			start_elevation: incoming_grade.mul_add(-half, pvi.elevation),
+			length: 2.0 * half,
+			incoming_grade,
+			outgoing_grade,
+		})
+	}
+
+	/// Mirrors the profile for travel in the opposite direction. Stations are reflected within
+	/// the span of the PVIs, so every grade keeps its size and flips its sign.
+	pub fn reverse(&mut self) {
+		let (Some(first), Some(last)) = (self.points.first(), self.points.last()) else {
+			return;
+		};
+		let span_sum = first.station + last.station;
+		self.points.reverse();
+		for point in &mut self.points {
+			point.station = span_sum - point.station;
+		}
+	}
 }
 
 impl ElevationProfile for PviProfile {
@@ -47,11 +124,18 @@ impl ElevationProfile for PviProfile {
 		if station >= last.station {
 			return last.elevation;
 		}
-		// Piecewise-linear interpolation between adjacent PVIs.
-		// Parabolic VCs will replace this in a future pass.
 		let i = points
 			.partition_point(|p| p.station <= station)
 			.saturating_sub(1);
+		// Curves stay within half of each tangent, so only the two PVIs bounding it can reach here.
+		if let Some(curve) = [i, i + 1]
+			.into_iter()
+			.filter_map(|index| self.vertical_curve(index))
+			.find(|curve| curve.contains(station))
+		{
+			return curve.elevation_at(station);
+		}
+		// Piecewise-linear interpolation along the tangent between adjacent PVIs.
 		let lo = &points[i];
 		let hi = &points[i + 1];
 		let span = hi.station - lo.station;
@@ -63,7 +147,7 @@ impl ElevationProfile for PviProfile {
 	}
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "kind", rename_all = "snake_case")]
 pub enum VerticalProfileData {
 	TerrainSampled,
@@ -79,10 +163,14 @@ impl Default for VerticalProfileData {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::tolerance::GeometryTolerances;
 
 	#[test]
 	fn pvi_profile_empty_returns_zero() {
-		let profile = PviProfile { points: vec![] };
+		let profile = PviProfile {
+			points: vec![],
+			..Default::default()
+		};
 		assert_eq!(profile.elevation_at(100.0), 0.0);
 	}
 
@@ -93,6 +181,7 @@ mod tests {
 				station: 50.0,
 				elevation: 10.0,
 			}],
+			..Default::default()
 		};
 		assert_eq!(profile.elevation_at(0.0), 10.0);
 		assert_eq!(profile.elevation_at(50.0), 10.0);
@@ -112,6 +201,7 @@ mod tests {
 					elevation: 10.0,
 				},
 			],
+			..Default::default()
 		};
 		assert!((profile.elevation_at(50.0) - 5.0).abs() < 1e-4);
 		assert!((profile.elevation_at(75.0) - 7.5).abs() < 1e-4);
@@ -130,11 +220,47 @@ mod tests {
 					elevation: 15.0,
 				},
 			],
+			..Default::default()
 		};
 		assert_eq!(profile.elevation_at(0.0), 5.0);
 		assert_eq!(profile.elevation_at(100.0), 15.0);
 	}
 
+	#[test]
+	fn vertical_curve_is_a_parabola_tangent_to_both_grades() {
+		// +2 % into -2 % with a 100 m curve: the crest sits 0.5 m below the PVI.
+		let profile = PviProfile {
+			points: vec![
+				Pvi {
+					station: 0.0,
+					elevation: 0.0,
+				},
+				Pvi {
+					station: 200.0,
+					elevation: 4.0,
+				},
+				Pvi {
+					station: 400.0,
+					elevation: 0.0,
+				},
+			],
+			vertical_curve_length: 100.0,
+		};
+		let curve = profile.vertical_curve(1).unwrap();
+		assert_eq!(curve.start_station, 150.0);
+		assert_eq!(curve.end_station(), 250.0);
+		assert!((profile.elevation_at(150.0) - 3.0).abs() < 1e-4);
+		assert!((profile.elevation_at(200.0) - 3.5).abs() < 1e-4);
+		assert!((profile.elevation_at(250.0) - 3.0).abs() < 1e-4);
+		// Outside the curve the tangents are untouched.
+		assert!((profile.elevation_at(100.0) - 2.0).abs() < 1e-4);
+		assert!((profile.elevation_at(300.0) - 2.0).abs() < 1e-4);
+		// The slope matches each grade where the curve meets its tangent.
+		let slope = |s: f32| (profile.elevation_at(s + 0.01) - profile.elevation_at(s - 0.01)) / 0.02;
+		assert!((slope(150.0) - 0.02).abs() < 1e-2);
+		assert!((slope(250.0) + 0.02).abs() < 1e-2);
+	}
+
 	#[test]
 	fn vertical_profile_data_default_is_terrain_sampled() {
 		let data = VerticalProfileData::default();
@@ -154,6 +280,7 @@ mod tests {
 					elevation: 20.0,
 				},
 			],
+			..Default::default()
 		});
 		let json = serde_json::to_string(&original).unwrap();
 		let restored: VerticalProfileData = serde_json::from_str(&json).unwrap();
@@ -177,13 +304,6 @@ mod tests {
 		));
 	}
 
-	struct ConstantSampler(f32);
-	impl HeightSampler for ConstantSampler {
-		fn height_at(&self, _position: glam::Vec3) -> f32 {
-			self.0
-		}
-	}
-
 	#[test]
 	fn terrain_sampled_profile_returns_sampler_height() {
 		use crate::geometry::{GeometrySegment, StraightGeometry};
@@ -197,8 +317,9 @@ mod tests {
 		};
 		let geometry = AlignmentGeometry {
 			segments: vec![GeometrySegment::Straight(straight)],
+			tolerances: GeometryTolerances::DEFAULT,
 		};
-		let sampler = ConstantSampler(42.0);
+		let sampler = crate::geometry::FlatHeightSampler(42.0);
 		let profile = TerrainSampledProfile {
 			sampler: &sampler,
 			horizontal: &geometry,
@@ -210,8 +331,11 @@ mod tests {
 
 	#[test]
 	fn terrain_sampled_profile_out_of_range_returns_zero() {
-		let geometry = AlignmentGeometry { segments: vec![] };
-		let sampler = ConstantSampler(5.0);
+		let geometry = AlignmentGeometry {
+			segments: vec![],
+			tolerances: GeometryTolerances::DEFAULT,
+		};
+		let sampler = crate::geometry::FlatHeightSampler(5.0);
 		let profile = TerrainSampledProfile {
 			sampler: &sampler,
 			horizontal: &geometry,
@@ -1,14 +1,39 @@
+use std::collections::HashMap;
 use std::f64::consts::PI;
 
 use glam::{Quat, Vec2, Vec3};
+use serde::{Deserialize, Serialize};
 use spec_math::Fresnel;
 
+use crate::constraints::applied_cant_mm;
+use crate::elevation::ElevationProfile;
 use crate::path::{Alignment, TurnSegment};
+use crate::tolerance::GeometryTolerances;
+
+const TANGENT_FINITE_DIFFERENCE_STEP: f32 = 0.05;
+const OFFSET_SAMPLE_SPACING: f32 = 2.0;
+const NEAREST_STATION_SPACING: f32 = 0.5;
+const BOUNDS_SAMPLE_SPACING: f32 = 1.0;
+const REPORT_CSV_HEADER: &str =
+	"station,x,y,z,azimuth_deg,curvature,radius,grade_percent,cant_mm,element";
+// Fraction of the local radius an inward offset must leave intact so the offset line never
+// folds back on itself.
+const MIN_OFFSET_RADIUS_FRACTION: f32 = 0.05;
 
 pub trait HeightSampler {
 	fn height_at(&self, position: Vec3) -> f32;
 }
 
+/// The same height everywhere, for using the geometry without any terrain.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlatHeightSampler(pub f32);
+
+impl HeightSampler for FlatHeightSampler {
+	fn height_at(&self, _position: Vec3) -> f32 {
+		self.0
+	}
+}
+
 // Compute azimuth of the tangent from previous point to current point
 pub fn azimuth_of_tangent(current: Vec3, previous: Vec3) -> f32 {
 	let delta_x = current.x - previous.x;
@@ -95,6 +120,10 @@ pub fn circular_arc_start(
 	fresnel_scale: f64,
 	fresnel_scale_sign: f64,
 ) -> Vec3 {
+	// A zero-length clothoid (the arc takes the whole deflection) ends where it starts
+	if fresnel_scale <= 0.0 {
+		return t_i;
+	}
 	let fresnel_arg = l_c_abs / fresnel_scale;
 	let fresnel = fresnel_arg.fresnel();
 	let i_x = (fresnel_scale
@@ -119,6 +148,9 @@ pub fn clothoid_point(
 	fresnel_scale: f64,
 	fresnel_scale_sign: f64,
 ) -> Vec3 {
+	if fresnel_scale <= 0.0 {
+		return clothoid_endpoint;
+	}
 	let tilde_s = s * l_c_abs;
 	let fresnel_arg = tilde_s / fresnel_scale;
 	let fresnel = fresnel_arg.fresnel();
@@ -132,9 +164,12 @@ pub fn clothoid_point(
 	clothoid_endpoint + Vec3::new(i_x, 0.0, i_z)
 }
 
-#[derive(Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct AlignmentGeometry {
 	pub segments: Vec<GeometrySegment>,
+	/// Tolerances the geometry was built with, reused when evaluating it.
+	#[serde(skip)]
+	pub tolerances: GeometryTolerances,
 }
 
 impl AlignmentGeometry {
@@ -142,15 +177,378 @@ impl AlignmentGeometry {
 		self.segments.iter().map(GeometrySegment::length).sum()
 	}
 
+	fn turns(&self) -> impl Iterator<Item = &CurveSegment> {
+		self.segments.iter().filter_map(|segment| match segment {
+			GeometrySegment::Turn(turn) => Some(turn),
+			GeometrySegment::Straight(_) => None,
+		})
+	}
+
+	pub fn turn_count(&self) -> usize {
+		self.turns().count()
+	}
+
+	/// Tightest circular arc radius, or `None` for an all-straight alignment.
+	pub fn min_radius(&self) -> Option<f32> {
+		self
+			.turns()
+			.map(|turn| turn.circular_arc.radius())
+			.reduce(f32::min)
+	}
+
 	pub fn xz_at_station(&self, station: f32) -> Option<Vec2> {
 		self
 			.segments
 			.iter()
-			.find_map(|segment| segment.xz_at_station(station))
+			.find_map(|segment| segment.xz_at_station(station, &self.tolerances))
+	}
+
+	/// Min and max corners of the centerline in plan, from samples a meter apart. Y is 0 on both,
+	/// like [`Self::offset`]; an empty geometry gives two zero corners.
+	pub fn bounds(&self) -> (Vec3, Vec3) {
+		let samples = self.sample_by_station(BOUNDS_SAMPLE_SPACING);
+		let Some(first) = samples.first().map(|(_, xz)| *xz) else {
+			return (Vec3::ZERO, Vec3::ZERO);
+		};
+		let (min, max) = samples.iter().fold((first, first), |(min, max), (_, xz)| {
+			(min.min(*xz), max.max(*xz))
+		});
+		(Vec3::new(min.x, 0.0, min.y), Vec3::new(max.x, 0.0, max.y))
+	}
+
+	/// Samples the centerline at uniform stations from 0 to the total length, inclusive.
+	pub fn sample_by_station(&self, spacing: f32) -> Vec<(f32, Vec2)> {
+		let total = self.total_length();
+		if total <= 0.0 || !spacing.is_finite() || spacing <= 0.0 {
+			return Vec::new();
+		}
+		let count = (total / spacing).ceil() as usize;
+		(0..=count)
+			.filter_map(|i| {
+				let station = (i as f32 * spacing).min(total);
+				self.clamped_xz_at_station(station).map(|xz| (station, xz))
+			})
+			.collect()
+	}
+
+	/// Station of the centerline point closest to `xz`, and its distance from `xz`.
+	pub fn nearest_station(&self, xz: Vec2) -> Option<(f32, f32)> {
+		self
+			.sample_by_station(NEAREST_STATION_SPACING)
+			.into_iter()
+			.map(|(station, sample)| (station, sample.distance(xz)))
+			.min_by(|a, b| a.1.total_cmp(&b.1))
+	}
+
+	/// First place the ray `origin + t * direction` (t >= 0) passes within `tolerance` meters of
+	/// the centerline draped on `heights`, as `(station, point on the centerline)`. Lets the path
+	/// be picked without a mesh collider.
+	pub fn raycast(
+		&self,
+		origin: Vec3,
+		direction: Vec3,
+		heights: &dyn HeightSampler,
+		tolerance: f32,
+	) -> Option<(f32, Vec3)> {
+		let direction = direction.try_normalize()?;
+		let samples: Vec<(f32, Vec3)> = self
+			.sample_by_station(NEAREST_STATION_SPACING)
+			.into_iter()
+			.map(|(station, xz)| {
+				let point = Vec3::new(xz.x, 0.0, xz.y);
+				(station, point.with_y(heights.height_at(point)))
+			})
+			.collect();
+		// (distance along the ray, distance off it, station, point)
+		let mut closest: Option<(f32, f32, f32, Vec3)> = None;
+		for pair in samples.windows(2) {
+			let ((s0, p0), (s1, p1)) = (pair[0], pair[1]);
+			let u = chord_parameter_nearest_line(origin, direction, p0, p1, &self.tolerances);
+			let point = p0.lerp(p1, u);
+			let along_ray = (point - origin).dot(direction);
+			let off_ray = (origin + direction * along_ray).distance(point);
+			if along_ray < 0.0 || off_ray > tolerance {
+				continue;
+			}
+			// Chords within a tolerance of the same depth are one hit; keep whichever the ray passes
+			// nearest rather than the first one sampled
+			let better = closest.is_none_or(|(nearest_along, nearest_off, _, _)| {
+				if (along_ray - nearest_along).abs() <= tolerance {
+					off_ray < nearest_off
+				} else {
+					along_ray < nearest_along
+				}
+			});
+			if better {
+				closest = Some((along_ray, off_ray, s0 + (s1 - s0) * u, point));
+			}
+		}
+		closest.map(|(_, _, station, point)| (station, point))
+	}
+
+	/// Signed curvature (1/m) at a station, positive when the path curves to the right of travel.
+	pub fn curvature_at_station(&self, station: f32) -> f32 {
+		self
+			.segments
+			.iter()
+			.find(|segment| segment.contains_station(station))
+			.map_or(0.0, |segment| {
+				segment.curvature_at_station(station, &self.tolerances)
+			})
+	}
+
+	/// `(station, curvature)` every `spacing` meters, end included: zero on tangents, linear
+	/// through the clothoids and `1/radius` on the arcs. A step instead of a ramp shows a transition
+	/// too short for its radius.
+	pub fn curvature_profile(&self, spacing: f32) -> Vec<(f32, f32)> {
+		self
+			.sample_by_station(spacing)
+			.into_iter()
+			.map(|(station, _)| (station, self.curvature_at_station(station)))
+			.collect()
+	}
+
+	/// Places where the centerline crosses itself, as `(earlier segment, later segment, point)`
+	/// with the point on the XZ plane, ordered by segment pair. Each segment is sampled as a
+	/// polyline; adjacent segments meeting at their shared end don't count.
+	pub fn find_self_intersections(&self) -> Vec<(usize, usize, Vec3)> {
+		let tolerances = &self.tolerances;
+		let chords: Vec<(usize, Vec2, Vec2)> = self
+			.segments
+			.iter()
+			.enumerate()
+			.flat_map(|(index, segment)| {
+				segment_polyline(segment, INTERSECTION_SAMPLE_SPACING, tolerances)
+					.windows(2)
+					.map(|pair| (index, pair[0], pair[1]))
+					.collect::<Vec<_>>()
+			})
+			.collect();
+		// Broad phase: only chords sharing a grid cell can cross. Chords go in in segment order,
+		// so within a cell the earlier chord always belongs to the earlier segment.
+		let mut grid: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
+		for (chord, (_, a, b)) in chords.iter().enumerate() {
+			let (min, max) = (intersection_cell(a.min(*b)), intersection_cell(a.max(*b)));
+			for x in min.0..=max.0 {
+				for z in min.1..=max.1 {
+					grid.entry((x, z)).or_default().push(chord);
+				}
+			}
+		}
+		let mut crossings: Vec<(usize, usize, Vec3)> = Vec::new();
+		for cell in grid.values() {
+			for (n, &first) in cell.iter().enumerate() {
+				for &second in &cell[n + 1..] {
+					let ((i, a0, a1), (j, b0, b1)) = (chords[first], chords[second]);
+					if i == j {
+						continue;
+					}
+					let Some(point) = chord_intersection(a0, a1, b0, b1, tolerances) else {
+						continue;
+					};
+					let shared_end = (j == i + 1).then(|| self.segments[i].end_xz());
+					if shared_end.is_some_and(|end| end.distance(point) < INTERSECTION_SAMPLE_SPACING) {
+						continue;
+					}
+					let point = Vec3::new(point.x, 0.0, point.y);
+					// A crossing exactly on a sample vertex, or on a cell border, is hit more than once
+					if !crossings
+						.iter()
+						.any(|&(ci, cj, p)| (ci, cj) == (i, j) && tolerances.coincident(p, point))
+					{
+						crossings.push((i, j, point));
+					}
+				}
+			}
+		}
+		crossings.sort_by_key(|&(i, j, _)| (i, j));
+		crossings
+	}
+
+	/// Centerline displaced sideways by `lateral_m`, positive to the right of travel direction.
+	/// Points lie in the XZ plane; callers lift them with their own elevation source.
+	pub fn offset(&self, lateral_m: f32) -> Vec<Vec3> {
+		self
+			.offset_samples(lateral_m)
+			.into_iter()
+			.map(|(_, xz)| Vec3::new(xz.x, 0.0, xz.y))
+			.collect()
+	}
+
+	/// Same as [`Self::offset`], keeping the centerline station each offset point belongs to.
+	pub fn offset_samples(&self, lateral_m: f32) -> Vec<(f32, Vec2)> {
+		let mut clamped = false;
+		let samples = self
+			.sample_by_station(OFFSET_SAMPLE_SPACING)
+			.into_iter()
+			.filter_map(|(station, xz)| {
+				let tangent = self.tangent_xz_at_station(station)?;
+				let right = Vec2::new(-tangent.y, tangent.x);
+				let curvature = self.curvature_at_station(station);
+				let mut lateral = lateral_m;
+				if curvature != 0.0 && lateral.signum() == curvature.signum() {
+					let max_lateral = (1.0 - MIN_OFFSET_RADIUS_FRACTION) / curvature.abs();
+					if lateral.abs() > max_lateral {
+						lateral = max_lateral.copysign(lateral);
+						clamped = true;
+					}
+				}
+				Some((station, xz + right * lateral))
+			})
+			.collect();
+		if clamped {
+			log::warn!(
+				"offset of {lateral_m} m exceeds the radius of at least one curve; clamped to avoid a cusp"
+			);
+		}
+		samples
+	}
+
+	/// Closed corridor outline in XZ: the right offset in travel order, then the left offset back
+	/// to the start, joined straight across at both ends.
+	pub fn footprint(&self, half_width: f32) -> Vec<Vec2> {
+		let half_width = half_width.abs();
+		let right = self
+			.offset_samples(half_width)
+			.into_iter()
+			.map(|(_, xz)| xz);
+		let left = self
+			.offset_samples(-half_width)
+			.into_iter()
+			.map(|(_, xz)| xz);
+		right.chain(left.rev()).collect()
+	}
+
+	/// Plan area of [`Self::footprint`] in square meters.
+	pub fn footprint_area(&self, half_width: f32) -> f32 {
+		polygon_area(&self.footprint(half_width))
+	}
+
+	/// Staking table with one row per `step` meters of station. Cant is the equilibrium cant for
+	/// `design_speed_kmh` at the local curvature, capped at `max_cant_mm`.
+	pub fn full_report_csv(
+		&self,
+		heights: &dyn ElevationProfile,
+		step: f32,
+		design_speed_kmh: f32,
+		max_cant_mm: f32,
+	) -> String {
+		let total = self.total_length();
+		let mut csv = String::from(REPORT_CSV_HEADER);
+		csv.push('\n');
+		for (station, xz) in self.sample_by_station(step) {
+			let y = heights.elevation_at(station);
+			let azimuth = self
+				.heading_at_station(station)
+				.map_or(0.0, f32::to_degrees);
+			let curvature = self.curvature_at_station(station);
+			let radius = if curvature == 0.0 {
+				String::new()
+			} else {
+				format!("{:.3}", 1.0 / curvature.abs())
+			};
+			let behind = (station - TANGENT_FINITE_DIFFERENCE_STEP).max(0.0);
+			let ahead = (station + TANGENT_FINITE_DIFFERENCE_STEP).min(total);
+			let grade = if ahead > behind {
+				(heights.elevation_at(ahead) - heights.elevation_at(behind)) / (ahead - behind)
+			} else {
+				0.0
+			};
+			let cant = if curvature == 0.0 {
+				0.0
+			} else {
+				applied_cant_mm(1.0 / curvature.abs(), design_speed_kmh, max_cant_mm)
+			};
+			let element = self
+				.element_at_station(station)
+				.map_or("", ElementKind::as_str);
+			csv.push_str(&format!(
+				"{station:.3},{:.3},{y:.3},{:.3},{azimuth:.4},{curvature:.6},{radius},{:.3},{cant:.1},{element}\n",
+				xz.x,
+				xz.y,
+				grade * 100.0,
+			));
+		}
+		csv
+	}
+
+	pub fn element_at_station(&self, station: f32) -> Option<ElementKind> {
+		self
+			.segments
+			.iter()
+			.find(|segment| segment.contains_station(station))
+			.map(|segment| match segment {
+				GeometrySegment::Straight(_) => ElementKind::Straight,
+				GeometrySegment::Turn(turn) => turn.element_at_station(station),
+			})
+	}
+
+	/// Unit direction of travel at a station, in the XZ plane, for orienting things along the
+	/// track. `None` only for an empty alignment.
+	pub fn tangent_at_station(&self, station: f32) -> Option<Vec3> {
+		let tangent = self.tangent_xz_at_station(station)?;
+		Some(Vec3::new(tangent.x, 0.0, tangent.y))
+	}
+
+	/// Heading in radians at a station, measured like [`azimuth_of_tangent`].
+	pub fn heading_at_station(&self, station: f32) -> Option<f32> {
+		let tangent = self.tangent_xz_at_station(station)?;
+		Some(-tangent.y.atan2(tangent.x))
+	}
+
+	// Taken from the element itself: a finite difference loses too much to f32 rounding at the
+	// coordinates of a real site.
+	fn tangent_xz_at_station(&self, station: f32) -> Option<Vec2> {
+		let station = station.clamp(0.0, self.total_length());
+		self
+			.segments
+			.iter()
+			.find(|segment| segment.contains_station(station))
+			.or_else(|| {
+				self
+					.segments
+					.iter()
+					.rev()
+					.find(|segment| segment.start_station() <= station)
+			})
+			.and_then(|segment| segment.tangent_xz_at_station(station))
+	}
+
+	// Station lookups at the very end can miss by an ulp because stations are accumulated
+	// per segment, so fall back to the end of the last segment starting before the station.
+	pub(crate) fn clamped_xz_at_station(&self, station: f32) -> Option<Vec2> {
+		let station = station.clamp(0.0, self.total_length());
+		self.xz_at_station(station).or_else(|| {
+			self
+				.segments
+				.iter()
+				.rev()
+				.find(|segment| segment.start_station() <= station)
+				.map(GeometrySegment::end_xz)
+		})
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElementKind {
+	Straight,
+	IngoingClothoid,
+	CircularArc,
+	OutgoingClothoid,
+}
+
+impl ElementKind {
+	pub const fn as_str(self) -> &'static str {
+		match self {
+			Self::Straight => "straight",
+			Self::IngoingClothoid => "clothoid_in",
+			Self::CircularArc => "arc",
+			Self::OutgoingClothoid => "clothoid_out",
+		}
 	}
 }
 
-#[derive(Clone, Copy)]
+#[derive(Serialize, Deserialize, Clone, Copy)]
 pub enum GeometrySegment {
 	Straight(StraightGeometry),
 	Turn(CurveSegment),
@@ -171,15 +569,43 @@ impl GeometrySegment {
 		}
 	}
 
-	pub fn xz_at_station(&self, station: f32) -> Option<Vec2> {
+	pub fn xz_at_station(&self, station: f32, tolerances: &GeometryTolerances) -> Option<Vec2> {
 		match self {
 			Self::Straight(s) => s.xz_at_station(station),
-			Self::Turn(t) => t.xz_at_station(station),
+			Self::Turn(t) => t.xz_at_station(station, tolerances),
+		}
+	}
+
+	pub fn contains_station(&self, station: f32) -> bool {
+		let start = self.start_station();
+		(start..=start + self.length()).contains(&station)
+	}
+
+	pub fn end_xz(&self) -> Vec2 {
+		let end = match self {
+			Self::Straight(s) => s.end,
+			Self::Turn(t) => t.outgoing_clothoid_end,
+		};
+		Vec2::new(end.x, end.z)
+	}
+
+	pub fn curvature_at_station(&self, station: f32, tolerances: &GeometryTolerances) -> f32 {
+		match self {
+			Self::Straight(_) => 0.0,
+			Self::Turn(t) => t.curvature_at_station(station, tolerances),
+		}
+	}
+
+	/// Unit direction of travel in XZ at a station on this segment.
+	pub fn tangent_xz_at_station(&self, station: f32) -> Option<Vec2> {
+		match self {
+			Self::Straight(s) => Vec2::new(s.end.x - s.start.x, s.end.z - s.start.z).try_normalize(),
+			Self::Turn(t) => t.tangent_xz_at_station(station),
 		}
 	}
 }
 
-#[derive(Clone, Copy)]
+#[derive(Serialize, Deserialize, Clone, Copy)]
 pub struct StraightGeometry {
 	pub start: Vec3,
 	pub end: Vec3,
@@ -205,7 +631,7 @@ impl StraightGeometry {
 	}
 }
 
-#[derive(Clone, Copy)]
+#[derive(Serialize, Deserialize, Clone, Copy)]
 pub struct CurveSegment {
 	pub tangent_vertex_prev: Vec3,
 	pub tangent_vertex: Vec3,
@@ -225,16 +651,103 @@ impl CurveSegment {
 		self.ingoing_clothoid.length + self.circular_arc.length + self.outgoing_clothoid.length
 	}
 
-	pub fn xz_at_station(&self, station: f32) -> Option<Vec2> {
+	/// Clothoid parameter A of the (symmetric) transitions, from A² = R·L.
+	pub fn clothoid_parameter(&self) -> f64 {
+		(f64::from(self.circular_arc.radius()) * f64::from(self.ingoing_clothoid.length)).sqrt()
+	}
+
+	pub fn xz_at_station(&self, station: f32, tolerances: &GeometryTolerances) -> Option<Vec2> {
 		self
 			.ingoing_clothoid
-			.xz_at_station(station)
+			.xz_at_station(station, tolerances)
 			.or_else(|| self.circular_arc.xz_at_station(station))
-			.or_else(|| self.outgoing_clothoid.xz_at_station(station))
+			.or_else(|| self.outgoing_clothoid.xz_at_station(station, tolerances))
+	}
+
+	pub fn element_at_station(&self, station: f32) -> ElementKind {
+		let arc_start = self.circular_arc.start_station;
+		if station < arc_start {
+			ElementKind::IngoingClothoid
+		} else if station > arc_start + self.circular_arc.length {
+			ElementKind::OutgoingClothoid
+		} else {
+			ElementKind::CircularArc
+		}
+	}
+
+	pub fn turns_right(&self) -> bool {
+		let incoming = self.tangent_vertex - self.tangent_vertex_prev;
+		let outgoing = self.tangent_vertex_next - self.tangent_vertex;
+		incoming.x.mul_add(outgoing.z, -(incoming.z * outgoing.x)) >= 0.0
+	}
+
+	// Each clothoid turns through l²/2RL over the distance l from its tangent; the arc's direction
+	// is square to its radius.
+	pub fn tangent_xz_at_station(&self, station: f32) -> Option<Vec2> {
+		let sense = self.circular_arc.arc_sweep.signum();
+		let radius = self.circular_arc.radius();
+		let turned = |from: Vec3, to: Vec3, angle: f32| {
+			let direction = Quat::from_axis_angle(Vec3::Y, angle) * (to - from).with_y(0.0);
+			Vec2::new(direction.x, direction.z).try_normalize()
+		};
+		let arc_start = self.circular_arc.start_station;
+		let arc_end = arc_start + self.circular_arc.length;
+		if station < arc_start {
+			let along = station - self.start_station;
+			let spiral = along * along / (2.0 * radius * self.ingoing_clothoid.length);
+			turned(
+				self.tangent_vertex_prev,
+				self.tangent_vertex,
+				sense * spiral,
+			)
+		} else if station > arc_end {
+			let remaining = self.start_station + self.length() - station;
+			let spiral = remaining * remaining / (2.0 * radius * self.outgoing_clothoid.length);
+			turned(
+				self.tangent_vertex,
+				self.tangent_vertex_next,
+				-sense * spiral,
+			)
+		} else {
+			let center = Vec2::new(self.circular_arc.center.x, self.circular_arc.center.z);
+			let from_center = self.circular_arc.xz_at_station(station)? - center;
+			(Vec2::new(from_center.y, -from_center.x) * sense).try_normalize()
+		}
+	}
+
+	// Curvature ramps linearly from zero through each clothoid and is 1/R on the arc.
+	pub fn curvature_at_station(&self, station: f32, tolerances: &GeometryTolerances) -> f32 {
+		let radius = self.circular_arc.radius();
+		if tolerances.is_negligible(radius) {
+			return 0.0;
+		}
+		let transition_length = self.ingoing_clothoid.length;
+		let ramp = |distance_from_tangent: f32| {
+			if transition_length > 0.0 {
+				(distance_from_tangent / transition_length).clamp(0.0, 1.0)
+			} else {
+				1.0
+			}
+		};
+		let arc_start = self.circular_arc.start_station;
+		let arc_end = arc_start + self.circular_arc.length;
+		let fraction = if station < arc_start {
+			ramp(station - self.start_station)
+		} else if station > arc_end {
+			ramp(self.start_station + self.length() - station)
+		} else {
+			1.0
+		};
+		let magnitude = fraction / radius;
+		if self.turns_right() {
+			magnitude
+		} else {
+			-magnitude
+		}
 	}
 }
 
-#[derive(Clone, Copy)]
+#[derive(Serialize, Deserialize, Clone, Copy)]
 pub struct ClothoidParameters {
 	pub endpoint: Vec3,
 	pub circular_arc_length: f64,
@@ -269,14 +782,14 @@ impl ClothoidParameters {
 		self.station_at_s0 * (1.0 - s) + self.station_at_s1 * s
 	}
 
-	pub fn xz_at_station(&self, station: f32) -> Option<Vec2> {
+	pub fn xz_at_station(&self, station: f32, tolerances: &GeometryTolerances) -> Option<Vec2> {
 		let lo = self.station_at_s0.min(self.station_at_s1);
 		let hi = self.station_at_s0.max(self.station_at_s1);
 		if !(lo..=hi).contains(&station) {
 			return None;
 		}
 		let span = self.station_at_s1 - self.station_at_s0;
-		let s = if span.abs() < f32::EPSILON {
+		let s = if tolerances.is_negligible(span) {
 			0.0
 		} else {
 			(station - self.station_at_s0) / span
@@ -285,7 +798,7 @@ impl ClothoidParameters {
 	}
 }
 
-#[derive(Clone, Copy)]
+#[derive(Serialize, Deserialize, Clone, Copy)]
 pub struct CircularArcGeometry {
 	pub start_point: Vec3,
 	pub center: Vec3,
@@ -314,6 +827,42 @@ impl CircularArcGeometry {
 		let s = local_s_for_station(station, self.start_station, self.length)?;
 		Some(self.xz_at(s))
 	}
+
+	pub fn radius(&self) -> f32 {
+		self.start_vector.length()
+	}
+
+	pub fn chord_length(&self) -> f32 {
+		2.0 * self.radius() * (0.5 * self.arc_sweep.abs()).sin()
+	}
+
+	/// Perpendicular distance from the chord to the arc at `n` evenly spaced points along the
+	/// chord, endpoints included, as `(distance_along_chord, offset)` for staking out.
+	pub fn chord_offsets(&self, n: usize) -> Vec<(f32, f32)> {
+		let radius = self.radius();
+		let chord = self.chord_length();
+		let half_chord = 0.5 * chord;
+		// Distance from the center to the chord; the offset is measured beyond it
+		let apothem = radius * (0.5 * self.arc_sweep.abs()).cos();
+		let offset_at = |along: f32| {
+			let from_middle = along - half_chord;
+			((radius * radius - from_middle * from_middle)
+				.max(0.0)
+				.sqrt()
+				- apothem)
+				.max(0.0)
+		};
+		match n {
+			0 => Vec::new(),
+			1 => vec![(half_chord, offset_at(half_chord))],
+			_ => (0..n)
+				.map(|i| {
+					let along = chord * i as f32 / (n - 1) as f32;
+					(along, offset_at(along))
+				})
+				.collect(),
+		}
+	}
 }
 
 fn local_s_for_station(station: f32, start_station: f32, length: f32) -> Option<f32> {
@@ -326,10 +875,94 @@ fn local_s_for_station(station: f32, start_station: f32, length: f32) -> Option<
 	Some(((station - start_station) / length).clamp(0.0, 1.0))
 }
 
+/// Sample spacing, in meters, of the polylines checked for self-intersections.
+const INTERSECTION_SAMPLE_SPACING: f32 = 1.0;
+/// Side, in meters, of the grid cells that bucket chords before they are tested pairwise.
+const INTERSECTION_CELL_SIZE: f32 = 16.0;
+
+/// Grid cell of [`INTERSECTION_CELL_SIZE`] containing `point`.
+fn intersection_cell(point: Vec2) -> (i32, i32) {
+	let cell = (point / INTERSECTION_CELL_SIZE).floor();
+	(cell.x as i32, cell.y as i32)
+}
+
+/// A segment's centerline as a polyline with points at most `spacing` apart, ends included.
+fn segment_polyline(
+	segment: &GeometrySegment,
+	spacing: f32,
+	tolerances: &GeometryTolerances,
+) -> Vec<Vec2> {
+	let (start, length) = (segment.start_station(), segment.length());
+	let count = (length / spacing).ceil().max(1.0) as usize;
+	(0..=count)
+		.filter_map(|i| segment.xz_at_station(start + length * i as f32 / count as f32, tolerances))
+		.collect()
+}
+
+/// Where chords `a0-a1` and `b0-b1` cross, if they do. Parallel chords never cross.
+fn chord_intersection(
+	a0: Vec2,
+	a1: Vec2,
+	b0: Vec2,
+	b1: Vec2,
+	tolerances: &GeometryTolerances,
+) -> Option<Vec2> {
+	let (da, db) = (a1 - a0, b1 - b0);
+	let denominator = da.perp_dot(db);
+	let lengths = da.length() * db.length();
+	if tolerances.is_negligible(lengths) || tolerances.is_straight(denominator / lengths) {
+		return None;
+	}
+	let offset = b0 - a0;
+	let t = offset.perp_dot(db) / denominator;
+	let u = offset.perp_dot(da) / denominator;
+	((0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)).then(|| a0 + da * t)
+}
+
+/// Parameter in 0..=1 of the point on chord `p0-p1` closest to the line through `origin` along
+/// the unit vector `direction`. A chord parallel to the line gives its start.
+fn chord_parameter_nearest_line(
+	origin: Vec3,
+	direction: Vec3,
+	p0: Vec3,
+	p1: Vec3,
+	tolerances: &GeometryTolerances,
+) -> f32 {
+	let chord = p1 - p0;
+	let to_start = p0 - origin;
+	let along = direction.dot(chord);
+	let denominator = chord.length_squared() - along * along;
+	if tolerances.is_negligible(denominator) {
+		return 0.0;
+	}
+	((along * direction.dot(to_start) - chord.dot(to_start)) / denominator).clamp(0.0, 1.0)
+}
+
+/// Unsigned area of a simple closed polygon (shoelace formula).
+pub fn polygon_area(points: &[Vec2]) -> f32 {
+	let twice_area: f32 = points
+		.iter()
+		.zip(points.iter().cycle().skip(1))
+		.map(|(a, b)| a.perp_dot(*b))
+		.sum();
+	twice_area.abs() / 2.0
+}
+
 pub fn calculate_alignment_geometry(
 	start: Vec3,
 	end: Vec3,
 	alignment: &Alignment,
+) -> AlignmentGeometry {
+	calculate_alignment_geometry_with_tolerances(start, end, alignment, &GeometryTolerances::DEFAULT)
+}
+
+/// Like [`calculate_alignment_geometry`], dropping straights shorter than the coincidence distance
+/// and turns whose deflection is below the angle epsilon.
+pub fn calculate_alignment_geometry_with_tolerances(
+	start: Vec3,
+	end: Vec3,
+	alignment: &Alignment,
+	tolerances: &GeometryTolerances,
 ) -> AlignmentGeometry {
 	assert!(start.is_finite(), "start vertex must be finite: {start}");
 	assert!(end.is_finite(), "end vertex must be finite: {end}");
@@ -364,7 +997,9 @@ pub fn calculate_alignment_geometry(
 		let previous = control_points[i];
 		let tangent_vertex = control_points[i + 1];
 		let next = control_points[i + 2];
-		if let Some(turn_geometry) = compute_turn_geometry(previous, tangent_vertex, next, turn) {
+		if let Some(turn_geometry) =
+			compute_turn_geometry(previous, tangent_vertex, next, turn, tolerances)
+		{
 			turn_geometry_by_control_point[i + 1] = Some(turn_geometry);
 		}
 	}
@@ -382,7 +1017,7 @@ pub fn calculate_alignment_geometry(
 			.map(|turn| turn.ingoing_clothoid_start)
 			.unwrap_or(right_cp);
 
-		if straight_start.distance_squared(straight_end) > f32::EPSILON {
+		if !tolerances.coincident(straight_start, straight_end) {
 			let length = Vec2::new(
 				straight_end.x - straight_start.x,
 				straight_end.z - straight_start.z,
@@ -404,7 +1039,10 @@ pub fn calculate_alignment_geometry(
 		}
 	}
 
-	AlignmentGeometry { segments }
+	AlignmentGeometry {
+		segments,
+		tolerances: *tolerances,
+	}
 }
 
 fn assign_turn_stations(turn: &mut CurveSegment, start_station: f32) {
@@ -430,9 +1068,9 @@ fn compute_turn_geometry(
 	tangent_vertex_i: Vec3,
 	tangent_vertex_i_plus_1: Vec3,
 	turn: &TurnSegment,
+	tolerances: &GeometryTolerances,
 ) -> Option<CurveSegment> {
 	let circular_arc_radius_i = turn.circular_section_radius;
-	let circular_arc_angle_i = turn.circular_section_angle;
 
 	let unit_vector_i = unit_vector(tangent_vertex_i, tangent_vertex_i_minus_1);
 	let unit_vector_i_plus_1 = unit_vector(tangent_vertex_i_plus_1, tangent_vertex_i);
@@ -445,9 +1083,18 @@ fn compute_turn_geometry(
 
 	let difference_in_azimuth_i =
 		difference_in_azimuth(azimuth_of_tangent_i, azimuth_of_tangent_i_plus_1);
-	if difference_in_azimuth_i <= f32::EPSILON {
+	if tolerances.is_straight(difference_in_azimuth_i) {
 		return None;
 	}
+	// A full reversal has no interior angle to fit a curve into; the tangent length would divide
+	// by sin(0). Pass straight through the vertex instead.
+	if tolerances.is_straight(std::f32::consts::PI - difference_in_azimuth_i) {
+		log::debug!(
+			"turn at {tangent_vertex_i} reverses direction ({difference_in_azimuth_i} rad); drawing it as a sharp vertex"
+		);
+		return None;
+	}
+	let circular_arc_angle_i = turn.arc_angle(difference_in_azimuth_i);
 
 	let length_of_circular_section = circular_section_length(
 		circular_arc_radius_i,
@@ -462,6 +1109,13 @@ fn compute_turn_geometry(
 		length_of_circular_section,
 	);
 
+	if !total_tangent_length_i.is_finite() || !length_of_circular_section.is_finite() {
+		log::debug!(
+			"turn at {tangent_vertex_i} has a non-finite tangent length; drawing it as a sharp vertex"
+		);
+		return None;
+	}
+
 	let ingoing_clothoid_start_point = tangent_vertex_i - total_tangent_length_i * unit_vector_i;
 
 	let r_i_abs = f64::from(circular_arc_radius_i.abs());
@@ -539,6 +1193,19 @@ fn compute_turn_geometry(
 		station_at_s1: 0.0,
 	};
 
+	let points = [
+		circular_arc.start_point,
+		circular_arc.center,
+		circular_arc.end_point,
+		clothoid_transition_end,
+	];
+	if points.iter().any(|point| !point.is_finite()) {
+		log::debug!(
+			"turn at {tangent_vertex_i} produced non-finite geometry; drawing it as a sharp vertex"
+		);
+		return None;
+	}
+
 	Some(CurveSegment {
 		tangent_vertex_prev: tangent_vertex_i_minus_1,
 		tangent_vertex: tangent_vertex_i,
@@ -559,6 +1226,27 @@ mod tests {
 	use super::*;
 	use crate::path::{Alignment, TurnSegment};
 
+	#[test]
+	fn straights_shorter_than_coincidence_distance_are_dropped() {
+		// Half a millimetre: longer than machine epsilon allows, shorter than the default tolerance
+		let start = Vec3::new(1_500.0, 0.0, 1_500.0);
+		let end = start + Vec3::X * 5.0e-4;
+		let alignment = Alignment::new(start, end, 0);
+
+		assert!(
+			calculate_alignment_geometry(start, end, &alignment)
+				.segments
+				.is_empty()
+		);
+		let strict = GeometryTolerances {
+			coincidence_distance: 1.0e-5,
+			..GeometryTolerances::DEFAULT
+		};
+		let geometry = calculate_alignment_geometry_with_tolerances(start, end, &alignment, &strict);
+		assert_eq!(geometry.segments.len(), 1);
+		assert_eq!(geometry.tolerances, strict);
+	}
+
 	#[test]
 	fn straight_length_matches_xz_distance() {
 		let straight = StraightGeometry {
@@ -603,6 +1291,28 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn bounds_enclose_the_bulge_of_a_turn() {
+		let alignment = right_turn_alignment(40.0);
+		let geometry = calculate_alignment_geometry(alignment.start, alignment.end, &alignment);
+		let (min, max) = geometry.bounds();
+		// The turn cuts inside its vertex, so the box is set by the start and end points
+		assert!(min.distance(Vec3::ZERO) < 1e-3, "min {min}");
+		assert!(
+			max.distance(Vec3::new(100.0, 0.0, 100.0)) < 1e-3,
+			"max {max}"
+		);
+		for (_, xz) in geometry.sample_by_station(BOUNDS_SAMPLE_SPACING) {
+			assert!(xz.cmpge(Vec2::new(min.x, min.z)).all());
+			assert!(xz.cmple(Vec2::new(max.x, max.z)).all());
+		}
+		let empty = AlignmentGeometry {
+			segments: Vec::new(),
+			tolerances: GeometryTolerances::DEFAULT,
+		};
+		assert_eq!(empty.bounds(), (Vec3::ZERO, Vec3::ZERO));
+	}
+
 	#[test]
 	fn total_length_matches_segment_sum() {
 		let alignment = Alignment::new(Vec3::new(0.0, 0.0, 0.0), Vec3::new(200.0, 0.0, 200.0), 1);
@@ -611,6 +1321,82 @@ mod tests {
 		assert!((geometry.total_length() - sum).abs() < 1e-3);
 	}
 
+	#[test]
+	fn min_radius_picks_the_tightest_turn() {
+		let straight = Alignment::new(Vec3::ZERO, Vec3::new(100.0, 0.0, 0.0), 0);
+		let geometry = calculate_alignment_geometry(straight.start, straight.end, &straight);
+		assert_eq!(geometry.turn_count(), 0);
+		assert_eq!(geometry.min_radius(), None);
+
+		let alignment = right_turn_alignment(40.0);
+		let geometry = calculate_alignment_geometry(alignment.start, alignment.end, &alignment);
+		assert_eq!(geometry.turn_count(), 1);
+		let radius = geometry.min_radius().expect("one turn");
+		assert!((radius - 40.0).abs() < 1e-2, "radius {radius}");
+	}
+
+	fn assert_finite(geometry: &AlignmentGeometry) {
+		assert!(geometry.total_length().is_finite());
+		for (station, xz) in geometry.sample_by_station(5.0) {
+			assert!(xz.is_finite(), "non-finite point {xz} at station {station}");
+		}
+	}
+
+	#[test]
+	fn collinear_vertex_passes_straight_through() {
+		let alignment = Alignment {
+			start: Vec3::ZERO,
+			end: Vec3::new(200.0, 0.0, 0.0),
+			segments: vec![crate::path::PathSegment::Turn(TurnSegment::new(Vec3::new(
+				100.0, 0.0, 0.0,
+			)))],
+			..Default::default()
+		};
+		let geometry = calculate_alignment_geometry(alignment.start, alignment.end, &alignment);
+		assert_finite(&geometry);
+		assert!(
+			geometry
+				.segments
+				.iter()
+				.all(|segment| matches!(segment, GeometrySegment::Straight(_)))
+		);
+		assert!((geometry.total_length() - 200.0).abs() < 1e-3);
+	}
+
+	#[test]
+	fn near_reversal_stays_finite() {
+		for offset in [0.0, 1.0e-4, 1.0e-2] {
+			let alignment = Alignment {
+				start: Vec3::ZERO,
+				end: Vec3::new(0.0, 0.0, offset),
+				segments: vec![crate::path::PathSegment::Turn(TurnSegment::new(Vec3::new(
+					100.0, 0.0, 0.0,
+				)))],
+				..Default::default()
+			};
+			let geometry = calculate_alignment_geometry(alignment.start, alignment.end, &alignment);
+			assert_finite(&geometry);
+		}
+	}
+
+	#[test]
+	fn clothoid_parameter_squares_to_radius_times_length() {
+		let alignment = right_turn_alignment(40.0);
+		let geometry = calculate_alignment_geometry(alignment.start, alignment.end, &alignment);
+		let turn = geometry
+			.segments
+			.iter()
+			.find_map(|segment| match segment {
+				GeometrySegment::Turn(turn) => Some(turn),
+				GeometrySegment::Straight(_) => None,
+			})
+			.expect("one turn");
+		let a = turn.clothoid_parameter();
+		let rl = f64::from(turn.circular_arc.radius()) * f64::from(turn.ingoing_clothoid.length);
+		assert!(a > 0.0);
+		assert!((a * a - rl).abs() < 1e-6 * rl.max(1.0));
+	}
+
 	#[test]
 	fn clothoid_length_matches_computed_length() {
 		let alignment = Alignment {
@@ -620,6 +1406,7 @@ mod tests {
 				tangent_vertex: Vec3::new(150.0, 0.0, 50.0),
 				circular_section_radius: 100.0,
 				circular_section_angle: 0.3,
+				..Default::default()
 			})],
 			..Default::default()
 		};
@@ -633,4 +1420,341 @@ mod tests {
 			}
 		}
 	}
+
+	fn right_turn_alignment(radius: f32) -> Alignment {
+		Alignment {
+			start: Vec3::new(0.0, 0.0, 0.0),
+			end: Vec3::new(100.0, 0.0, 100.0),
+			segments: vec![crate::path::PathSegment::Turn(TurnSegment {
+				tangent_vertex: Vec3::new(100.0, 0.0, 0.0),
+				circular_section_radius: radius,
+				circular_section_angle: 0.5,
+				..Default::default()
+			})],
+			..Default::default()
+		}
+	}
+
+	fn v_turn(tangent_vertex_z: f32) -> CurveSegment {
+		let alignment = Alignment {
+			start: Vec3::ZERO,
+			end: Vec3::new(200.0, 0.0, 0.0),
+			segments: vec![crate::path::PathSegment::Turn(TurnSegment {
+				tangent_vertex: Vec3::new(100.0, 0.0, tangent_vertex_z),
+				circular_section_radius: 40.0,
+				circular_section_angle: 0.5,
+				..Default::default()
+			})],
+			..Default::default()
+		};
+		let geometry = calculate_alignment_geometry(alignment.start, alignment.end, &alignment);
+		geometry
+			.segments
+			.iter()
+			.find_map(|segment| match segment {
+				GeometrySegment::Turn(turn) => Some(*turn),
+				GeometrySegment::Straight(_) => None,
+			})
+			.expect("one turn")
+	}
+
+	#[test]
+	fn symmetric_turn_has_mirrored_clothoids() {
+		let left = v_turn(100.0);
+		let right = v_turn(-100.0);
+		assert_ne!(left.turns_right(), right.turns_right());
+		let close = |a: Vec2, b: Vec2| a.distance(b) < 1e-3;
+		// Reflect across the bisector x = 100, or across the x axis between the two turns
+		let across_bisector = |p: Vec2| Vec2::new(200.0 - p.x, p.y);
+		let across_axis = |p: Vec2| Vec2::new(p.x, -p.y);
+		for i in 0..=20 {
+			let s = i as f32 / 20.0;
+			for turn in [&left, &right] {
+				let ingoing = turn.ingoing_clothoid.xz_at(s);
+				let outgoing = turn.outgoing_clothoid.xz_at(s);
+				assert!(
+					close(across_bisector(ingoing), outgoing),
+					"s = {s}: {ingoing} does not mirror {outgoing}"
+				);
+			}
+			assert!(close(
+				across_axis(left.ingoing_clothoid.xz_at(s)),
+				right.ingoing_clothoid.xz_at(s)
+			));
+			assert!(close(
+				across_axis(left.circular_arc.xz_at(s)),
+				right.circular_arc.xz_at(s)
+			));
+		}
+		for turn in [&left, &right] {
+			let arc_end = Vec2::new(turn.circular_arc.end_point.x, turn.circular_arc.end_point.z);
+			assert!(close(turn.outgoing_clothoid.xz_at(1.0), arc_end));
+		}
+	}
+
+	#[test]
+	fn curvature_profile_ramps_through_clothoids_to_the_arc() {
+		let radius = 30.0;
+		let geometry = calculate_alignment_geometry(
+			Vec3::ZERO,
+			Vec3::new(100.0, 0.0, 100.0),
+			&right_turn_alignment(radius),
+		);
+		let profile = geometry.curvature_profile(0.25);
+		assert_eq!(profile.first().map(|(_, k)| *k), Some(0.0));
+		assert_eq!(profile.last().map(|(_, k)| *k), Some(0.0));
+		let peak = profile.iter().map(|(_, k)| k.abs()).fold(0.0, f32::max);
+		assert!((peak - 1.0 / radius).abs() < 1e-4, "peak curvature {peak}");
+
+		// No sample-to-sample jump bigger than a clothoid ramp step
+		let GeometrySegment::Turn(turn) = geometry.segments[1] else {
+			panic!("expected a turn");
+		};
+		let max_step = 0.25 / turn.ingoing_clothoid.length / radius;
+		for pair in profile.windows(2) {
+			assert!((pair[1].1 - pair[0].1).abs() <= max_step + 1e-5);
+		}
+	}
+
+	#[test]
+	fn geometry_round_trips_through_json() {
+		let geometry = calculate_alignment_geometry(
+			Vec3::ZERO,
+			Vec3::new(100.0, 0.0, 100.0),
+			&right_turn_alignment(30.0),
+		);
+		let json = serde_json::to_string(&geometry).unwrap();
+		let restored: AlignmentGeometry = serde_json::from_str(&json).unwrap();
+
+		assert_eq!(restored.segments.len(), geometry.segments.len());
+		assert_eq!(restored.total_length(), geometry.total_length());
+		let GeometrySegment::Turn(turn) = restored.segments[1] else {
+			panic!("expected a turn");
+		};
+		assert!(turn.ingoing_clothoid.fresnel_scale > 0.0);
+		let station = turn.circular_arc.start_station;
+		assert_eq!(
+			restored.xz_at_station(station),
+			geometry.xz_at_station(station)
+		);
+	}
+
+	fn straight_chain(points: &[Vec3]) -> AlignmentGeometry {
+		let mut station = 0.0;
+		let segments = points
+			.windows(2)
+			.map(|leg| {
+				let length = leg[0].distance(leg[1]);
+				let segment = GeometrySegment::Straight(StraightGeometry {
+					start: leg[0],
+					end: leg[1],
+					start_station: station,
+					length,
+				});
+				station += length;
+				segment
+			})
+			.collect();
+		AlignmentGeometry {
+			segments,
+			tolerances: GeometryTolerances::DEFAULT,
+		}
+	}
+
+	#[test]
+	fn self_intersection_is_found_where_the_path_crosses_back() {
+		let geometry = straight_chain(&[
+			Vec3::ZERO,
+			Vec3::new(100.0, 0.0, 0.0),
+			Vec3::new(100.0, 0.0, 50.0),
+			Vec3::new(50.0, 0.0, -50.0),
+		]);
+		let crossings = geometry.find_self_intersections();
+		assert_eq!(crossings.len(), 1, "{crossings:?}");
+		let (i, j, point) = crossings[0];
+		assert_eq!((i, j), (0, 2));
+		assert!(point.distance(Vec3::new(75.0, 0.0, 0.0)) < 1e-3, "{point}");
+	}
+
+	#[test]
+	fn plain_turn_has_no_self_intersections() {
+		let geometry = calculate_alignment_geometry(
+			Vec3::ZERO,
+			Vec3::new(100.0, 0.0, 100.0),
+			&right_turn_alignment(30.0),
+		);
+		assert!(geometry.find_self_intersections().is_empty());
+	}
+
+	#[test]
+	fn straight_tangent_is_the_start_to_end_direction_everywhere() {
+		let (start, end) = (Vec3::new(10.0, 0.0, -20.0), Vec3::new(310.0, 0.0, 380.0));
+		let alignment = Alignment::new(start, end, 0);
+		let geometry = calculate_alignment_geometry(start, end, &alignment);
+		let direction = (end - start).normalize();
+		let heading = azimuth_of_tangent(end, start);
+		for station in [0.0, 1.0, 250.0, geometry.total_length()] {
+			let tangent = geometry.tangent_at_station(station).unwrap();
+			assert!(tangent.distance(direction) < 1e-4, "{station}: {tangent}");
+			let station_heading = geometry.heading_at_station(station).unwrap();
+			assert!((station_heading - heading).abs() < 1e-4);
+		}
+	}
+
+	#[test]
+	fn turn_tangent_follows_the_centerline() {
+		let alignment = right_turn_alignment(30.0);
+		let geometry = calculate_alignment_geometry(alignment.start, alignment.end, &alignment);
+		let step = 0.01;
+		let mut station = step;
+		while station < geometry.total_length() - step {
+			let tangent = geometry.tangent_at_station(station).unwrap();
+			let behind = geometry.xz_at_station(station - step).unwrap();
+			let ahead = geometry.xz_at_station(station + step).unwrap();
+			let expected = (ahead - behind).normalize();
+			assert!(
+				Vec2::new(tangent.x, tangent.z).distance(expected) < 1e-3,
+				"{station}: {tangent} vs {expected}"
+			);
+			station += 0.5;
+		}
+	}
+
+	#[test]
+	fn raycast_hits_the_draped_centerline() {
+		let alignment = Alignment::new(Vec3::ZERO, Vec3::new(200.0, 0.0, 0.0), 0);
+		let geometry = calculate_alignment_geometry(alignment.start, alignment.end, &alignment);
+		let heights = FlatHeightSampler(5.0);
+		// Looking down at station 120 from above and to the side
+		let origin = Vec3::new(120.0, 105.0, 100.0);
+		let direction = Vec3::new(120.0, 5.0, 0.0) - origin;
+		let (station, point) = geometry.raycast(origin, direction, &heights, 0.5).unwrap();
+		assert!((station - 120.0).abs() < 1e-2, "station {station}");
+		assert!(point.distance(Vec3::new(120.0, 5.0, 0.0)) < 1e-2, "{point}");
+
+		// Pointing away, or passing too far from the path
+		assert!(
+			geometry
+				.raycast(origin, -direction, &heights, 0.5)
+				.is_none()
+		);
+		let wide = Vec3::new(120.0, 5.0, 3.0) - origin;
+		assert!(geometry.raycast(origin, wide, &heights, 0.5).is_none());
+	}
+
+	#[test]
+	fn chord_offsets_peak_at_middle_ordinate() {
+		let radius = 100.0;
+		let sweep = std::f32::consts::FRAC_PI_2;
+		let arc = CircularArcGeometry {
+			start_point: Vec3::new(radius, 0.0, 0.0),
+			center: Vec3::ZERO,
+			start_vector: Vec3::new(radius, 0.0, 0.0),
+			arc_sweep: sweep,
+			end_point: Vec3::new(0.0, 0.0, -radius),
+			start_station: 0.0,
+			length: radius * sweep,
+		};
+		let offsets = arc.chord_offsets(5);
+		assert_eq!(offsets.len(), 5);
+
+		let middle_ordinate = radius * (1.0 - (0.5 * sweep).cos());
+		let max_offset = offsets.iter().map(|&(_, o)| o).fold(0.0, f32::max);
+		assert!((max_offset - middle_ordinate).abs() < 1e-3);
+		assert!((offsets[2].1 - middle_ordinate).abs() < 1e-3);
+		assert!(offsets[0].1.abs() < 1e-3);
+		assert!(offsets[4].1.abs() < 1e-3);
+		assert!((offsets[4].0 - arc.chord_length()).abs() < 1e-3);
+	}
+
+	#[test]
+	fn full_report_csv_has_header_and_one_row_per_step() {
+		let alignment = Alignment::new(Vec3::ZERO, Vec3::new(100.0, 0.0, 0.0), 0);
+		let geometry = calculate_alignment_geometry(alignment.start, alignment.end, &alignment);
+		let heights = crate::elevation::PviProfile::default();
+		let step = 10.0;
+		let csv = geometry.full_report_csv(&heights, step, 80.0, 150.0);
+
+		let mut lines = csv.lines();
+		let header: Vec<&str> = lines
+			.next()
+			.expect("csv should have a header")
+			.split(',')
+			.collect();
+		assert_eq!(
+			header,
+			[
+				"station",
+				"x",
+				"y",
+				"z",
+				"azimuth_deg",
+				"curvature",
+				"radius",
+				"grade_percent",
+				"cant_mm",
+				"element"
+			]
+		);
+		let rows: Vec<&str> = lines.collect();
+		let expected_rows = (geometry.total_length() / step).round() as usize + 1;
+		assert_eq!(rows.len(), expected_rows);
+		assert!(rows.iter().all(|row| row.ends_with(",0.0,straight")));
+	}
+
+	#[test]
+	fn straight_footprint_area_is_width_times_length() {
+		let (length, half_width) = (250.0, 6.0);
+		let alignment = Alignment::new(Vec3::ZERO, Vec3::new(length, 0.0, 0.0), 0);
+		let geometry = calculate_alignment_geometry(alignment.start, alignment.end, &alignment);
+		// Flat end caps, so nothing beyond the rectangle
+		let area = geometry.footprint_area(half_width);
+		assert!(
+			(area - 2.0 * half_width * length).abs() < 0.5,
+			"area {area}"
+		);
+	}
+
+	#[test]
+	fn offset_of_straight_is_parallel_to_the_right() {
+		let alignment = Alignment::new(Vec3::ZERO, Vec3::new(100.0, 0.0, 0.0), 0);
+		let geometry = calculate_alignment_geometry(alignment.start, alignment.end, &alignment);
+		let offset = geometry.offset(5.0);
+		assert!(!offset.is_empty(), "offset should produce samples");
+		for point in &offset {
+			assert!(
+				(point.z - 5.0).abs() < 1e-3,
+				"point not offset to the right: {point}"
+			);
+		}
+		for point in geometry.offset(-5.0) {
+			assert!(
+				(point.z + 5.0).abs() < 1e-3,
+				"point not offset to the left: {point}"
+			);
+		}
+	}
+
+	#[test]
+	fn offset_toward_curve_center_is_clamped_inside_radius() {
+		let radius = 10.0;
+		let alignment = right_turn_alignment(radius);
+		let geometry = calculate_alignment_geometry(alignment.start, alignment.end, &alignment);
+		let GeometrySegment::Turn(turn) = geometry.segments[1] else {
+			panic!("expected the second segment to be a turn");
+		};
+		assert!(turn.turns_right(), "alignment should turn right");
+		let arc = turn.circular_arc;
+		let center = Vec2::new(arc.center.x, arc.center.z);
+		for (station, point) in geometry.offset_samples(50.0) {
+			assert!(point.is_finite(), "offset point must be finite");
+			if (arc.start_station..=arc.start_station + arc.length).contains(&station) {
+				let distance = point.distance(center);
+				assert!(
+					(distance - MIN_OFFSET_RADIUS_FRACTION * radius).abs() < 0.1,
+					"clamped offset should stay on the center side of the arc, got {distance}",
+				);
+			}
+		}
+	}
 }
@@ -2,14 +2,23 @@ pub mod constraints;
 pub mod elevation;
 pub mod geometry;
 pub mod path;
+pub mod ribbon;
+pub mod tolerance;
 
 pub use constraints::{
-	MAX_ARC_RADIUS, MIN_ARC_RADIUS, clamp_turn_parameters, compute_max_angle,
-	enforce_alignment_constraints,
+	CantRunoffShortfall, ClothoidParameterWarning, MAX_ARC_RADIUS, MIN_ARC_RADIUS,
+	MIN_VERTEX_SPACING, applied_cant_mm, cant_runoff_length, check_cant_runoff,
+	check_clothoid_parameter, check_min_radius, clamp_turn_parameters, clothoid_parameter_in_range,
+	compute_max_angle, enforce_alignment_constraints, min_radius_for_speed,
+};
+pub use elevation::{
+	ElevationProfile, PviProfile, TerrainSampledProfile, VerticalCurve, VerticalProfileData,
 };
-pub use elevation::{ElevationProfile, PviProfile, TerrainSampledProfile, VerticalProfileData};
 pub use geometry::{
-	AlignmentGeometry, CircularArcGeometry, ClothoidParameters, CurveSegment, GeometrySegment,
-	HeightSampler, StraightGeometry, calculate_alignment_geometry,
+	AlignmentGeometry, CircularArcGeometry, ClothoidParameters, CurveSegment, ElementKind,
+	FlatHeightSampler, GeometrySegment, HeightSampler, StraightGeometry,
+	calculate_alignment_geometry, calculate_alignment_geometry_with_tolerances, polygon_area,
 };
-pub use path::{Alignment, PathSegment, StraightSegment, TurnSegment};
+pub use path::{Alignment, PathSegment, StraightSegment, TransitionSpec, TurnSegment};
+pub use ribbon::RibbonMesh;
+pub use tolerance::GeometryTolerances;
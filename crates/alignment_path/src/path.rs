@@ -2,11 +2,12 @@ use glam::Vec3;
 use serde::{Deserialize, Serialize};
 
 use crate::elevation::VerticalProfileData;
+use crate::tolerance::GeometryTolerances;
 
 const DEFAULT_STRAIGHT_FRACTION: f32 = 0.5;
 const STRAIGHT_FRACTION_EPSILON: f32 = 1.0e-4;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Alignment {
 	pub start: Vec3,
 	pub end: Vec3,
@@ -45,6 +46,26 @@ impl Alignment {
 		}
 	}
 
+	/// Builds an alignment with one turn per `(tangent_vertex, radius, arc_angle)` entry, in order.
+	pub fn from_vertices(start: Vec3, end: Vec3, verts: Vec<(Vec3, f32, f32)>) -> Self {
+		let segments = verts
+			.into_iter()
+			.map(|(tangent_vertex, radius, angle)| {
+				PathSegment::Turn(TurnSegment {
+					circular_section_radius: radius,
+					circular_section_angle: angle,
+					..TurnSegment::new(tangent_vertex)
+				})
+			})
+			.collect();
+		Self {
+			start,
+			end,
+			segments,
+			vertical_profile: VerticalProfileData::default(),
+		}
+	}
+
 	pub fn turn_count(&self) -> usize {
 		self
 			.segments
@@ -91,7 +112,12 @@ impl Alignment {
 		control_points
 	}
 
-	pub fn append_segment_boundary(&mut self, point: Vec3, next_anchor: Vec3) {
+	pub fn append_segment_boundary(
+		&mut self,
+		point: Vec3,
+		next_anchor: Vec3,
+		tolerances: &GeometryTolerances,
+	) {
 		if self.end != point {
 			return;
 		}
@@ -99,7 +125,7 @@ impl Alignment {
 			.segments
 			.last()
 			.and_then(|_| self.segment_control_point(self.segments.len().saturating_sub(1)))
-			.is_some_and(|last| last.distance_squared(point) <= f32::EPSILON)
+			.is_some_and(|last| tolerances.coincident(last, point))
 		{
 			return;
 		}
@@ -115,7 +141,7 @@ impl Alignment {
 
 		for (offset, boundary_point) in boundary_points.iter().enumerate() {
 			let projected_fraction =
-				project_fraction_onto_span(*boundary_point, section_start, next_anchor);
+				project_fraction_onto_span(*boundary_point, section_start, next_anchor, tolerances);
 			let remaining = boundary_points.len() - offset - 1;
 			let max_for_current =
 				(max_fraction - STRAIGHT_FRACTION_EPSILON * remaining as f32).max(min_fraction);
@@ -139,7 +165,47 @@ impl Alignment {
 		}
 	}
 
-	pub fn set_segment_control_point(&mut self, segment_index: usize, point: Vec3) -> bool {
+	/// `point` pushed along the start→end axis just far enough to project between the control
+	/// points either side of `segment_index`, so a dragged vertex can't pass its neighbours.
+	/// Points that already fit are returned unchanged, as is everything on a degenerate span.
+	pub fn keep_between_neighbors(
+		&self,
+		segment_index: usize,
+		point: Vec3,
+		tolerances: &GeometryTolerances,
+	) -> Vec3 {
+		let span = self.end - self.start;
+		if tolerances.is_negligible(span.length()) {
+			return point;
+		}
+		let previous = match segment_index.checked_sub(1) {
+			Some(previous_index) => self.segment_control_point(previous_index),
+			None => Some(self.start),
+		};
+		let next = if segment_index + 1 < self.segments.len() {
+			self.segment_control_point(segment_index + 1)
+		} else {
+			Some(self.end)
+		};
+		let (Some(previous), Some(next)) = (previous, next) else {
+			return point;
+		};
+		let fraction = |p: Vec3| project_fraction_onto_span(p, self.start, self.end, tolerances);
+		let (low, high) = (fraction(previous), fraction(next));
+		// Neighbours already out of order leave nothing sensible to clamp between
+		if low > high {
+			return point;
+		}
+		let current = fraction(point);
+		point + span * (current.clamp(low, high) - current)
+	}
+
+	pub fn set_segment_control_point(
+		&mut self,
+		segment_index: usize,
+		point: Vec3,
+		tolerances: &GeometryTolerances,
+	) -> bool {
 		let anchors = if matches!(
 			self.segments.get(segment_index),
 			Some(PathSegment::Straight(_))
@@ -167,6 +233,7 @@ impl Alignment {
 					point,
 					previous_anchor,
 					next_anchor,
+					tolerances,
 				));
 				if straight.fraction() == fraction {
 					return false;
@@ -183,6 +250,98 @@ impl Alignment {
 			.push(PathSegment::Turn(TurnSegment::new(tangent_vertex)));
 	}
 
+	/// Inserts a turn before `segment_index`, keeping every other control point where it was.
+	pub fn insert_turn(
+		&mut self,
+		segment_index: usize,
+		tangent_vertex: Vec3,
+		tolerances: &GeometryTolerances,
+	) {
+		let segment_index = segment_index.min(self.segments.len());
+		let mut points = self.segment_control_points();
+		self.segments.insert(
+			segment_index,
+			PathSegment::Turn(TurnSegment::new(tangent_vertex)),
+		);
+		points.insert(segment_index, tangent_vertex);
+		self.restore_straight_control_points(&points, tolerances);
+	}
+
+	/// Removes a segment, keeping every other control point where it was.
+	pub fn remove_segment(
+		&mut self,
+		segment_index: usize,
+		tolerances: &GeometryTolerances,
+	) -> Option<PathSegment> {
+		if segment_index >= self.segments.len() {
+			return None;
+		}
+		let mut points = self.segment_control_points();
+		let removed = self.segments.remove(segment_index);
+		points.remove(segment_index);
+		self.restore_straight_control_points(&points, tolerances);
+		Some(removed)
+	}
+
+	/// Splits every leg of the tangent polygon into `k` equal parts by inserting straight control
+	/// points, so the shape is unchanged but there are more vertices to work with.
+	pub fn subdivide(&self, k: usize, tolerances: &GeometryTolerances) -> Alignment {
+		if k <= 1 {
+			return self.clone();
+		}
+		let polyline = self.control_points();
+		let mut segments = Vec::with_capacity(self.segments.len() * k + k - 1);
+		let mut points = Vec::with_capacity(segments.capacity());
+		for (leg_index, leg) in polyline.windows(2).enumerate() {
+			if let Some(segment) = leg_index.checked_sub(1).map(|i| self.segments[i]) {
+				segments.push(segment);
+				points.push(leg[0]);
+			}
+			for i in 1..k {
+				segments.push(PathSegment::Straight(StraightSegment::default()));
+				points.push(leg[0].lerp(leg[1], i as f32 / k as f32));
+			}
+		}
+		let mut subdivided = Alignment {
+			segments,
+			..self.clone()
+		};
+		subdivided.restore_straight_control_points(&points, tolerances);
+		subdivided
+	}
+
+	/// Flips the direction of travel: the tangent polygon is walked end to start and any PVI
+	/// profile is mirrored to match.
+	pub fn reverse(&mut self, tolerances: &GeometryTolerances) {
+		let mut points = self.segment_control_points();
+		points.reverse();
+		std::mem::swap(&mut self.start, &mut self.end);
+		self.segments.reverse();
+		self.restore_straight_control_points(&points, tolerances);
+		if let VerticalProfileData::Pvi(profile) = &mut self.vertical_profile {
+			profile.reverse();
+		}
+	}
+
+	fn segment_control_points(&self) -> Vec<Vec3> {
+		(0..self.segments.len())
+			.filter_map(|segment_index| self.segment_control_point(segment_index))
+			.collect()
+	}
+
+	// Straight control points are stored relative to the neighbouring turns, so they have to be
+	// re-projected whenever a turn is added or removed.
+	fn restore_straight_control_points(&mut self, points: &[Vec3], tolerances: &GeometryTolerances) {
+		for (segment_index, point) in points.iter().enumerate() {
+			if matches!(
+				self.segments.get(segment_index),
+				Some(PathSegment::Straight(_))
+			) {
+				self.set_segment_control_point(segment_index, *point, tolerances);
+			}
+		}
+	}
+
 	fn resolve_straight_control_point(
 		&self,
 		segment_index: usize,
@@ -198,10 +357,12 @@ impl Alignment {
 		let fraction = straight
 			.legacy_point()
 			.map(|legacy_point| {
+				// Legacy points only come from files saved before tolerances were configurable
 				clamp_straight_fraction(project_fraction_onto_span(
 					legacy_point,
 					previous_anchor,
 					next_anchor,
+					&GeometryTolerances::DEFAULT,
 				))
 			})
 			.unwrap_or_else(|| straight.fraction());
@@ -252,7 +413,7 @@ impl Alignment {
 	}
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
 #[serde(untagged)]
 pub enum PathSegment {
 	Straight(StraightSegment),
@@ -289,7 +450,7 @@ impl PathSegment {
 	}
 }
 
-#[derive(Debug, Serialize, Clone, Copy)]
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
 pub struct StraightSegment {
 	pub fraction: f32,
 	#[serde(skip)]
@@ -346,11 +507,36 @@ impl<'de> Deserialize<'de> for StraightSegment {
 	}
 }
 
-#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq)]
 pub struct TurnSegment {
 	pub tangent_vertex: Vec3,
 	pub circular_section_radius: f32,
 	pub circular_section_angle: f32,
+	#[serde(default)]
+	pub transition: TransitionSpec,
+}
+
+/// Which quantity sizes a turn's transition curves. Each clothoid turns through half of what the
+/// arc leaves of the deflection, so its length is L = R·(Δ − ω) and fixing one fixes the other.
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq)]
+pub enum TransitionSpec {
+	/// `circular_section_angle` is set directly and the clothoids take up the rest.
+	#[default]
+	ByAngle,
+	/// Length of each clothoid in meters.
+	ByLength(f32),
+	/// Clothoid parameter A, where A² = R·L.
+	ByParameter(f32),
+}
+
+impl TransitionSpec {
+	pub const fn label(self) -> &'static str {
+		match self {
+			Self::ByAngle => "Arc angle",
+			Self::ByLength(_) => "Transition length",
+			Self::ByParameter(_) => "Clothoid A",
+		}
+	}
 }
 
 impl TurnSegment {
@@ -359,6 +545,40 @@ impl TurnSegment {
 			tangent_vertex,
 			circular_section_radius: 50.0,
 			circular_section_angle: 0.5,
+			transition: TransitionSpec::ByAngle,
+		}
+	}
+
+	/// Clothoid length implied by the current radius and arc angle for a turn deflecting by
+	/// `difference_in_azimuth`.
+	pub fn clothoid_length(&self, difference_in_azimuth: f32) -> f32 {
+		(self.circular_section_radius * (difference_in_azimuth - self.circular_section_angle)).max(0.0)
+	}
+
+	/// Arc angle for a turn deflecting by `difference_in_azimuth`, derived from `transition`.
+	pub fn arc_angle(&self, difference_in_azimuth: f32) -> f32 {
+		let radius = self.circular_section_radius;
+		let clothoid_length = match self.transition {
+			TransitionSpec::ByAngle => return self.circular_section_angle,
+			TransitionSpec::ByLength(length) => length,
+			TransitionSpec::ByParameter(parameter) => parameter * parameter / radius,
+		};
+		if !clothoid_length.is_finite() || radius <= 0.0 {
+			return self.circular_section_angle;
+		}
+		(difference_in_azimuth - clothoid_length.max(0.0) / radius).clamp(0.0, difference_in_azimuth)
+	}
+
+	/// Rewrite the length or A held by `transition` to match the current radius and arc angle,
+	/// after constraints have moved them.
+	pub fn sync_transition(&mut self, difference_in_azimuth: f32) {
+		let clothoid_length = self.clothoid_length(difference_in_azimuth);
+		match &mut self.transition {
+			TransitionSpec::ByAngle => {}
+			TransitionSpec::ByLength(length) => *length = clothoid_length,
+			TransitionSpec::ByParameter(parameter) => {
+				*parameter = (self.circular_section_radius * clothoid_length).sqrt();
+			}
 		}
 	}
 }
@@ -370,11 +590,202 @@ pub(crate) fn clamp_straight_fraction(fraction: f32) -> f32 {
 	fraction.clamp(STRAIGHT_FRACTION_EPSILON, 1.0 - STRAIGHT_FRACTION_EPSILON)
 }
 
-pub(crate) fn project_fraction_onto_span(point: Vec3, start: Vec3, end: Vec3) -> f32 {
+pub(crate) fn project_fraction_onto_span(
+	point: Vec3,
+	start: Vec3,
+	end: Vec3,
+	tolerances: &GeometryTolerances,
+) -> f32 {
 	let span = end - start;
 	let span_length_sq = span.length_squared();
-	if !span_length_sq.is_finite() || span_length_sq <= f32::EPSILON {
+	if !span_length_sq.is_finite() || tolerances.is_negligible(span_length_sq.sqrt()) {
 		return DEFAULT_STRAIGHT_FRACTION;
 	}
 	(point - start).dot(span) / span_length_sq
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn distance_to_polyline(point: Vec3, polyline: &[Vec3]) -> f32 {
+		polyline
+			.windows(2)
+			.map(|leg| {
+				let t = project_fraction_onto_span(point, leg[0], leg[1], &GeometryTolerances::DEFAULT)
+					.clamp(0.0, 1.0);
+				point.distance(leg[0].lerp(leg[1], t))
+			})
+			.fold(f32::INFINITY, f32::min)
+	}
+
+	#[test]
+	fn subdivide_inserts_points_on_the_tangent_polygon() {
+		let mut alignment = Alignment::new(Vec3::ZERO, Vec3::new(300.0, 0.0, 0.0), 2);
+		alignment.set_segment_control_point(
+			0,
+			Vec3::new(100.0, 0.0, 80.0),
+			&GeometryTolerances::DEFAULT,
+		);
+		alignment.set_segment_control_point(
+			1,
+			Vec3::new(200.0, 0.0, -60.0),
+			&GeometryTolerances::DEFAULT,
+		);
+		let original = alignment.control_points();
+
+		let subdivided = alignment.subdivide(2, &GeometryTolerances::DEFAULT);
+		let points = subdivided.control_points();
+
+		// Three legs, each gaining one midpoint
+		assert_eq!(subdivided.segments.len(), alignment.segments.len() + 3);
+		assert_eq!(subdivided.turn_count(), alignment.turn_count());
+		assert_eq!(points.len(), original.len() + 3);
+		for (i, point) in original.iter().enumerate() {
+			assert!(points[i * 2].distance(*point) < 1e-3);
+		}
+		for point in &points {
+			assert!(
+				distance_to_polyline(*point, &original) < 1e-3,
+				"{point} is off the original polyline"
+			);
+		}
+	}
+
+	#[test]
+	fn kept_vertices_stop_at_their_neighbours_station() {
+		let mut alignment = Alignment::new(Vec3::ZERO, Vec3::new(300.0, 0.0, 0.0), 2);
+		alignment.set_segment_control_point(
+			0,
+			Vec3::new(100.0, 0.0, 80.0),
+			&GeometryTolerances::DEFAULT,
+		);
+		alignment.set_segment_control_point(
+			1,
+			Vec3::new(200.0, 0.0, -60.0),
+			&GeometryTolerances::DEFAULT,
+		);
+
+		let past_next = alignment.keep_between_neighbors(
+			0,
+			Vec3::new(250.0, 0.0, 40.0),
+			&GeometryTolerances::DEFAULT,
+		);
+		assert!(past_next.distance(Vec3::new(200.0, 0.0, 40.0)) < 1e-3);
+		let before_start = alignment.keep_between_neighbors(
+			0,
+			Vec3::new(-30.0, 0.0, 10.0),
+			&GeometryTolerances::DEFAULT,
+		);
+		assert!(before_start.distance(Vec3::new(0.0, 0.0, 10.0)) < 1e-3);
+		let past_end =
+			alignment.keep_between_neighbors(1, Vec3::new(320.0, 0.0, 5.0), &GeometryTolerances::DEFAULT);
+		assert!(past_end.distance(Vec3::new(300.0, 0.0, 5.0)) < 1e-3);
+
+		let in_range = Vec3::new(150.0, 0.0, 10.0);
+		assert_eq!(
+			alignment.keep_between_neighbors(0, in_range, &GeometryTolerances::DEFAULT),
+			in_range
+		);
+	}
+
+	#[test]
+	fn from_vertices_keeps_each_turns_vertex_radius_and_angle() {
+		let vertices = vec![
+			(Vec3::new(100.0, 0.0, 0.0), 80.0, 0.2),
+			(Vec3::new(200.0, 0.0, 100.0), 120.0, 0.4),
+		];
+		let end = Vec3::new(300.0, 0.0, 100.0);
+		let alignment = Alignment::from_vertices(Vec3::ZERO, end, vertices.clone());
+		assert_eq!(alignment.turn_count(), 2);
+		assert_eq!(
+			alignment.control_points(),
+			vec![Vec3::ZERO, vertices[0].0, vertices[1].0, end]
+		);
+		for (segment, (_, radius, angle)) in alignment.segments.iter().zip(vertices) {
+			let turn = segment.as_turn().unwrap();
+			assert_eq!(turn.circular_section_radius, radius);
+			assert_eq!(turn.circular_section_angle, angle);
+		}
+	}
+
+	#[test]
+	fn reversing_twice_is_the_identity() {
+		let mut alignment = Alignment::new(Vec3::ZERO, Vec3::new(300.0, 0.0, 0.0), 2);
+		alignment.set_segment_control_point(
+			0,
+			Vec3::new(100.0, 0.0, 80.0),
+			&GeometryTolerances::DEFAULT,
+		);
+		// A straight control point is stored relative to its neighbours, so it must survive too
+		alignment
+			.segments
+			.insert(1, PathSegment::Straight(StraightSegment::default()));
+		alignment.set_segment_control_point(
+			1,
+			Vec3::new(150.0, 0.0, 20.0),
+			&GeometryTolerances::DEFAULT,
+		);
+		let original = alignment.control_points();
+
+		let mut reversed = alignment.clone();
+		reversed.reverse(&GeometryTolerances::DEFAULT);
+		assert_eq!(
+			(reversed.start, reversed.end),
+			(alignment.end, alignment.start)
+		);
+		let mut expected = original.clone();
+		expected.reverse();
+		for (point, expected) in reversed.control_points().iter().zip(&expected) {
+			assert!(point.distance(*expected) < 1e-3, "{point} != {expected}");
+		}
+
+		reversed.reverse(&GeometryTolerances::DEFAULT);
+		assert_eq!(
+			(reversed.start, reversed.end),
+			(alignment.start, alignment.end)
+		);
+		for (point, original) in reversed.control_points().iter().zip(&original) {
+			assert!(point.distance(*original) < 1e-3, "{point} != {original}");
+		}
+	}
+
+	#[test]
+	fn reversing_mirrors_the_pvi_profile() {
+		use crate::elevation::{ElevationProfile, Pvi, PviProfile};
+
+		let mut alignment = Alignment::new(Vec3::ZERO, Vec3::new(100.0, 0.0, 0.0), 0);
+		let profile = PviProfile {
+			points: vec![
+				Pvi {
+					station: 0.0,
+					elevation: 10.0,
+				},
+				Pvi {
+					station: 40.0,
+					elevation: 14.0,
+				},
+				Pvi {
+					station: 100.0,
+					elevation: 8.0,
+				},
+			],
+			..Default::default()
+		};
+		alignment.vertical_profile = VerticalProfileData::Pvi(profile.clone());
+		alignment.reverse(&GeometryTolerances::DEFAULT);
+		let VerticalProfileData::Pvi(reversed) = &alignment.vertical_profile else {
+			panic!("profile kind should be kept");
+		};
+		for station in [0.0, 25.0, 40.0, 60.0, 100.0] {
+			let (forward, backward) = (
+				profile.elevation_at(station),
+				reversed.elevation_at(100.0 - station),
+			);
+			assert!(
+				(forward - backward).abs() < 1e-4,
+				"{station}: {forward} vs {backward}"
+			);
+		}
+	}
+}
@@ -0,0 +1,107 @@
+use glam::{Vec2, Vec3};
+
+use crate::geometry::{AlignmentGeometry, HeightSampler};
+
+/// Vertex and index buffers for a triangle-list mesh, independent of any renderer.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RibbonMesh {
+	pub positions: Vec<[f32; 3]>,
+	pub normals: Vec<[f32; 3]>,
+	/// `u` runs 0 on the left edge to 1 on the right; `v` repeats once per ribbon width.
+	pub uvs: Vec<[f32; 2]>,
+	pub indices: Vec<u32>,
+}
+
+impl AlignmentGeometry {
+	/// Flat ribbon `2 * half_width` wide following the centerline draped on `heights`, with
+	/// `resolution_per_segment` quads per geometry segment. Cross sections stay level; the
+	/// ribbon only tilts along the direction of travel.
+	pub fn build_ribbon_mesh(
+		&self,
+		half_width: f32,
+		resolution_per_segment: u32,
+		heights: &dyn HeightSampler,
+	) -> RibbonMesh {
+		let half_width = half_width.abs();
+		let resolution = resolution_per_segment.max(1);
+		let mut stations: Vec<f32> = Vec::new();
+		for segment in &self.segments {
+			let (start, length) = (segment.start_station(), segment.length());
+			// Each segment starts where the previous one ended
+			let first = if stations.is_empty() { 0 } else { 1 };
+			stations.extend((first..=resolution).map(|i| start + length * i as f32 / resolution as f32));
+		}
+		let centerline: Vec<(f32, Vec3, Vec2)> = stations
+			.into_iter()
+			.filter_map(|station| {
+				let xz = self.clamped_xz_at_station(station)?;
+				let tangent = self.tangent_at_station(station)?;
+				let point = Vec3::new(xz.x, 0.0, xz.y);
+				let right = Vec2::new(-tangent.z, tangent.x);
+				Some((station, point.with_y(heights.height_at(point)), right))
+			})
+			.collect();
+
+		let mut mesh = RibbonMesh::default();
+		for (i, (station, point, right)) in centerline.iter().enumerate() {
+			let before = centerline[i.saturating_sub(1)].1;
+			let after = centerline[(i + 1).min(centerline.len() - 1)].1;
+			let right = Vec3::new(right.x, 0.0, right.y);
+			let normal = right
+				.cross(after - before)
+				.try_normalize()
+				.unwrap_or(Vec3::Y);
+			let v = station / (2.0 * half_width).max(f32::EPSILON);
+			for (side, u) in [(-1.0, 0.0), (1.0, 1.0)] {
+				mesh
+					.positions
+					.push((*point + right * half_width * side).to_array());
+				mesh.normals.push(normal.to_array());
+				mesh.uvs.push([u, v]);
+			}
+		}
+		let quads = centerline.len().saturating_sub(1) as u32;
+		mesh.indices = (0..quads)
+			.flat_map(|i| {
+				let (left0, right0, left1, right1) = (2 * i, 2 * i + 1, 2 * i + 2, 2 * i + 3);
+				[left0, right0, left1, left1, right0, right1]
+			})
+			.collect();
+		mesh
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::geometry::calculate_alignment_geometry;
+	use crate::path::Alignment;
+
+	struct Slope;
+	impl HeightSampler for Slope {
+		fn height_at(&self, position: Vec3) -> f32 {
+			position.x * 0.1
+		}
+	}
+
+	#[test]
+	fn ribbon_follows_a_sloped_straight_facing_up() {
+		let alignment = Alignment::new(Vec3::ZERO, Vec3::new(100.0, 0.0, 0.0), 0);
+		let geometry = calculate_alignment_geometry(alignment.start, alignment.end, &alignment);
+		let mesh = geometry.build_ribbon_mesh(2.0, 10, &Slope);
+
+		assert_eq!(mesh.positions.len(), 22);
+		assert_eq!(mesh.indices.len(), 10 * 6);
+		for (position, normal) in mesh.positions.iter().zip(&mesh.normals) {
+			let [x, y, z] = *position;
+			assert!((y - x * 0.1).abs() < 1e-3);
+			assert!((z.abs() - 2.0).abs() < 1e-3);
+			assert!(normal[1] > 0.99, "normal {normal:?} should point up");
+		}
+		// Counter-clockwise seen from above, so the lit side faces the sky
+		for triangle in mesh.indices.chunks(3) {
+			let [a, b, c] = [0, 1, 2].map(|k| Vec3::from(mesh.positions[triangle[k] as usize]));
+			assert!((b - a).cross(c - a).y > 0.0);
+		}
+	}
+}
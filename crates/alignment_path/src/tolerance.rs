@@ -0,0 +1,58 @@
+use glam::Vec3;
+
+/// Thresholds below which geometry is treated as degenerate. World coordinates are meters and
+/// often in the thousands, where `f32::EPSILON` is far below the precision actually available.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeometryTolerances {
+	/// Points closer than this many meters are the same point. Default 1 mm.
+	pub coincidence_distance: f32,
+	/// Deflections below this many radians are treated as straight. Default 1 µrad, about 1 mm of
+	/// offset over a kilometer.
+	pub angle_epsilon: f32,
+}
+
+impl GeometryTolerances {
+	pub const DEFAULT: Self = Self {
+		coincidence_distance: 1.0e-3,
+		angle_epsilon: 1.0e-6,
+	};
+
+	pub fn coincident(&self, a: Vec3, b: Vec3) -> bool {
+		a.distance_squared(b) <= self.coincidence_distance * self.coincidence_distance
+	}
+
+	/// Whether a length (or a difference of stations or heights) is too small to divide by.
+	pub fn is_negligible(&self, length: f32) -> bool {
+		length.abs() <= self.coincidence_distance
+	}
+
+	pub fn is_straight(&self, deflection: f32) -> bool {
+		deflection.abs() <= self.angle_epsilon
+	}
+}
+
+impl Default for GeometryTolerances {
+	fn default() -> Self {
+		Self::DEFAULT
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn coincidence_uses_the_configured_distance() {
+		let a = Vec3::new(2_000.0, 0.0, 2_000.0);
+		let b = a + Vec3::X * 5.0e-4;
+		// Further apart than machine epsilon, but within the 1 mm default
+		assert!(a.distance_squared(b) > f32::EPSILON);
+		assert!(GeometryTolerances::DEFAULT.coincident(a, b));
+
+		let strict = GeometryTolerances {
+			coincidence_distance: 1.0e-4,
+			..GeometryTolerances::DEFAULT
+		};
+		assert!(!strict.coincident(a, b));
+	}
+}
@@ -0,0 +1,99 @@
+//! Builds geometry through the public API only, with no terrain or renderer involved.
+
+use std::f32::consts::FRAC_PI_2;
+
+use alignment_path::{
+	Alignment, ElevationProfile, FlatHeightSampler, GeometrySegment, PathSegment,
+	TerrainSampledProfile, TurnSegment, calculate_alignment_geometry, compute_max_angle,
+};
+use glam::{Vec2, Vec3};
+
+const RADIUS: f32 = 100.0;
+const TOLERANCE: f32 = 1e-2;
+
+/// Two right-angle turns with no transitions, so every arc point can be worked out by hand:
+/// east to (400, 0), north to (400, 400), then east again to (800, 400).
+fn two_turn_alignment() -> Alignment {
+	let start = Vec3::ZERO;
+	let end = Vec3::new(800.0, 0.0, 400.0);
+	let vertices = [Vec3::new(400.0, 0.0, 0.0), Vec3::new(400.0, 0.0, 400.0)];
+	let neighbors = [(start, vertices[1]), (vertices[0], end)];
+	let segments = vertices
+		.iter()
+		.zip(neighbors)
+		.map(|(vertex, (previous, next))| {
+			PathSegment::Turn(TurnSegment {
+				circular_section_radius: RADIUS,
+				// The arc takes the whole deflection, leaving zero-length clothoids
+				circular_section_angle: compute_max_angle(previous, *vertex, next),
+				..TurnSegment::new(*vertex)
+			})
+		})
+		.collect();
+	Alignment {
+		start,
+		end,
+		segments,
+		..Default::default()
+	}
+}
+
+fn assert_near(actual: Vec3, expected: Vec3) {
+	assert!(
+		actual.distance(expected) < TOLERANCE,
+		"expected {expected}, got {actual}"
+	);
+}
+
+#[test]
+fn two_turn_arcs_match_hand_computed_points() {
+	let alignment = two_turn_alignment();
+	let geometry = calculate_alignment_geometry(alignment.start, alignment.end, &alignment);
+	let arcs: Vec<_> = geometry
+		.segments
+		.iter()
+		.filter_map(|segment| match segment {
+			GeometrySegment::Turn(turn) => Some(turn.circular_arc),
+			GeometrySegment::Straight(_) => None,
+		})
+		.collect();
+	assert_eq!(arcs.len(), 2);
+
+	// A 90° arc of radius R touches each leg R before and after the vertex
+	assert_near(arcs[0].start_point, Vec3::new(300.0, 0.0, 0.0));
+	assert_near(arcs[0].center, Vec3::new(300.0, 0.0, 100.0));
+	assert_near(arcs[0].end_point, Vec3::new(400.0, 0.0, 100.0));
+	assert_near(arcs[1].start_point, Vec3::new(400.0, 0.0, 300.0));
+	assert_near(arcs[1].center, Vec3::new(500.0, 0.0, 300.0));
+	assert_near(arcs[1].end_point, Vec3::new(500.0, 0.0, 400.0));
+
+	let quarter_circle = FRAC_PI_2 * RADIUS;
+	let expected_length = 300.0 + 200.0 + 300.0 + 2.0 * quarter_circle;
+	assert!((geometry.total_length() - expected_length).abs() < TOLERANCE);
+
+	// Halfway round the first arc
+	let mid_arc = geometry
+		.xz_at_station(300.0 + quarter_circle / 2.0)
+		.expect("station on the first arc");
+	let expected = Vec2::new(300.0, 100.0) + Vec2::new(1.0, -1.0).normalize() * RADIUS;
+	assert!(mid_arc.distance(expected) < TOLERANCE, "{mid_arc}");
+}
+
+#[test]
+fn flat_sampler_drapes_everything_at_one_height() {
+	let alignment = two_turn_alignment();
+	let geometry = calculate_alignment_geometry(alignment.start, alignment.end, &alignment);
+	let sampler = FlatHeightSampler(3.0);
+
+	let profile = TerrainSampledProfile {
+		sampler: &sampler,
+		horizontal: &geometry,
+	};
+	for (station, _) in geometry.sample_by_station(50.0) {
+		assert_eq!(profile.elevation_at(station), 3.0);
+	}
+
+	let ribbon = geometry.build_ribbon_mesh(2.0, 8, &sampler);
+	assert!(!ribbon.indices.is_empty());
+	assert!(ribbon.positions.iter().all(|[_, y, _]| *y == 3.0));
+}
@@ -2,27 +2,56 @@ use std::f64::consts::PI;
 
 use bevy::prelude::*;
 
+use crate::saveable::{FileDialogPlugin, SaveableSettings};
+
 mod components;
 mod constraints;
+mod export;
+mod footprint;
+mod history;
+mod poi;
+mod profile_plot;
 mod render;
+mod sight_line;
 mod state;
 mod systems;
+mod templates;
 mod ui;
 
 pub(crate) use alignment_path::constraints::{MAX_ARC_RADIUS, MIN_ARC_RADIUS};
 pub(crate) use components::{AlignmentGizmos, configure_gizmos};
+pub(crate) use export::GeoOrigin;
+pub(crate) use render::{AlignmentRenderTimings, CachedAlignmentGeometry};
 pub(crate) use state::{
-	MAX_SNAP_ANGLE_DEGREES, MIN_SNAP_ANGLE_DEGREES, TangentSnapSettings, TrackBuildingMode,
-	load_alignment,
+	AlignmentState, MAX_SNAP_ANGLE_DEGREES, MIN_SNAP_ANGLE_DEGREES, PinOrderSettings,
+	TangentSnapSettings, TrackBuildingMode, load_alignment, prepare_loaded_alignments,
 };
 
 pub(crate) const MAX_TURNS: usize = 8;
 pub(crate) const FRAC_PI_180: f64 = PI / 180.;
-pub(crate) const MAX_GEOMETRY_DEBUG_LEVEL: u8 = 3;
+pub(crate) const MAX_GEOMETRY_DEBUG_LEVEL: u8 = 4;
+/// Debug level at which the centerline is redrawn colored by gradient.
+pub(crate) const GRADIENT_DEBUG_LEVEL: u8 = 4;
+/// Time geometry and gizmo drawing in the alignment render and show it in the HUD.
+pub(crate) const ALIGNMENT_RENDER_TIMINGS: bool = false;
 
 #[derive(Resource)]
 pub(crate) struct GeometryDebugLevel(pub u8);
 
+impl GeometryDebugLevel {
+	/// The next level up (or down, when `forward` is false), wrapping around
+	/// `0..=MAX_GEOMETRY_DEBUG_LEVEL`.
+	pub(crate) fn cycled(&self, forward: bool) -> u8 {
+		let count = MAX_GEOMETRY_DEBUG_LEVEL + 1;
+		let level = self.0.min(MAX_GEOMETRY_DEBUG_LEVEL);
+		if forward {
+			(level + 1) % count
+		} else {
+			(level + count - 1) % count
+		}
+	}
+}
+
 pub struct AlignmentPlugin;
 
 impl Plugin for AlignmentPlugin {
@@ -30,9 +59,24 @@ impl Plugin for AlignmentPlugin {
 		app
 			.insert_resource(load_alignment())
 			.insert_resource(GeometryDebugLevel(2))
+			.add_plugins(FileDialogPlugin::<AlignmentState>::default())
 			.init_resource::<state::TangentSnapSettings>()
+			.init_resource::<state::PinOrderSettings>()
 			.init_resource::<TrackBuildingMode>()
 			.init_resource::<state::DraftAlignment>()
+			.init_resource::<render::OffsetTrackSettings>()
+			.init_resource::<render::CachedAlignmentGeometry>()
+			.init_resource::<render::SpiralExaggerationSettings>()
+			.init_resource::<render::StationLabelSettings>()
+			.init_resource::<render::TrackRibbonSettings>()
+			.init_resource::<profile_plot::ProfilePlotSettings>()
+			.init_resource::<constraints::DesignSpeedSettings>()
+			.init_resource::<history::AlignmentHistory>()
+			.init_resource::<export::GeoOrigin>()
+			.init_resource::<footprint::FootprintSettings>()
+			.init_resource::<sight_line::SightLineSettings>()
+			.init_resource::<state::SelectedPins>()
+			.insert_resource(poi::PointsOfInterest::load_or_default())
 			.init_gizmo_group::<AlignmentGizmos>()
 			.add_systems(Startup, (state::startup, configure_gizmos))
 			.add_systems(
@@ -48,18 +92,65 @@ impl Plugin for AlignmentPlugin {
 					systems::update_alignment_from_pins,
 					systems::update_alignment_from_intermediate_pins,
 					constraints::enforce_alignment_constraints,
+					poi::apply_poi_bindings.before(systems::update_pins_from_alignment_state),
 					systems::update_pins_from_alignment_state,
 					systems::update_alignment_pins,
 					systems::update_draft_cursor_pin,
+					systems::insert_vertex_on_path_click.after(render::update_alignment_geometry_cache),
+					systems::select_alignment_on_path_click,
+					render::update_alignment_geometry_cache
+						.after(constraints::enforce_alignment_constraints)
+						.after(systems::update_alignment_from_pins)
+						.before(render::render_alignment_path),
 					render::render_alignment_path,
+					(
+						render::draw_self_intersections,
+						render::draw_gradient_profile,
+						render::draw_station_posts,
+						render::update_track_ribbon,
+						footprint::update_footprint_overlay,
+						sight_line::draw_sight_line,
+					)
+						.after(render::update_alignment_geometry_cache),
+					history::record_alignment_history
+						.after(constraints::enforce_alignment_constraints)
+						.after(systems::update_alignment_from_pins),
+					history::draw_alignment_diff,
+					systems::clear_selection_on_vertex_change,
+					systems::draw_selected_pin_markers,
 					(
 						systems::toggle_track_building_mode,
+						systems::cycle_geometry_debug_level,
 						systems::commit_first_segment,
 						systems::place_initial_point,
 					)
 						.chain(),
 				),
 			)
-			.add_systems(bevy_egui::EguiPrimaryContextPass, ui::ui);
+			.add_systems(
+				bevy_egui::EguiPrimaryContextPass,
+				(ui::ui, render::draw_station_labels),
+			)
+			.add_observer(systems::remove_vertex_on_pin_click)
+			.add_observer(systems::toggle_pin_selection_on_click);
+		if ALIGNMENT_RENDER_TIMINGS {
+			app.init_resource::<AlignmentRenderTimings>();
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn debug_level_wraps_both_ways() {
+		assert_eq!(GeometryDebugLevel(2).cycled(true), 3);
+		assert_eq!(GeometryDebugLevel(MAX_GEOMETRY_DEBUG_LEVEL).cycled(true), 0);
+		assert_eq!(
+			GeometryDebugLevel(0).cycled(false),
+			MAX_GEOMETRY_DEBUG_LEVEL
+		);
+		assert_eq!(GeometryDebugLevel(1).cycled(false), 0);
 	}
 }
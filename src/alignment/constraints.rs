@@ -1,12 +1,53 @@
+use alignment_path::GeometryTolerances;
 use alignment_path::constraints as path_constraints;
 use bevy::prelude::*;
 
 use super::state::AlignmentState;
 
-pub(crate) use alignment_path::constraints::compute_max_angle;
+pub(crate) use alignment_path::constraints::{
+	check_cant_runoff, check_clothoid_parameter, check_min_radius, check_tangent_overlap,
+	compute_max_angle,
+};
+
+/// Design speed, cant and ruling grade used for the soft design checks in the UI.
+#[derive(Resource, Debug, Clone, Copy)]
+pub(crate) struct DesignSpeedSettings {
+	pub design_speed_kmh: f32,
+	pub max_cant_mm: f32,
+	/// Steepest rate cant may be run in along a transition, in mm per meter.
+	pub max_cant_gradient: f32,
+	/// Ruling grade as rise over run; steeper track is flagged in the gradient debug view.
+	pub max_gradient: f32,
+}
+
+impl Default for DesignSpeedSettings {
+	fn default() -> Self {
+		Self {
+			design_speed_kmh: 80.0,
+			max_cant_mm: 150.0,
+			max_cant_gradient: 2.5,
+			max_gradient: 0.025,
+		}
+	}
+}
 
 pub(crate) fn enforce_alignment_constraints(mut alignment_state: ResMut<AlignmentState>) {
-	for alignment in alignment_state.alignments.values_mut() {
-		path_constraints::enforce_alignment_constraints(alignment);
+	if !alignment_state.is_changed() {
+		return;
+	}
+	// Only flag a change when clamping actually altered something, so the geometry cache
+	// isn't invalidated every frame.
+	let mut changed = false;
+	for alignment in alignment_state
+		.bypass_change_detection()
+		.alignments
+		.values_mut()
+	{
+		let before = alignment.clone();
+		path_constraints::enforce_alignment_constraints(alignment, &GeometryTolerances::DEFAULT);
+		changed |= *alignment != before;
+	}
+	if changed {
+		alignment_state.set_changed();
 	}
 }
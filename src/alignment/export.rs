@@ -0,0 +1,204 @@
+use std::path::Path;
+
+use alignment_path::{
+	Alignment, ElevationProfile, HeightSampler, TerrainSampledProfile, VerticalProfileData,
+	calculate_alignment_geometry,
+};
+use bevy::prelude::*;
+use serde_json::{Value, json};
+
+use super::state::AlignmentState;
+
+/// Station spacing of exported centerlines, in meters.
+const CENTERLINE_SAMPLE_SPACING: f32 = 5.0;
+
+/// Centerline of `alignment` every [`CENTERLINE_SAMPLE_SPACING`] meters as `(station, point)`,
+/// with Y taken from its vertical profile.
+pub(crate) fn centerline_samples<H: HeightSampler>(
+	alignment: &Alignment,
+	sampler: &H,
+) -> Vec<(f64, Vec3)> {
+	let geometry = calculate_alignment_geometry(alignment.start, alignment.end, alignment);
+	let terrain_profile;
+	let profile: &dyn ElevationProfile = match &alignment.vertical_profile {
+		VerticalProfileData::TerrainSampled => {
+			terrain_profile = TerrainSampledProfile {
+				sampler,
+				horizontal: &geometry,
+			};
+			&terrain_profile
+		}
+		VerticalProfileData::Pvi(pvi) => pvi,
+	};
+	geometry
+		.sample_by_station(CENTERLINE_SAMPLE_SPACING)
+		.into_iter()
+		.map(|(station, xz)| {
+			let point = Vec3::new(xz.x, profile.elevation_at(station), xz.y);
+			(f64::from(station), point)
+		})
+		.collect()
+}
+
+/// Where the world origin sits in the projected CRS the GIS expects. World X maps to easting and
+/// world Z to northing.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) struct GeoOrigin {
+	pub easting: f64,
+	pub northing: f64,
+	/// Treat world +Z as south, so northing decreases as Z grows.
+	pub flip_z: bool,
+}
+
+impl GeoOrigin {
+	/// GeoJSON position of a world point: `[easting, northing, elevation]`.
+	fn position(&self, point: Vec3) -> Value {
+		let northing = if self.flip_z {
+			self.northing - f64::from(point.z)
+		} else {
+			self.northing + f64::from(point.z)
+		};
+		json!([
+			self.easting + f64::from(point.x),
+			northing,
+			f64::from(point.y)
+		])
+	}
+}
+
+/// LineString feature through `samples`, with the station and elevation of every vertex added to
+/// `properties`.
+fn centerline_feature(samples: &[(f64, Vec3)], origin: &GeoOrigin, mut properties: Value) -> Value {
+	let stations: Vec<f64> = samples.iter().map(|(station, _)| *station).collect();
+	let elevations: Vec<f64> = samples
+		.iter()
+		.map(|(_, point)| f64::from(point.y))
+		.collect();
+	properties["stations"] = json!(stations);
+	properties["elevations"] = json!(elevations);
+	let coordinates: Vec<Value> = samples
+		.iter()
+		.map(|(_, point)| origin.position(*point))
+		.collect();
+	json!({
+		"type": "Feature",
+		"geometry": {
+			"type": "LineString",
+			"coordinates": coordinates,
+		},
+		"properties": properties,
+	})
+}
+
+/// Writes one centerline as a GeoJSON LineString feature, translated into real-world coordinates.
+pub(crate) fn export_geojson(
+	samples: &[(f64, Vec3)],
+	origin: &GeoOrigin,
+	path: &Path,
+) -> anyhow::Result<()> {
+	let feature = centerline_feature(samples, origin, json!({}));
+	std::fs::write(path, serde_json::to_string_pretty(&feature)?)?;
+	Ok(())
+}
+
+/// One feature per alignment, in ID order, tagged with its ID, turn count and length.
+fn alignments_feature_collection<H: HeightSampler>(
+	alignment_state: &AlignmentState,
+	sampler: &H,
+	origin: &GeoOrigin,
+) -> Value {
+	let mut ids: Vec<_> = alignment_state.alignments.keys().copied().collect();
+	ids.sort_unstable();
+	let features: Vec<Value> = ids
+		.into_iter()
+		.map(|id| {
+			let alignment = &alignment_state.alignments[&id];
+			let samples = centerline_samples(alignment, sampler);
+			let length = samples.last().map_or(0.0, |(station, _)| *station);
+			let properties = json!({
+				"id": id,
+				"turn_count": alignment.turn_count(),
+				"total_length": length,
+			});
+			centerline_feature(&samples, origin, properties)
+		})
+		.collect();
+	json!({
+		"type": "FeatureCollection",
+		"features": features,
+	})
+}
+
+/// Writes every alignment, computed against the current terrain, into one GeoJSON file.
+pub(crate) fn export_all_geojson<H: HeightSampler>(
+	alignment_state: &AlignmentState,
+	sampler: &H,
+	origin: &GeoOrigin,
+	path: &Path,
+) -> anyhow::Result<()> {
+	let collection = alignments_feature_collection(alignment_state, sampler, origin);
+	std::fs::write(path, serde_json::to_string_pretty(&collection)?)?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use alignment_path::FlatHeightSampler;
+
+	use super::*;
+
+	#[test]
+	fn every_alignment_becomes_a_tagged_feature() {
+		let mut alignment_state = AlignmentState::default();
+		alignment_state.alignments.insert(
+			3,
+			Alignment::new(Vec3::ZERO, Vec3::new(300.0, 0.0, 300.0), 2),
+		);
+		alignment_state
+			.alignments
+			.insert(1, Alignment::new(Vec3::ZERO, Vec3::new(100.0, 0.0, 0.0), 0));
+
+		let collection = alignments_feature_collection(
+			&alignment_state,
+			&FlatHeightSampler(7.0),
+			&GeoOrigin::default(),
+		);
+		let features = collection["features"].as_array().unwrap();
+		assert_eq!(features.len(), 2);
+		assert_eq!(features[0]["properties"]["id"], 1);
+		assert_eq!(features[0]["properties"]["turn_count"], 0);
+		assert_eq!(features[1]["properties"]["turn_count"], 2);
+
+		let straight = &features[0];
+		let coordinates = straight["geometry"]["coordinates"].as_array().unwrap();
+		assert_eq!(coordinates.first().unwrap(), &json!([0.0, 0.0, 7.0]));
+		assert_eq!(coordinates.last().unwrap(), &json!([100.0, 0.0, 7.0]));
+		assert_eq!(straight["properties"]["total_length"], 100.0);
+		assert_eq!(
+			straight["properties"]["stations"].as_array().unwrap().len(),
+			coordinates.len()
+		);
+	}
+
+	#[test]
+	fn positions_are_offset_by_the_origin() {
+		let samples = [(0.0, Vec3::new(10.0, 3.0, 20.0))];
+		let mut origin = GeoOrigin {
+			easting: 500_000.0,
+			northing: 4_000_000.0,
+			flip_z: false,
+		};
+		let feature = centerline_feature(&samples, &origin, json!({}));
+		assert_eq!(
+			feature["geometry"]["coordinates"][0],
+			json!([500_010.0, 4_000_020.0, 3.0])
+		);
+		assert_eq!(feature["properties"]["elevations"], json!([3.0]));
+
+		origin.flip_z = true;
+		assert_eq!(
+			origin.position(samples[0].1),
+			json!([500_010.0, 3_999_980.0, 3.0])
+		);
+	}
+}
@@ -0,0 +1,107 @@
+use bevy::asset::RenderAssetUsages;
+use bevy::mesh::{Indices, PrimitiveTopology};
+use bevy::prelude::*;
+
+use crate::terrain::{self, DeferredMeshRemovals, calculate_terrain_height};
+
+use super::render::CachedAlignmentGeometry;
+
+const FOOTPRINT_COLOR: Color = Color::srgba(0.95, 0.75, 0.2, 0.35);
+/// Lift above the terrain so the overlay doesn't z-fight with it.
+const FOOTPRINT_LIFT: f32 = 0.5;
+
+/// Corridor swept by the track, drawn as a translucent band over the terrain.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct FootprintSettings {
+	pub enabled: bool,
+	/// Distance from the centerline to either edge of the corridor.
+	pub half_width: f32,
+}
+
+impl Default for FootprintSettings {
+	fn default() -> Self {
+		Self {
+			enabled: false,
+			half_width: 7.5,
+		}
+	}
+}
+
+#[derive(Component)]
+pub(crate) struct FootprintOverlay;
+
+/// Triangle strip between matching right and left offset points, draped on the terrain.
+fn build_footprint_mesh(right: &[Vec3], left: &[Vec3]) -> Mesh {
+	let positions: Vec<[f32; 3]> = right
+		.iter()
+		.zip(left)
+		.flat_map(|(r, l)| [r.to_array(), l.to_array()])
+		.collect();
+	let normals = vec![[0.0, 1.0, 0.0]; positions.len()];
+	let quads = (positions.len() / 2).saturating_sub(1) as u32;
+	let indices = (0..quads)
+		.flat_map(|i| {
+			let (r0, l0, r1, l1) = (2 * i, 2 * i + 1, 2 * i + 2, 2 * i + 3);
+			[r0, l0, r1, r1, l0, l1]
+		})
+		.collect();
+	Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::all())
+		.with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+		.with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+		.with_inserted_indices(Indices::U32(indices))
+}
+
+pub(crate) fn update_footprint_overlay(
+	mut commands: Commands,
+	mut meshes: ResMut<Assets<Mesh>>,
+	mut materials: ResMut<Assets<StandardMaterial>>,
+	settings: Res<FootprintSettings>,
+	geometry_cache: Res<CachedAlignmentGeometry>,
+	terrain_heightmap: Single<&terrain::HeightMap>,
+	terrain_settings: Res<terrain::Settings>,
+	mut removals: ResMut<DeferredMeshRemovals>,
+	existing: Query<(Entity, &Mesh3d), With<FootprintOverlay>>,
+	mut material: Local<Option<Handle<StandardMaterial>>>,
+) {
+	if !settings.is_changed() && !geometry_cache.is_changed() {
+		return;
+	}
+	for (entity, mesh) in &existing {
+		removals.defer(mesh.0.clone());
+		commands.entity(entity).despawn();
+	}
+	if !settings.enabled {
+		return;
+	}
+	let Some((_, _, geometry)) = geometry_cache.get() else {
+		return;
+	};
+	let drape = |lateral: f32| -> Vec<Vec3> {
+		geometry
+			.offset(lateral)
+			.into_iter()
+			.map(|point| {
+				let height = calculate_terrain_height(point, &terrain_heightmap, &terrain_settings);
+				point.with_y(height + FOOTPRINT_LIFT)
+			})
+			.collect()
+	};
+	let half_width = settings.half_width.abs();
+	let mesh = build_footprint_mesh(&drape(half_width), &drape(-half_width));
+	// The look never changes, so every rebuild shares one material
+	let material = material.get_or_insert_with(|| {
+		materials.add(StandardMaterial {
+			base_color: FOOTPRINT_COLOR,
+			alpha_mode: AlphaMode::Blend,
+			unlit: true,
+			double_sided: true,
+			cull_mode: None,
+			..default()
+		})
+	});
+	commands.spawn((
+		Mesh3d(meshes.add(mesh)),
+		MeshMaterial3d(material.clone()),
+		FootprintOverlay,
+	));
+}
@@ -0,0 +1,237 @@
+use alignment_path::Alignment;
+use bevy::color::palettes::css::*;
+use bevy::prelude::*;
+use bevy_egui::egui;
+use std::collections::VecDeque;
+
+use crate::pin::PinDragState;
+
+use super::components::AlignmentGizmos;
+use super::state::{AlignmentId, AlignmentState};
+
+/// Snapshots kept across all alignments before the oldest is dropped.
+const HISTORY_LIMIT: usize = 64;
+/// How long the alignments must stay put before an edit is recorded.
+const HISTORY_SETTLE_SECS: f32 = 0.5;
+/// Vertices closer than this to a vertex of the snapshot count as unchanged.
+const DIFF_TOLERANCE: f32 = 0.01;
+const CHANGED_COLOR: Srgba = ORANGE;
+const UNCHANGED_COLOR: Color = Color::srgba(0.7, 0.7, 0.7, 0.35);
+const SNAPSHOT_COLOR: Color = Color::srgba(0.5, 0.5, 0.5, 0.5);
+const VERTEX_MARKER_RADIUS: f32 = 12.0;
+
+/// Past versions of the alignments, recorded whenever an edit settles.
+#[derive(Resource, Default)]
+pub(crate) struct AlignmentHistory {
+	snapshots: VecDeque<(AlignmentId, Alignment)>,
+	/// How many versions back to compare the current alignment against, if at all.
+	pub compare_steps_back: Option<usize>,
+}
+
+impl AlignmentHistory {
+	fn versions(&self, id: AlignmentId) -> impl DoubleEndedIterator<Item = &Alignment> {
+		self
+			.snapshots
+			.iter()
+			.filter(move |(snapshot_id, _)| *snapshot_id == id)
+			.map(|(_, alignment)| alignment)
+	}
+
+	/// Number of recorded versions of an alignment, including the latest one.
+	pub(crate) fn version_count(&self, id: AlignmentId) -> usize {
+		self.versions(id).count()
+	}
+
+	/// The version `steps_back` edits before the latest, which matches the current alignment.
+	pub(crate) fn snapshot(&self, id: AlignmentId, steps_back: usize) -> Option<&Alignment> {
+		self.versions(id).rev().nth(steps_back)
+	}
+
+	fn record(&mut self, id: AlignmentId, alignment: &Alignment) {
+		if self.snapshot(id, 0) == Some(alignment) {
+			return;
+		}
+		if self.snapshots.len() == HISTORY_LIMIT {
+			self.snapshots.pop_front();
+		}
+		self.snapshots.push_back((id, alignment.clone()));
+	}
+}
+
+/// How a control point of the current alignment differs from a snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum VertexChange {
+	Unchanged,
+	/// Not in the snapshot, which had at least as many vertices.
+	Moved,
+	/// Not in the snapshot, which had fewer vertices.
+	Added,
+}
+
+/// Per control point of `current` (endpoints included), whether it changed since `snapshot`.
+/// Vertices are matched by position, so inserting one doesn't flag everything after it.
+pub(crate) fn diff_vertices(snapshot: &Alignment, current: &Alignment) -> Vec<VertexChange> {
+	let old_points = snapshot.control_points();
+	let new_points = current.control_points();
+	let unmatched = if new_points.len() > old_points.len() {
+		VertexChange::Added
+	} else {
+		VertexChange::Moved
+	};
+	new_points
+		.iter()
+		.map(|point| {
+			if old_points
+				.iter()
+				.any(|old| old.distance(*point) <= DIFF_TOLERANCE)
+			{
+				VertexChange::Unchanged
+			} else {
+				unmatched
+			}
+		})
+		.collect()
+}
+
+pub(crate) fn record_alignment_history(
+	time: Res<Time>,
+	alignment_state: Res<AlignmentState>,
+	drag_state: Res<PinDragState>,
+	mut history: ResMut<AlignmentHistory>,
+	mut settle: Local<Option<Timer>>,
+) {
+	// Restart the wait on every change and for as long as a pin is held, so a dragged pin or
+	// slider records one snapshot once it is let go
+	if alignment_state.is_changed() || drag_state.is_dragging_any() {
+		*settle = Some(Timer::from_seconds(HISTORY_SETTLE_SECS, TimerMode::Once));
+		return;
+	}
+	let Some(timer) = settle.as_mut() else {
+		return;
+	};
+	if !timer.tick(time.delta()).just_finished() {
+		return;
+	}
+	*settle = None;
+	let id = alignment_state.current_alignment;
+	if let Some(alignment) = alignment_state.alignments.get(&id) {
+		history.record(id, alignment);
+	}
+}
+
+pub(crate) fn draw_alignment_diff(
+	history: Res<AlignmentHistory>,
+	alignment_state: Res<AlignmentState>,
+	mut gizmos: Gizmos<AlignmentGizmos>,
+) {
+	let Some(steps_back) = history.compare_steps_back else {
+		return;
+	};
+	let id = alignment_state.current_alignment;
+	let (Some(current), Some(snapshot)) = (
+		alignment_state.alignments.get(&id),
+		history.snapshot(id, steps_back),
+	) else {
+		return;
+	};
+
+	gizmos.linestrip(snapshot.control_points(), SNAPSHOT_COLOR);
+
+	let points = current.control_points();
+	let changes = diff_vertices(snapshot, current);
+	let changed = |index: usize| changes[index] != VertexChange::Unchanged;
+	for (index, leg) in points.windows(2).enumerate() {
+		let color = if changed(index) || changed(index + 1) {
+			CHANGED_COLOR.into()
+		} else {
+			UNCHANGED_COLOR
+		};
+		gizmos.line(leg[0], leg[1], color);
+	}
+	for (index, point) in points.iter().enumerate() {
+		let color = if changed(index) {
+			CHANGED_COLOR.into()
+		} else {
+			UNCHANGED_COLOR
+		};
+		gizmos.sphere(
+			Isometry3d::from_translation(*point),
+			VERTEX_MARKER_RADIUS,
+			color,
+		);
+	}
+}
+
+pub(crate) fn history_diff_ui(
+	ui: &mut egui::Ui,
+	history: &mut AlignmentHistory,
+	current_id: AlignmentId,
+) {
+	let previous_versions = history.version_count(current_id).saturating_sub(1);
+	ui.horizontal(|ui| {
+		let mut comparing = history.compare_steps_back.is_some();
+		ui.add_enabled_ui(previous_versions > 0, |ui| {
+			if ui
+				.checkbox(&mut comparing, "Compare with history")
+				.changed()
+			{
+				history.compare_steps_back = comparing.then_some(1);
+			}
+		});
+		if let Some(steps_back) = &mut history.compare_steps_back {
+			*steps_back = (*steps_back).clamp(1, previous_versions.max(1));
+			ui.add(
+				egui::DragValue::new(steps_back)
+					.range(1..=previous_versions.max(1))
+					.suffix(" edits back"),
+			);
+		}
+	});
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use alignment_path::GeometryTolerances;
+
+	#[test]
+	fn diff_flags_moved_and_added_vertices() {
+		let snapshot = Alignment::new(Vec3::ZERO, Vec3::new(300.0, 0.0, 0.0), 2);
+		let mut current = snapshot.clone();
+		current.set_segment_control_point(1, Vec3::new(200.0, 0.0, 50.0), &GeometryTolerances::DEFAULT);
+		assert_eq!(
+			diff_vertices(&snapshot, &current),
+			[
+				VertexChange::Unchanged,
+				VertexChange::Unchanged,
+				VertexChange::Moved,
+				VertexChange::Unchanged,
+			]
+		);
+
+		current.insert_turn(0, Vec3::new(50.0, 0.0, -40.0), &GeometryTolerances::DEFAULT);
+		assert_eq!(
+			diff_vertices(&snapshot, &current),
+			[
+				VertexChange::Unchanged,
+				VertexChange::Added,
+				VertexChange::Unchanged,
+				VertexChange::Added,
+				VertexChange::Unchanged,
+			]
+		);
+	}
+
+	#[test]
+	fn history_skips_unchanged_snapshots() {
+		let mut history = AlignmentHistory::default();
+		let mut alignment = Alignment::new(Vec3::ZERO, Vec3::X * 100.0, 1);
+		history.record(0, &alignment);
+		history.record(0, &alignment);
+		alignment.end = Vec3::X * 120.0;
+		history.record(0, &alignment);
+		assert_eq!(history.version_count(0), 2);
+		assert_eq!(history.snapshot(0, 0), Some(&alignment));
+		assert_eq!(history.snapshot(0, 1).map(|a| a.end), Some(Vec3::X * 100.0));
+	}
+}
@@ -0,0 +1,126 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::saveable::SaveableSettings;
+
+use super::state::{AlignmentId, AlignmentState};
+
+/// Which end of an alignment a point of interest is bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub(crate) enum Endpoint {
+	Start,
+	End,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct BoundEndpoint {
+	pub alignment_id: AlignmentId,
+	pub endpoint: Endpoint,
+}
+
+/// A named, reusable place such as "North Yard" that alignment endpoints can be pinned to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct PointOfInterest {
+	pub name: String,
+	pub position: Vec3,
+	/// Endpoints that follow this point when it moves.
+	#[serde(default)]
+	pub bound: Vec<BoundEndpoint>,
+}
+
+#[derive(Resource, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct PointsOfInterest {
+	pub points: Vec<PointOfInterest>,
+	/// Name typed for the next point to add.
+	#[serde(skip)]
+	pub new_name: String,
+}
+
+impl SaveableSettings for PointsOfInterest {
+	fn filename() -> &'static str {
+		"points_of_interest.json"
+	}
+}
+
+impl PointsOfInterest {
+	/// Index of the point `endpoint` is bound to, if any.
+	pub(crate) fn bound_to(&self, endpoint: BoundEndpoint) -> Option<usize> {
+		self
+			.points
+			.iter()
+			.position(|point| point.bound.contains(&endpoint))
+	}
+
+	/// Bind `endpoint` to the point at `index`, or unbind it with `None`. An endpoint follows at
+	/// most one point.
+	pub(crate) fn bind(&mut self, endpoint: BoundEndpoint, index: Option<usize>) {
+		for point in &mut self.points {
+			point.bound.retain(|bound| *bound != endpoint);
+		}
+		if let Some(point) = index.and_then(|index| self.points.get_mut(index)) {
+			point.bound.push(endpoint);
+		}
+	}
+}
+
+/// Move every bound alignment endpoint onto its point of interest.
+pub(crate) fn apply_poi_bindings(
+	points_of_interest: Res<PointsOfInterest>,
+	mut alignment_state: ResMut<AlignmentState>,
+) {
+	if !points_of_interest.is_changed() {
+		return;
+	}
+	for point in &points_of_interest.points {
+		for bound in &point.bound {
+			let Some(alignment) = alignment_state.alignments.get(&bound.alignment_id) else {
+				continue;
+			};
+			let current = match bound.endpoint {
+				Endpoint::Start => alignment.start,
+				Endpoint::End => alignment.end,
+			};
+			if current == point.position {
+				continue;
+			}
+			// Only touch the state when something moves, so history and caches stay quiet
+			if let Some(alignment) = alignment_state.alignments.get_mut(&bound.alignment_id) {
+				match bound.endpoint {
+					Endpoint::Start => alignment.start = point.position,
+					Endpoint::End => alignment.end = point.position,
+				}
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn depot(bound: Vec<BoundEndpoint>) -> PointOfInterest {
+		PointOfInterest {
+			name: "Depot".to_owned(),
+			position: Vec3::new(10.0, 0.0, 20.0),
+			bound,
+		}
+	}
+
+	#[test]
+	fn an_endpoint_binds_to_one_point_at_a_time() {
+		let start = BoundEndpoint {
+			alignment_id: 1,
+			endpoint: Endpoint::Start,
+		};
+		let mut points = PointsOfInterest {
+			points: vec![depot(vec![start]), depot(Vec::new())],
+			..Default::default()
+		};
+		points.bind(start, Some(1));
+		assert_eq!(points.bound_to(start), Some(1));
+		assert!(points.points[0].bound.is_empty());
+
+		points.bind(start, None);
+		assert_eq!(points.bound_to(start), None);
+	}
+}
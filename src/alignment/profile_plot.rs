@@ -0,0 +1,477 @@
+use alignment_path::{AlignmentGeometry, ElevationProfile};
+use bevy::prelude::*;
+use bevy_egui::egui;
+
+use crate::units::Units;
+
+const PLOT_WIDTH: u32 = 320;
+const PLOT_HEIGHT: u32 = 120;
+/// Smallest vertical span the plot will stretch to in auto mode.
+const MIN_AUTO_RANGE: f32 = 1e-5;
+/// Roughly how many vertical station gridlines the plot aims for.
+const TARGET_STATION_TICKS: f32 = 5.0;
+const GRID_COLOR: egui::Color32 = egui::Color32::from_gray(60);
+const AXIS_LABEL_COLOR: egui::Color32 = egui::Color32::from_gray(170);
+/// Line colors for overlaid profiles, the current alignment first.
+const SERIES_COLORS: [egui::Color32; 6] = [
+	egui::Color32::RED,
+	egui::Color32::LIGHT_BLUE,
+	egui::Color32::GREEN,
+	egui::Color32::YELLOW,
+	egui::Color32::from_rgb(255, 128, 255),
+	egui::Color32::from_rgb(255, 165, 0),
+];
+const GROUND_COLOR: egui::Color32 = egui::Color32::from_rgb(160, 120, 80);
+/// Ground above the track, to be dug out.
+const CUT_COLOR: egui::Color32 = egui::Color32::from_rgba_premultiplied(110, 40, 30, 110);
+/// Track above the ground, to be built up.
+const FILL_COLOR: egui::Color32 = egui::Color32::from_rgba_premultiplied(30, 60, 110, 110);
+
+/// Vertical extent of the elevation profile plot.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ProfileYRange {
+	/// Fit the plot to the sampled elevations.
+	Auto,
+	/// Keep the plot at a fixed elevation window so flat profiles look flat.
+	Fixed { min: f32, max: f32 },
+}
+
+#[derive(Resource, Debug, Clone)]
+pub(crate) struct ProfilePlotSettings {
+	pub y_range: ProfileYRange,
+	/// Other alignments whose profiles are overlaid on the current one.
+	pub compare_ids: Vec<usize>,
+	/// Plot the terrain under the current alignment and shade cut and fill against it.
+	pub show_ground: bool,
+}
+
+impl Default for ProfilePlotSettings {
+	fn default() -> Self {
+		Self {
+			y_range: ProfileYRange::Auto,
+			compare_ids: Vec::new(),
+			show_ground: true,
+		}
+	}
+}
+
+/// One line on the profile plot.
+pub(crate) struct ProfileSeries {
+	pub label: String,
+	pub samples: Vec<(f32, f32)>,
+}
+
+/// One elevation window shared by every series and the ground line so they can be compared
+/// directly.
+pub(crate) fn shared_y_range(
+	y_range: ProfileYRange,
+	series: &[ProfileSeries],
+	ground: &[(f32, f32)],
+) -> (f32, f32) {
+	y_range.resolve(
+		series
+			.iter()
+			.flat_map(|s| s.samples.iter())
+			.chain(ground)
+			.map(|(_, elevation)| *elevation),
+	)
+}
+
+/// Whether the ground lies above the track (cut) or below it (fill).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Earthwork {
+	Cut,
+	Fill,
+}
+
+/// Convex `(station, elevation)` polygons between the track and the ground, which must be sampled
+/// at the same stations. Steps where the two cross are split at the crossing so each piece is
+/// wholly cut or fill.
+pub(crate) fn earthwork_polygons(
+	track: &[(f32, f32)],
+	ground: &[(f32, f32)],
+) -> Vec<(Earthwork, Vec<(f32, f32)>)> {
+	let kind = |difference: f32| {
+		if difference > 0.0 {
+			Earthwork::Cut
+		} else {
+			Earthwork::Fill
+		}
+	};
+	let mut polygons = Vec::new();
+	for (t, g) in track.windows(2).zip(ground.windows(2)) {
+		let (station0, station1) = (t[0].0, t[1].0);
+		// Ground minus track at either end of the step
+		let (d0, d1) = (g[0].1 - t[0].1, g[1].1 - t[1].1);
+		if d0 == 0.0 && d1 == 0.0 {
+			continue;
+		}
+		if d0 * d1 >= 0.0 {
+			polygons.push((kind(d0 + d1), vec![t[0], t[1], g[1], g[0]]));
+			continue;
+		}
+		let s = d0 / (d0 - d1);
+		let crossing = (
+			station0 + (station1 - station0) * s,
+			t[0].1 + (t[1].1 - t[0].1) * s,
+		);
+		polygons.push((kind(d0), vec![t[0], crossing, g[0]]));
+		polygons.push((kind(d1), vec![crossing, t[1], g[1]]));
+	}
+	polygons
+}
+
+impl ProfileYRange {
+	/// Resolve to a `(min, max)` elevation window for the given samples.
+	pub(crate) fn resolve(self, elevations: impl IntoIterator<Item = f32>) -> (f32, f32) {
+		match self {
+			Self::Auto => {
+				let (min, max) = elevations.into_iter().fold(
+					(f32::INFINITY, f32::NEG_INFINITY),
+					|(min, max), elevation| (min.min(elevation), max.max(elevation)),
+				);
+				if !min.is_finite() || !max.is_finite() {
+					return (0.0, MIN_AUTO_RANGE);
+				}
+				(min, max.max(min + MIN_AUTO_RANGE))
+			}
+			Self::Fixed { min, max } => (min.min(max), min.max(max)),
+		}
+	}
+}
+
+/// Pixel row (0 at the top) an elevation is drawn at inside a plot `height_px` tall.
+pub(crate) fn elevation_to_row(elevation: f32, range: (f32, f32), height_px: u32) -> u32 {
+	let (min, max) = range;
+	let span = (max - min).max(MIN_AUTO_RANGE);
+	let t = ((elevation - min) / span).clamp(0.0, 1.0);
+	let max_row = height_px.saturating_sub(1) as f32;
+	(max_row - t * max_row).round() as u32
+}
+
+/// A 1, 2 or 5 times power-of-ten spacing giving about `TARGET_STATION_TICKS` ticks over `span`.
+pub(crate) fn tick_interval(span: f32) -> f32 {
+	if !span.is_finite() || span <= 0.0 {
+		return 1.0;
+	}
+	let raw = span / TARGET_STATION_TICKS;
+	let magnitude = 10f32.powi(raw.log10().floor() as i32);
+	let step = [1.0, 2.0, 5.0, 10.0]
+		.into_iter()
+		.find(|step| step * magnitude >= raw)
+		.unwrap_or(10.0);
+	step * magnitude
+}
+
+/// Horizontal gridlines at the bottom, middle and top of the elevation window, labelled in the
+/// display units, and vertical gridlines at round station intervals labelled along the bottom edge.
+fn draw_profile_grid(
+	painter: &egui::Painter,
+	rect: egui::Rect,
+	range: (f32, f32),
+	total_station: f32,
+	units: Units,
+) {
+	let stroke = egui::Stroke::new(1.0_f32, GRID_COLOR);
+	let font = egui::FontId::monospace(10.0);
+	let (min, max) = range;
+	for (elevation, anchor) in [
+		(min, egui::Align2::LEFT_BOTTOM),
+		(0.5 * (min + max), egui::Align2::LEFT_CENTER),
+		(max, egui::Align2::LEFT_TOP),
+	] {
+		let y = rect.top() + elevation_to_row(elevation, range, PLOT_HEIGHT) as f32;
+		painter.hline(rect.x_range(), y, stroke);
+		painter.text(
+			egui::pos2(rect.left() + 2.0, y),
+			anchor,
+			units.format_length(f64::from(elevation)),
+			font.clone(),
+			AXIS_LABEL_COLOR,
+		);
+	}
+	if total_station <= 0.0 {
+		return;
+	}
+	let interval = tick_interval(total_station);
+	let ticks = (total_station / interval).floor() as usize;
+	for i in 1..=ticks {
+		let station = interval * i as f32;
+		let x = rect.left() + station / total_station * rect.width();
+		painter.vline(x, rect.y_range(), stroke);
+		painter.text(
+			egui::pos2(x - 2.0, rect.bottom() - 2.0),
+			egui::Align2::RIGHT_BOTTOM,
+			format!("{station:.0}"),
+			font.clone(),
+			AXIS_LABEL_COLOR,
+		);
+	}
+}
+
+/// Sample `(station, elevation)` pairs at `count` evenly spaced stations, with stations in meters
+/// of arc length from the start so plot ticks and exports can label them directly.
+pub(crate) fn sample_profile(
+	geometry: &AlignmentGeometry,
+	profile: &dyn ElevationProfile,
+	count: usize,
+) -> Vec<(f32, f32)> {
+	let total = geometry.total_length();
+	if count < 2 || total <= 0.0 {
+		return Vec::new();
+	}
+	(0..count)
+		.map(|i| {
+			let station = total * i as f32 / (count - 1) as f32;
+			(station, profile.elevation_at(station))
+		})
+		.collect()
+}
+
+/// Plot `series` with the current alignment first. `ground` is the terrain under the current
+/// alignment at the same stations, or empty to leave it out.
+pub(crate) fn elevation_profile_plot_ui(
+	ui: &mut egui::Ui,
+	series: &[ProfileSeries],
+	ground: &[(f32, f32)],
+	settings: &mut ProfilePlotSettings,
+	units: Units,
+) {
+	let range = shared_y_range(settings.y_range, series, ground);
+
+	ui.horizontal(|ui| {
+		let mut auto = settings.y_range == ProfileYRange::Auto;
+		if ui.checkbox(&mut auto, "Auto Y range").changed() {
+			settings.y_range = if auto {
+				ProfileYRange::Auto
+			} else {
+				ProfileYRange::Fixed {
+					min: range.0,
+					max: range.1,
+				}
+			};
+		}
+		if let ProfileYRange::Fixed { min, max } = &mut settings.y_range {
+			ui.label("Min:");
+			ui.add(units.length_drag_value(min).speed(1.0));
+			ui.label("Max:");
+			ui.add(units.length_drag_value(max).speed(1.0));
+		}
+	});
+	ui.checkbox(&mut settings.show_ground, "Ground line with cut and fill");
+
+	let (response, painter) = ui.allocate_painter(
+		egui::vec2(PLOT_WIDTH as f32, PLOT_HEIGHT as f32),
+		egui::Sense::hover(),
+	);
+	let rect = response.rect;
+	painter.rect_filled(rect, 0.0, egui::Color32::BLACK);
+
+	// Shared station axis so a shorter alignment also draws shorter
+	let total_station = series
+		.iter()
+		.filter_map(|s| s.samples.last().map(|(station, _)| *station))
+		.fold(0.0, f32::max);
+	draw_profile_grid(&painter, rect, range, total_station, units);
+	if total_station <= 0.0 {
+		return;
+	}
+	let to_screen = |(station, elevation): &(f32, f32)| {
+		egui::pos2(
+			rect.left() + station / total_station * rect.width(),
+			rect.top() + elevation_to_row(*elevation, range, PLOT_HEIGHT) as f32,
+		)
+	};
+	if let Some(track) = series.first() {
+		for (earthwork, polygon) in earthwork_polygons(&track.samples, ground) {
+			let color = match earthwork {
+				Earthwork::Cut => CUT_COLOR,
+				Earthwork::Fill => FILL_COLOR,
+			};
+			painter.add(egui::Shape::convex_polygon(
+				polygon.iter().map(to_screen).collect(),
+				color,
+				egui::Stroke::NONE,
+			));
+		}
+	}
+	if ground.len() >= 2 {
+		painter.add(egui::Shape::line(
+			ground.iter().map(to_screen).collect(),
+			egui::Stroke::new(1.0_f32, GROUND_COLOR),
+		));
+	}
+	for (s, color) in series.iter().zip(SERIES_COLORS.iter().cycle()) {
+		if s.samples.len() < 2 {
+			continue;
+		}
+		let points = s.samples.iter().map(to_screen).collect();
+		painter.add(egui::Shape::line(
+			points,
+			egui::Stroke::new(1.5_f32, *color),
+		));
+	}
+	if !ground.is_empty() {
+		ui.horizontal(|ui| {
+			ui.colored_label(GROUND_COLOR, "— Ground");
+			ui.colored_label(CUT_COLOR.to_opaque(), "■ Cut");
+			ui.colored_label(FILL_COLOR.to_opaque(), "■ Fill");
+		});
+	}
+
+	if series.len() > 1 {
+		ui.horizontal_wrapped(|ui| {
+			for (s, color) in series.iter().zip(SERIES_COLORS.iter().cycle()) {
+				ui.colored_label(*color, format!("— {}", s.label));
+			}
+		});
+	}
+}
+
+/// Curvature against station, centred on zero so left and right turns read the same way.
+pub(crate) fn curvature_plot_ui(ui: &mut egui::Ui, samples: &[(f32, f32)]) {
+	let (response, painter) = ui.allocate_painter(
+		egui::vec2(PLOT_WIDTH as f32, PLOT_HEIGHT as f32),
+		egui::Sense::hover(),
+	);
+	let rect = response.rect;
+	painter.rect_filled(rect, 0.0, egui::Color32::BLACK);
+
+	let peak = samples
+		.iter()
+		.map(|(_, curvature)| curvature.abs())
+		.fold(MIN_AUTO_RANGE, f32::max);
+	let range = (-peak, peak);
+	let zero_row = rect.top() + elevation_to_row(0.0, range, PLOT_HEIGHT) as f32;
+	painter.hline(
+		rect.x_range(),
+		zero_row,
+		egui::Stroke::new(1.0_f32, egui::Color32::DARK_GRAY),
+	);
+
+	let total_station = samples.last().map_or(0.0, |(station, _)| *station);
+	if samples.len() < 2 || total_station <= 0.0 {
+		return;
+	}
+	let points = samples
+		.iter()
+		.map(|(station, curvature)| {
+			egui::pos2(
+				rect.left() + station / total_station * rect.width(),
+				rect.top() + elevation_to_row(*curvature, range, PLOT_HEIGHT) as f32,
+			)
+		})
+		.collect();
+	painter.add(egui::Shape::line(
+		points,
+		egui::Stroke::new(1.5_f32, SERIES_COLORS[0]),
+	));
+	ui.label(format!("Peak curvature: {peak:.5} 1/m"));
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	struct StationAsElevation;
+	impl ElevationProfile for StationAsElevation {
+		fn elevation_at(&self, station: f32) -> f32 {
+			station
+		}
+	}
+
+	#[test]
+	fn profile_samples_carry_true_stations() {
+		let alignment = alignment_path::Alignment::new(Vec3::ZERO, Vec3::new(120.0, 0.0, 0.0), 0);
+		let geometry =
+			alignment_path::calculate_alignment_geometry(alignment.start, alignment.end, &alignment);
+		let samples = sample_profile(&geometry, &StationAsElevation, 5);
+		let stations: Vec<f32> = samples.iter().map(|(station, _)| *station).collect();
+		assert_eq!(stations, vec![0.0, 30.0, 60.0, 90.0, 120.0]);
+		assert!(
+			samples
+				.iter()
+				.all(|(station, elevation)| station == elevation)
+		);
+	}
+
+	#[test]
+	fn tick_interval_rounds_to_one_two_five() {
+		assert_eq!(tick_interval(1000.0), 200.0);
+		assert_eq!(tick_interval(420.0), 100.0);
+		assert_eq!(tick_interval(60.0), 20.0);
+		assert_eq!(tick_interval(25.0), 5.0);
+		assert_eq!(tick_interval(0.0), 1.0);
+	}
+
+	#[test]
+	fn fixed_range_draws_constant_profile_flat() {
+		let range = ProfileYRange::Fixed {
+			min: 0.0,
+			max: 100.0,
+		}
+		.resolve([42.0; 4]);
+		let rows: Vec<u32> = (0..64)
+			.map(|i| elevation_to_row(42.0 + (i % 2) as f32 * 1e-4, range, PLOT_HEIGHT))
+			.collect();
+		assert!(
+			rows.iter().all(|row| *row == rows[0]),
+			"constant profile should map to a single row, got {rows:?}"
+		);
+	}
+
+	#[test]
+	fn auto_range_stretches_small_variation() {
+		let elevations = [42.0, 42.0001];
+		let range = ProfileYRange::Auto.resolve(elevations);
+		let top = elevation_to_row(elevations[1], range, PLOT_HEIGHT);
+		let bottom = elevation_to_row(elevations[0], range, PLOT_HEIGHT);
+		assert_eq!(top, 0);
+		assert_eq!(bottom, PLOT_HEIGHT - 1);
+	}
+
+	#[test]
+	fn overlaid_profiles_share_one_y_range() {
+		let series = [
+			ProfileSeries {
+				label: "A".to_string(),
+				samples: vec![(0.0, 10.0), (100.0, 30.0)],
+			},
+			ProfileSeries {
+				label: "B".to_string(),
+				samples: vec![(0.0, -5.0), (80.0, 20.0)],
+			},
+		];
+		assert_eq!(
+			shared_y_range(ProfileYRange::Auto, &series, &[]),
+			(-5.0, 30.0)
+		);
+		assert_eq!(
+			shared_y_range(ProfileYRange::Auto, &series, &[(50.0, 45.0)]),
+			(-5.0, 45.0)
+		);
+	}
+
+	#[test]
+	fn earthwork_splits_where_the_ground_crosses_the_track() {
+		let track = [(0.0, 10.0), (10.0, 10.0), (20.0, 10.0)];
+		let ground = [(0.0, 14.0), (10.0, 12.0), (20.0, 8.0)];
+		let polygons = earthwork_polygons(&track, &ground);
+
+		let kinds: Vec<Earthwork> = polygons.iter().map(|(kind, _)| *kind).collect();
+		assert_eq!(kinds, [Earthwork::Cut, Earthwork::Cut, Earthwork::Fill]);
+		// The second step crosses halfway, at station 15
+		assert_eq!(polygons[1].1[1], (15.0, 10.0));
+		assert_eq!(polygons[2].1[0], (15.0, 10.0));
+	}
+
+	#[test]
+	fn fixed_range_tolerates_swapped_bounds() {
+		let range = ProfileYRange::Fixed {
+			min: 10.0,
+			max: -10.0,
+		}
+		.resolve([]);
+		assert_eq!(range, (-10.0, 10.0));
+	}
+}
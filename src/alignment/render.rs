@@ -1,60 +1,537 @@
 use alignment_path::{
-	CurveSegment, ElevationProfile, GeometrySegment, HeightSampler, TerrainSampledProfile,
-	VerticalProfileData, calculate_alignment_geometry,
+	AlignmentGeometry, CurveSegment, ElevationProfile, GeometrySegment, GeometryTolerances,
+	HeightSampler, TerrainSampledProfile, VerticalProfileData, calculate_alignment_geometry,
 };
+use bevy::asset::RenderAssetUsages;
 use bevy::color::palettes::css::*;
+use bevy::mesh::{Indices, PrimitiveTopology};
 use bevy::picking::{
 	backend::ray::RayMap,
 	mesh_picking::ray_cast::{MeshRayCast, MeshRayCastSettings},
 	pointer::PointerId,
 };
+use bevy::platform::time::Instant;
 use bevy::prelude::*;
+use bevy_egui::{EguiContexts, egui};
+use std::collections::VecDeque;
+use std::time::Duration;
 
 use crate::camera::PrimaryCamera3d;
-use crate::terrain::{self, calculate_terrain_height};
+use crate::terrain::{self, DeferredMeshRemovals, calculate_terrain_height};
+use crate::units::Units;
 
-use super::GeometryDebugLevel;
 use super::components::{AlignmentGizmos, AlignmentPoint, PointType};
+use super::constraints::DesignSpeedSettings;
 use super::state::{
 	AlignmentState, DraftAlignment, TangentSnapSettings, TrackBuildingMode, build_preview_alignment,
 	snapped_segment_end_with_lock, snapped_tangent_direction_with_lock,
 };
+use super::{GRADIENT_DEBUG_LEVEL, GeometryDebugLevel, MAX_ARC_RADIUS, MIN_ARC_RADIUS};
 use crate::terrain::{HeightMap, TerrainMesh};
 
-const CURVE_RESOLUTION: u32 = 16;
+/// Arc length between gizmo samples on clothoids and arcs, in meters.
+const CURVE_SAMPLE_SPACING: f32 = 2.0;
+const MIN_CURVE_RESOLUTION: u32 = 4;
+const MAX_CURVE_RESOLUTION: u32 = 512;
+
+/// Number of gizmo line pieces for a curve `length` meters long.
+fn curve_resolution(length: f32) -> u32 {
+	let pieces = (length.abs() / CURVE_SAMPLE_SPACING).ceil();
+	if pieces.is_finite() {
+		(pieces as u32).clamp(MIN_CURVE_RESOLUTION, MAX_CURVE_RESOLUTION)
+	} else {
+		MIN_CURVE_RESOLUTION
+	}
+}
+
+/// Evenly spaced curve parameters in `0..=1`, dense enough for a curve `length` meters long.
+fn curve_samples(length: f32) -> impl Iterator<Item = f32> {
+	let resolution = curve_resolution(length);
+	(0..=resolution).map(move |i| i as f32 / resolution as f32)
+}
+
+/// Gradient color: white when level, shading to red uphill and blue downhill as `grade` nears
+/// `max_gradient`. Past the ruling grade uphill and downhill both turn red, alternating with
+/// white when `flash_on` is false so it blinks.
+pub(crate) fn gradient_to_color(grade: f32, max_gradient: f32, flash_on: bool) -> Color {
+	if grade.abs() > max_gradient {
+		return if flash_on {
+			Color::srgb(1.0, 0.0, 0.0)
+		} else {
+			Color::WHITE
+		};
+	}
+	let t = (grade.abs() / max_gradient.max(f32::EPSILON)).clamp(0.0, 1.0);
+	if grade >= 0.0 {
+		Color::srgb(1.0, 1.0 - t, 1.0 - t)
+	} else {
+		Color::srgb(1.0 - t, 1.0 - t, 1.0)
+	}
+}
+
+/// Arc color from red at `MIN_ARC_RADIUS` to green at `MAX_ARC_RADIUS`. The ramp is logarithmic
+/// so the tight radii that matter get most of the color range.
+pub(crate) fn radius_to_color(radius: f32) -> Color {
+	let t = ((radius / MIN_ARC_RADIUS).max(1.0).ln() / (MAX_ARC_RADIUS / MIN_ARC_RADIUS).ln())
+		.clamp(0.0, 1.0);
+	Color::srgb(1.0 - t, t, 0.0)
+}
+/// Station spacing of the gradient-colored centerline, in meters.
+const GRADIENT_SAMPLE_SPACING: f32 = 5.0;
+/// Lift of the gradient-colored centerline so it isn't hidden by the regular path gizmos.
+const GRADIENT_LINE_LIFT: f32 = 1.0;
+/// Blinks per second of track steeper than the ruling grade.
+const OVER_GRADE_FLASH_HZ: f32 = 2.0;
+const SELF_INTERSECTION_MARKER_RADIUS: f32 = 15.0;
 const TANGENT_RAY_DASH_LENGTH: f32 = 16.0;
 const TANGENT_RAY_GAP_LENGTH: f32 = 10.0;
 const TANGENT_RAY_EXTENT_MULTIPLIER: f32 = 12.0;
 const TANGENT_RAY_MIN_LENGTH: f32 = 8_000.0;
 const TANGENT_RAY_COLOR: Color = Color::srgba(0.22, 1.0, 0.08, 0.7);
+const OFFSET_TRACK_COLOR: Srgba = ORANGE;
+/// Shortest station label interval, so labels can't flood the screen.
+pub(crate) const MIN_STATION_LABEL_INTERVAL: f32 = 10.0;
+const STATION_POST_HEIGHT: f32 = 25.0;
+const RIBBON_RESOLUTION_PER_SEGMENT: u32 = 64;
+/// Lift above the terrain so the ribbon doesn't z-fight with it.
+const RIBBON_LIFT: f32 = 0.3;
+const RIBBON_COLOR: Color = Color::srgb(0.45, 0.42, 0.38);
+const STATION_POST_COLOR: Srgba = WHITE;
+/// Frames averaged by each [`RollingTiming`].
+const TIMING_WINDOW: usize = 60;
+
+/// Draws a second centerline parallel to the current alignment, e.g. for double track.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct OffsetTrackSettings {
+	pub enabled: bool,
+	/// Lateral distance from the centerline, positive to the right of travel direction.
+	pub lateral_m: f32,
+}
+
+impl Default for OffsetTrackSettings {
+	fn default() -> Self {
+		Self {
+			enabled: false,
+			lateral_m: 4.5,
+		}
+	}
+}
+
+/// Visually scales how far the clothoids stray from their tangents, for teaching. Not to scale.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct SpiralExaggerationSettings {
+	pub factor: f32,
+}
+
+impl Default for SpiralExaggerationSettings {
+	fn default() -> Self {
+		Self { factor: 1.0 }
+	}
+}
+
+/// Solid, lit track bed along the current alignment, instead of only gizmo lines.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct TrackRibbonSettings {
+	pub enabled: bool,
+	pub half_width: f32,
+}
+
+impl Default for TrackRibbonSettings {
+	fn default() -> Self {
+		Self {
+			enabled: false,
+			half_width: 2.5,
+		}
+	}
+}
+
+#[derive(Component)]
+pub(crate) struct TrackRibbon;
+
+/// Kilometer posts: a marker and a station label every `interval_m` along the alignment.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct StationLabelSettings {
+	pub enabled: bool,
+	pub interval_m: f32,
+}
+
+impl Default for StationLabelSettings {
+	fn default() -> Self {
+		Self {
+			enabled: false,
+			interval_m: 1000.0,
+		}
+	}
+}
+
+/// Rolling average over the last [`TIMING_WINDOW`] samples of one timed section.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RollingTiming {
+	samples: VecDeque<Duration>,
+}
+
+impl RollingTiming {
+	pub(crate) fn push(&mut self, elapsed: Duration) {
+		if self.samples.len() == TIMING_WINDOW {
+			self.samples.pop_front();
+		}
+		self.samples.push_back(elapsed);
+	}
+
+	pub(crate) fn average_micros(&self) -> Option<f64> {
+		if self.samples.is_empty() {
+			return None;
+		}
+		let total: Duration = self.samples.iter().sum();
+		Some(total.as_micros() as f64 / self.samples.len() as f64)
+	}
+}
+
+/// Per-frame cost of the alignment render. Only present when
+/// [`super::ALIGNMENT_RENDER_TIMINGS`] is enabled.
+#[derive(Resource, Debug, Clone, Default)]
+pub(crate) struct AlignmentRenderTimings {
+	/// Horizontal geometry for the current alignment and the draft preview.
+	pub geometry: RollingTiming,
+	/// Gizmo drawing.
+	pub draw: RollingTiming,
+}
+
+/// Render-only knobs for [`draw_alignment_geometry`].
+#[derive(Debug, Clone, Copy)]
+struct AlignmentDrawOptions {
+	debug_level: u8,
+	offset_lateral: Option<f32>,
+	spiral_exaggeration: f32,
+}
+
+/// Geometry of the current alignment, recomputed only when its inputs change.
+#[derive(Resource, Default)]
+pub(crate) struct CachedAlignmentGeometry {
+	/// Alignment id and start/end pin positions the geometry was built from.
+	key: Option<(usize, Vec3, Vec3)>,
+	geometry: Option<AlignmentGeometry>,
+	/// Where the geometry crosses itself, on the XZ plane.
+	self_intersections: Vec<Vec3>,
+}
+
+impl CachedAlignmentGeometry {
+	pub(crate) fn get(&self) -> Option<(Vec3, Vec3, &AlignmentGeometry)> {
+		let (_, start, end) = self.key?;
+		Some((start, end, self.geometry.as_ref()?))
+	}
+}
+
+pub(crate) fn update_alignment_geometry_cache(
+	mut cache: ResMut<CachedAlignmentGeometry>,
+	alignment_state: Res<AlignmentState>,
+	alignment_pins: Query<(&Transform, &AlignmentPoint)>,
+	changed_pins: Query<(), (Changed<Transform>, With<AlignmentPoint>)>,
+	changed_heightmap: Query<(), Changed<HeightMap>>,
+	timings: Option<ResMut<AlignmentRenderTimings>>,
+) {
+	let inputs_changed = alignment_state.is_changed()
+		|| !changed_pins.is_empty()
+		|| !changed_heightmap.is_empty()
+		|| cache.key.is_none();
+	if !inputs_changed {
+		return;
+	}
+
+	let current_id = alignment_state.current_alignment;
+	let endpoints = get_start_and_end_points(&alignment_state, alignment_pins);
+	let key = endpoints.map(|(start, end)| (current_id, start, end));
+	// Pin transforms also change when they are rescaled for the camera distance; only a moved
+	// endpoint or an edited alignment needs new geometry.
+	if !alignment_state.is_changed() && changed_heightmap.is_empty() && key == cache.key {
+		return;
+	}
+
+	cache.key = key;
+	let started = timings.is_some().then(Instant::now);
+	cache.geometry = endpoints.and_then(|(start, end)| {
+		alignment_state
+			.alignments
+			.get(&current_id)
+			.map(|alignment| calculate_alignment_geometry(start, end, alignment))
+	});
+	let self_intersections: Vec<Vec3> = cache.geometry.as_ref().map_or_else(Vec::new, |geometry| {
+		geometry
+			.find_self_intersections()
+			.into_iter()
+			.map(|(_, _, point)| point)
+			.collect()
+	});
+	// Dragging a pin rebuilds the geometry every frame; only report when the crossings come or go
+	if !self_intersections.is_empty() && self_intersections.len() != cache.self_intersections.len() {
+		warn!(
+			"Alignment {current_id} crosses itself {} time(s)",
+			self_intersections.len()
+		);
+	}
+	cache.self_intersections = self_intersections;
+	if let (Some(mut timings), Some(started)) = (timings, started) {
+		timings.geometry.push(started.elapsed());
+	}
+}
+
+/// Marks every place the current alignment crosses itself.
+pub(crate) fn draw_self_intersections(
+	mut gizmos: Gizmos<AlignmentGizmos>,
+	geometry_cache: Res<CachedAlignmentGeometry>,
+	terrain_heightmap: Single<&terrain::HeightMap>,
+	terrain_settings: Res<terrain::Settings>,
+) {
+	for point in &geometry_cache.self_intersections {
+		let height = calculate_terrain_height(*point, &terrain_heightmap, &terrain_settings);
+		gizmos.sphere(
+			Isometry3d::from_translation(point.with_y(height)),
+			SELF_INTERSECTION_MARKER_RADIUS,
+			RED,
+		);
+	}
+}
+
+/// Stations at whole multiples of `interval` from the start, with their terrain-draped positions.
+fn station_posts(
+	geometry: &AlignmentGeometry,
+	interval: f32,
+	heightmap: &terrain::HeightMap,
+	settings: &terrain::Settings,
+) -> Vec<(f32, Vec3)> {
+	let interval = interval.max(MIN_STATION_LABEL_INTERVAL);
+	let total = geometry.total_length();
+	geometry
+		.sample_by_station(interval)
+		.into_iter()
+		// The last sample is the end of the alignment, which is rarely on a whole interval
+		.filter(|(station, _)| *station < total || total % interval == 0.0)
+		.map(|(station, xz)| {
+			let position = Vec3::new(xz.x, 0.0, xz.y);
+			let height = calculate_terrain_height(position, heightmap, settings);
+			(station, position.with_y(height))
+		})
+		.collect()
+}
+
+pub(crate) fn draw_station_posts(
+	mut gizmos: Gizmos<AlignmentGizmos>,
+	settings: Res<StationLabelSettings>,
+	geometry_cache: Res<CachedAlignmentGeometry>,
+	terrain_heightmap: Single<&terrain::HeightMap>,
+	terrain_settings: Res<terrain::Settings>,
+) {
+	if !settings.enabled {
+		return;
+	}
+	let Some((_, _, geometry)) = geometry_cache.get() else {
+		return;
+	};
+	for (_, base) in station_posts(
+		geometry,
+		settings.interval_m,
+		&terrain_heightmap,
+		&terrain_settings,
+	) {
+		gizmos.line(
+			base,
+			base + Vec3::Y * STATION_POST_HEIGHT,
+			STATION_POST_COLOR,
+		);
+	}
+}
+
+/// Station labels drawn on screen at the top of each post, so they always face the camera.
+pub(crate) fn draw_station_labels(
+	mut contexts: EguiContexts,
+	settings: Res<StationLabelSettings>,
+	units: Res<Units>,
+	geometry_cache: Res<CachedAlignmentGeometry>,
+	terrain_heightmap: Single<&terrain::HeightMap>,
+	terrain_settings: Res<terrain::Settings>,
+	camera: Single<(&Camera, &GlobalTransform), With<PrimaryCamera3d>>,
+) {
+	if !settings.enabled {
+		return;
+	}
+	let Some((_, _, geometry)) = geometry_cache.get() else {
+		return;
+	};
+	let Ok(ctx) = contexts.ctx_mut() else {
+		return;
+	};
+	let (camera, camera_transform) = *camera;
+	let painter = ctx.layer_painter(egui::LayerId::background());
+	for (station, base) in station_posts(
+		geometry,
+		settings.interval_m,
+		&terrain_heightmap,
+		&terrain_settings,
+	) {
+		let top = base + Vec3::Y * STATION_POST_HEIGHT;
+		let Ok(screen) = camera.world_to_viewport(camera_transform, top) else {
+			continue;
+		};
+		painter.text(
+			egui::pos2(screen.x, screen.y),
+			egui::Align2::CENTER_BOTTOM,
+			units.format_station(f64::from(station)),
+			egui::FontId::monospace(12.0),
+			egui::Color32::WHITE,
+		);
+	}
+}
 
-struct TerrainHeightSampler<'a> {
-	heightmap: &'a terrain::HeightMap,
-	settings: &'a terrain::Settings,
+struct LiftedSampler<'a>(TerrainHeightSampler<'a>);
+
+impl HeightSampler for LiftedSampler<'_> {
+	fn height_at(&self, position: Vec3) -> f32 {
+		self.0.height_at(position) + RIBBON_LIFT
+	}
+}
+
+pub(crate) fn update_track_ribbon(
+	mut commands: Commands,
+	mut meshes: ResMut<Assets<Mesh>>,
+	mut materials: ResMut<Assets<StandardMaterial>>,
+	settings: Res<TrackRibbonSettings>,
+	geometry_cache: Res<CachedAlignmentGeometry>,
+	terrain_heightmap: Single<&terrain::HeightMap>,
+	terrain_settings: Res<terrain::Settings>,
+	mut removals: ResMut<DeferredMeshRemovals>,
+	existing: Query<(Entity, &Mesh3d), With<TrackRibbon>>,
+) {
+	if !settings.is_changed() && !geometry_cache.is_changed() {
+		return;
+	}
+	for (entity, mesh) in &existing {
+		removals.defer(mesh.0.clone());
+		commands.entity(entity).despawn();
+	}
+	if !settings.enabled {
+		return;
+	}
+	let Some((_, _, geometry)) = geometry_cache.get() else {
+		return;
+	};
+	let sampler = LiftedSampler(TerrainHeightSampler {
+		heightmap: &terrain_heightmap,
+		settings: &terrain_settings,
+	});
+	let ribbon =
+		geometry.build_ribbon_mesh(settings.half_width, RIBBON_RESOLUTION_PER_SEGMENT, &sampler);
+	let mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::all())
+		.with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, ribbon.positions)
+		.with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, ribbon.normals)
+		.with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, ribbon.uvs)
+		.with_inserted_indices(Indices::U32(ribbon.indices));
+	commands.spawn((
+		Mesh3d(meshes.add(mesh)),
+		MeshMaterial3d(materials.add(StandardMaterial {
+			base_color: RIBBON_COLOR,
+			perceptual_roughness: 0.9,
+			..default()
+		})),
+		TrackRibbon,
+	));
+}
+
+pub(super) struct TerrainHeightSampler<'a> {
+	pub heightmap: &'a terrain::HeightMap,
+	pub settings: &'a terrain::Settings,
 }
 
 impl<'a> HeightSampler for TerrainHeightSampler<'a> {
 	fn height_at(&self, position: Vec3) -> f32 {
-		calculate_terrain_height(position, self.heightmap, self.settings)
+		self.heightmap.sample_world(position, self.settings)
+	}
+}
+
+/// Redraws the current alignment's centerline colored by gradient between consecutive samples.
+pub(crate) fn draw_gradient_profile(
+	mut gizmos: Gizmos<AlignmentGizmos>,
+	alignment_state: Res<AlignmentState>,
+	geometry_cache: Res<CachedAlignmentGeometry>,
+	geometry_debug_level: Res<GeometryDebugLevel>,
+	design_speed: Res<DesignSpeedSettings>,
+	terrain_heightmap: Single<&terrain::HeightMap>,
+	terrain_settings: Res<terrain::Settings>,
+	time: Res<Time>,
+) {
+	if geometry_debug_level.0 < GRADIENT_DEBUG_LEVEL {
+		return;
+	}
+	let Some((_, _, geometry)) = geometry_cache.get() else {
+		return;
+	};
+	let Some(alignment) = alignment_state
+		.alignments
+		.get(&alignment_state.current_alignment)
+	else {
+		return;
+	};
+	let sampler = TerrainHeightSampler {
+		heightmap: &terrain_heightmap,
+		settings: &terrain_settings,
+	};
+	let terrain_profile;
+	let profile: &dyn ElevationProfile = match &alignment.vertical_profile {
+		VerticalProfileData::TerrainSampled => {
+			terrain_profile = TerrainSampledProfile {
+				sampler: &sampler,
+				horizontal: geometry,
+			};
+			&terrain_profile
+		}
+		VerticalProfileData::Pvi(pvi) => pvi,
+	};
+	let flash_on = (time.elapsed_secs() * OVER_GRADE_FLASH_HZ).fract() < 0.5;
+	let samples: Vec<(f32, Vec3)> = geometry
+		.sample_by_station(GRADIENT_SAMPLE_SPACING)
+		.into_iter()
+		.map(|(station, xz)| {
+			let y = profile.elevation_at(station) + GRADIENT_LINE_LIFT;
+			(station, Vec3::new(xz.x, y, xz.y))
+		})
+		.collect();
+	for pair in samples.windows(2) {
+		let ((s0, p0), (s1, p1)) = (pair[0], pair[1]);
+		let run = s1 - s0;
+		if run <= 0.0 {
+			continue;
+		}
+		let grade = (p1.y - p0.y) / run;
+		gizmos.line(
+			p0,
+			p1,
+			gradient_to_color(grade, design_speed.max_gradient, flash_on),
+		);
 	}
 }
 
 pub(crate) fn render_alignment_path(
 	mut gizmos: Gizmos<AlignmentGizmos>,
 	alignment_state: Res<AlignmentState>,
-	alignment_pins: Query<(&Transform, &AlignmentPoint)>,
+	geometry_cache: Res<CachedAlignmentGeometry>,
 	geometry_debug_level: Res<GeometryDebugLevel>,
 	terrain_heightmap: Single<&terrain::HeightMap>,
 	terrain_settings: Res<terrain::Settings>,
 	track_building_mode: Res<TrackBuildingMode>,
 	draft_alignment: Res<DraftAlignment>,
 	snap_settings: Res<TangentSnapSettings>,
+	offset_settings: Res<OffsetTrackSettings>,
+	spiral_exaggeration: Res<SpiralExaggerationSettings>,
 	terrain_mesh: Single<Entity, With<TerrainMesh>>,
 	ray_map: Res<RayMap>,
 	mut raycast: MeshRayCast,
 	camera_query: Single<Entity, With<PrimaryCamera3d>>,
+	mut timings: Option<ResMut<AlignmentRenderTimings>>,
 ) {
-	let geometry_debug_level = geometry_debug_level.0;
+	let options = AlignmentDrawOptions {
+		debug_level: geometry_debug_level.0,
+		offset_lateral: offset_settings.enabled.then_some(offset_settings.lateral_m),
+		spiral_exaggeration: spiral_exaggeration.factor,
+	};
 	let heightmap = *terrain_heightmap;
 	let sampler = TerrainHeightSampler {
 		heightmap: &heightmap,
@@ -65,19 +542,24 @@ pub(crate) fn render_alignment_path(
 		&& draft_alignment.active_alignment_id.is_none();
 
 	if !hide_current_alignment {
-		if let Some((start, end)) = get_start_and_end_points(&alignment_state, alignment_pins)
+		if let Some((start, end, geometry)) = geometry_cache.get()
 			&& let Some(alignment) = alignment_state
 				.alignments
 				.get(&alignment_state.current_alignment)
 		{
+			let started = timings.is_some().then(Instant::now);
 			draw_alignment_geometry(
 				&mut gizmos,
 				start,
 				end,
 				alignment,
-				geometry_debug_level,
+				geometry,
 				&sampler,
+				options,
 			);
+			if let (Some(timings), Some(started)) = (timings.as_mut(), started) {
+				timings.draw.push(started.elapsed());
+			}
 		}
 	}
 
@@ -130,15 +612,32 @@ pub(crate) fn render_alignment_path(
 		draft_alignment.previous_tangent,
 		*snap_settings,
 	);
-	alignment_path::constraints::enforce_alignment_constraints(&mut preview_alignment);
+	alignment_path::constraints::enforce_alignment_constraints(
+		&mut preview_alignment,
+		&GeometryTolerances::DEFAULT,
+	);
+	let started = timings.is_some().then(Instant::now);
+	let preview_geometry =
+		calculate_alignment_geometry(preview_start, preview_end, &preview_alignment);
+	if let (Some(timings), Some(started)) = (timings.as_mut(), started) {
+		timings.geometry.push(started.elapsed());
+	}
+	let started = timings.is_some().then(Instant::now);
 	draw_alignment_geometry(
 		&mut gizmos,
 		preview_start,
 		preview_end,
 		&preview_alignment,
-		geometry_debug_level,
+		&preview_geometry,
 		&sampler,
+		AlignmentDrawOptions {
+			offset_lateral: None,
+			..options
+		},
 	);
+	if let (Some(timings), Some(started)) = (timings.as_mut(), started) {
+		timings.draw.push(started.elapsed());
+	}
 }
 
 fn cursor_terrain_position(
@@ -172,22 +671,32 @@ fn draw_alignment_geometry<H: HeightSampler>(
 	start: Vec3,
 	end: Vec3,
 	alignment: &alignment_path::Alignment,
-	geometry_debug_level: u8,
+	alignment_geometry: &AlignmentGeometry,
 	sampler: &H,
+	options: AlignmentDrawOptions,
 ) {
-	let alignment_geometry = calculate_alignment_geometry(start, end, alignment);
+	let geometry_debug_level = options.debug_level;
+	let exaggeration = options.spiral_exaggeration;
 	let terrain_profile;
 	let profile: &dyn ElevationProfile = match &alignment.vertical_profile {
 		VerticalProfileData::TerrainSampled => {
 			terrain_profile = TerrainSampledProfile {
 				sampler,
-				horizontal: &alignment_geometry,
+				horizontal: alignment_geometry,
 			};
 			&terrain_profile
 		}
 		VerticalProfileData::Pvi(pvi) => pvi,
 	};
 
+	if let Some(lateral) = options.offset_lateral {
+		let offset_points = alignment_geometry
+			.offset_samples(lateral)
+			.into_iter()
+			.map(|(station, xz)| Vec3::new(xz.x, profile.elevation_at(station), xz.y));
+		gizmos.linestrip(offset_points, OFFSET_TRACK_COLOR);
+	}
+
 	// Degenerate fallback when the geometry pipeline has no drawable pieces.
 	if alignment_geometry.segments.is_empty() && geometry_debug_level >= 1 {
 		gizmos.line(start, end, AQUA);
@@ -222,26 +731,41 @@ fn draw_alignment_geometry<H: HeightSampler>(
 		}
 
 		let ingoing_params = segment.ingoing_clothoid;
+		let ingoing_origin = segment.ingoing_clothoid_start;
+		let ingoing_direction = segment.tangent_vertex - segment.ingoing_clothoid_start;
 		let profile_ref = &profile;
 		let ingoing_clothoid = FunctionCurve::new(Interval::UNIT, move |s| {
 			let y = profile_ref.elevation_at(ingoing_params.station_at(s));
-			ingoing_params.point_at(s, y)
+			exaggerate_lateral(
+				ingoing_params.point_at(s, y),
+				ingoing_origin,
+				ingoing_direction,
+				exaggeration,
+			)
 		});
-		draw_ingoing_clothoid(gizmos, ingoing_clothoid);
+		draw_ingoing_clothoid(gizmos, ingoing_clothoid, ingoing_params.length);
 
+		let outgoing_origin = segment.outgoing_clothoid_end;
+		let outgoing_direction = segment.outgoing_clothoid_end - segment.tangent_vertex;
 		if geometry_debug_level >= 1 {
 			let arc_geometry = segment.circular_arc;
 			let profile_ref = &profile;
 			let arc_function = FunctionCurve::new(Interval::UNIT, move |s| {
 				let station = arc_geometry.start_station + s * arc_geometry.length;
 				let y = profile_ref.elevation_at(station);
-				arc_geometry.point_at(s, y)
+				let point = arc_geometry.point_at(s, y);
+				// Blend from the ingoing tangent's exaggeration to the outgoing one, so the arc
+				// still meets both exaggerated clothoids
+				exaggerate_lateral(point, ingoing_origin, ingoing_direction, exaggeration).lerp(
+					exaggerate_lateral(point, outgoing_origin, outgoing_direction, exaggeration),
+					s,
+				)
 			});
 
 			gizmos.curve_3d(
 				arc_function,
-				(0..=CURVE_RESOLUTION).map(|i| i as f32 / CURVE_RESOLUTION as f32),
-				GREEN_YELLOW,
+				curve_samples(arc_geometry.length),
+				radius_to_color(arc_geometry.radius()),
 			);
 		}
 
@@ -267,32 +791,44 @@ fn draw_alignment_geometry<H: HeightSampler>(
 		let profile_ref = &profile;
 		let outgoing_clothoid = FunctionCurve::new(Interval::UNIT, move |s| {
 			let y = profile_ref.elevation_at(outgoing_params.station_at(s));
-			outgoing_params.point_at(s, y)
+			exaggerate_lateral(
+				outgoing_params.point_at(s, y),
+				outgoing_origin,
+				outgoing_direction,
+				exaggeration,
+			)
 		});
-		draw_outgoint_clothoid(gizmos, outgoing_clothoid);
+		draw_outgoint_clothoid(gizmos, outgoing_clothoid, outgoing_params.length);
+	}
+}
+
+/// Scales the horizontal distance of `point` from the tangent line through `origin` along
+/// `direction` by `factor`, keeping its elevation.
+fn exaggerate_lateral(point: Vec3, origin: Vec3, direction: Vec3, factor: f32) -> Vec3 {
+	let direction = direction.with_y(0.0).normalize_or_zero();
+	if factor == 1.0 || direction == Vec3::ZERO {
+		return point;
 	}
+	let offset = (point - origin).with_y(0.0);
+	let along = direction * offset.dot(direction);
+	let lateral = offset - along;
+	(origin + along + lateral * factor).with_y(point.y)
 }
 
 fn draw_outgoint_clothoid(
 	gizmos: &mut Gizmos<'_, '_, AlignmentGizmos>,
 	outgoing_clothoid: FunctionCurve<Vec3, impl Fn(f32) -> Vec3>,
+	length: f32,
 ) {
-	gizmos.curve_3d(
-		outgoing_clothoid,
-		(0..=CURVE_RESOLUTION).map(|i| i as f32 / CURVE_RESOLUTION as f32),
-		MAGENTA,
-	);
+	gizmos.curve_3d(outgoing_clothoid, curve_samples(length), MAGENTA);
 }
 
 fn draw_ingoing_clothoid(
 	gizmos: &mut Gizmos<'_, '_, AlignmentGizmos>,
 	ingoing_clothoid: FunctionCurve<Vec3, impl Fn(f32) -> Vec3>,
+	length: f32,
 ) {
-	gizmos.curve_3d(
-		ingoing_clothoid,
-		(0..=CURVE_RESOLUTION).map(|i| i as f32 / CURVE_RESOLUTION as f32),
-		MAGENTA,
-	);
+	gizmos.curve_3d(ingoing_clothoid, curve_samples(length), MAGENTA);
 }
 
 fn draw_dashed_tangent_ray(
@@ -360,3 +896,111 @@ fn get_start_and_end_points(
 	}
 	Some((start, end))
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn curve_resolution_follows_length_within_bounds() {
+		assert_eq!(curve_resolution(0.0), MIN_CURVE_RESOLUTION);
+		assert_eq!(curve_resolution(100.0), 50);
+		assert_eq!(curve_resolution(1.0e6), MAX_CURVE_RESOLUTION);
+		assert_eq!(curve_resolution(f32::NAN), MIN_CURVE_RESOLUTION);
+		let samples: Vec<f32> = curve_samples(100.0).collect();
+		assert_eq!(samples.len(), 51);
+		assert_eq!((samples[0], samples[50]), (0.0, 1.0));
+	}
+
+	#[test]
+	fn gradient_color_is_white_when_flat_and_red_past_the_ruling_grade() {
+		assert_eq!(
+			gradient_to_color(0.0, 0.02, true),
+			Color::srgb(1.0, 1.0, 1.0)
+		);
+		let uphill = gradient_to_color(0.01, 0.02, true).to_srgba();
+		assert!(uphill.red == 1.0 && uphill.blue < 1.0);
+		let downhill = gradient_to_color(-0.01, 0.02, true).to_srgba();
+		assert!(downhill.blue == 1.0 && downhill.red < 1.0);
+		for grade in [0.03, -0.03] {
+			assert_eq!(
+				gradient_to_color(grade, 0.02, true),
+				Color::srgb(1.0, 0.0, 0.0)
+			);
+			assert_eq!(gradient_to_color(grade, 0.02, false), Color::WHITE);
+		}
+	}
+
+	#[test]
+	fn radius_color_runs_from_red_when_tight_to_green_when_gentle() {
+		let tight = radius_to_color(MIN_ARC_RADIUS).to_srgba();
+		assert_eq!((tight.red, tight.green), (1.0, 0.0));
+		assert_eq!(
+			radius_to_color(0.5 * MIN_ARC_RADIUS),
+			radius_to_color(MIN_ARC_RADIUS)
+		);
+		let gentle = radius_to_color(MAX_ARC_RADIUS).to_srgba();
+		assert!((gentle.green - 1.0).abs() < 1e-6 && gentle.red.abs() < 1e-6);
+		let middle = radius_to_color(100.0).to_srgba();
+		assert!(middle.red > 0.0 && middle.green > 0.0);
+	}
+
+	#[test]
+	fn rolling_timing_averages_only_the_latest_window() {
+		let mut timing = RollingTiming::default();
+		assert_eq!(timing.average_micros(), None);
+		for _ in 0..TIMING_WINDOW {
+			timing.push(Duration::from_micros(1000));
+		}
+		assert_eq!(timing.average_micros(), Some(1000.0));
+		// Older samples fall out of the window as new ones arrive
+		for _ in 0..TIMING_WINDOW / 2 {
+			timing.push(Duration::from_micros(3000));
+		}
+		assert_eq!(timing.average_micros(), Some(2000.0));
+	}
+
+	#[test]
+	fn spiral_exaggeration_of_one_is_true_geometry() {
+		let point = Vec3::new(30.0, 5.0, 2.0);
+		let exaggerated = exaggerate_lateral(point, Vec3::ZERO, Vec3::X, 1.0);
+		assert_eq!(exaggerated, point);
+	}
+
+	#[test]
+	fn spiral_exaggeration_increases_lateral_deviation_monotonically() {
+		let origin = Vec3::new(10.0, 0.0, 10.0);
+		let direction = Vec3::new(1.0, 0.0, 1.0);
+		let point = Vec3::new(40.0, 3.0, 38.0);
+		let lateral_deviation = |factor: f32| {
+			let p = exaggerate_lateral(point, origin, direction, factor);
+			let offset = (p - origin).with_y(0.0);
+			offset.cross(direction.normalize()).length()
+		};
+		let mut previous = lateral_deviation(1.0);
+		for factor in [1.5, 2.0, 5.0, 10.0] {
+			let deviation = lateral_deviation(factor);
+			assert!(
+				deviation > previous,
+				"factor {factor} should increase deviation"
+			);
+			previous = deviation;
+		}
+	}
+
+	#[test]
+	fn station_posts_fall_on_whole_intervals_only() {
+		let settings = terrain::Settings::default();
+		let heightmap = terrain::HeightMap {
+			length_x: settings.grid_x(),
+			heights: vec![0.0; ((settings.grid_x() + 1) * (settings.grid_z() + 1)) as usize],
+		};
+		let alignment = alignment_path::Alignment::new(Vec3::ZERO, Vec3::X * 2_500.0, 0);
+		let geometry = calculate_alignment_geometry(alignment.start, alignment.end, &alignment);
+		let stations: Vec<f32> = station_posts(&geometry, 1_000.0, &heightmap, &settings)
+			.into_iter()
+			.map(|(station, _)| station)
+			.collect();
+		assert_eq!(stations, [0.0, 1_000.0, 2_000.0]);
+	}
+}
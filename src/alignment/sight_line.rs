@@ -0,0 +1,92 @@
+use alignment_path::{ElevationProfile, TerrainSampledProfile, VerticalProfileData};
+use bevy::color::palettes::css::{LIME, RED};
+use bevy::prelude::*;
+
+use crate::terrain::{self, line_of_sight};
+
+use super::components::AlignmentGizmos;
+use super::render::{CachedAlignmentGeometry, TerrainHeightSampler};
+use super::state::AlignmentState;
+
+/// Sight line between two stations of the current alignment, e.g. a signal and the point a
+/// driver first needs to see it from.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct SightLineSettings {
+	pub enabled: bool,
+	pub from_station: f32,
+	pub to_station: f32,
+	/// Height of the eye (or signal) above the track at both ends.
+	pub eye_height: f32,
+}
+
+impl Default for SightLineSettings {
+	fn default() -> Self {
+		Self {
+			enabled: false,
+			from_station: 0.0,
+			to_station: 200.0,
+			eye_height: 2.5,
+		}
+	}
+}
+
+/// Draws the sight line green when the two ends can see each other over the terrain, red when
+/// something is in the way.
+pub(crate) fn draw_sight_line(
+	mut gizmos: Gizmos<AlignmentGizmos>,
+	settings: Res<SightLineSettings>,
+	alignment_state: Res<AlignmentState>,
+	geometry_cache: Res<CachedAlignmentGeometry>,
+	terrain_heightmap: Single<&terrain::HeightMap>,
+	terrain_settings: Res<terrain::Settings>,
+) {
+	if !settings.enabled {
+		return;
+	}
+	let Some((_, _, geometry)) = geometry_cache.get() else {
+		return;
+	};
+	let Some(alignment) = alignment_state
+		.alignments
+		.get(&alignment_state.current_alignment)
+	else {
+		return;
+	};
+	let sampler = TerrainHeightSampler {
+		heightmap: &terrain_heightmap,
+		settings: &terrain_settings,
+	};
+	let terrain_profile;
+	let profile: &dyn ElevationProfile = match &alignment.vertical_profile {
+		VerticalProfileData::TerrainSampled => {
+			terrain_profile = TerrainSampledProfile {
+				sampler: &sampler,
+				horizontal: geometry,
+			};
+			&terrain_profile
+		}
+		VerticalProfileData::Pvi(pvi) => pvi,
+	};
+	let track_point = |station: f32| {
+		let xz = geometry.xz_at_station(station)?;
+		Some(Vec3::new(xz.x, profile.elevation_at(station), xz.y))
+	};
+	let (Some(a), Some(b)) = (
+		track_point(settings.from_station),
+		track_point(settings.to_station),
+	) else {
+		return;
+	};
+	let visible = line_of_sight(
+		a,
+		b,
+		&terrain_heightmap,
+		&terrain_settings,
+		settings.eye_height,
+	);
+	let color = if visible { LIME } else { RED };
+	let eye = Vec3::Y * settings.eye_height;
+	gizmos.line(a, a + eye, color);
+	gizmos.line(b, b + eye, color);
+	gizmos.line(a + eye, b + eye, color);
+}
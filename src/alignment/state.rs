@@ -1,10 +1,15 @@
-use alignment_path::{Alignment, MAX_ARC_RADIUS, PathSegment};
+use alignment_path::{
+	Alignment, GeometryTolerances, MAX_ARC_RADIUS, PathSegment, calculate_alignment_geometry,
+};
 use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 
+use super::MAX_TURNS;
 use crate::saveable::SaveableSettings;
 use crate::terrain;
+use crate::terrain::least_cost::{TraceKind, least_cost_path};
 use terrain::spatial::world_size_for_height;
 
 pub(crate) type AlignmentId = usize;
@@ -28,7 +33,7 @@ pub(crate) struct DraftAlignment {
 	pub active_alignment_id: Option<AlignmentId>,
 }
 
-#[derive(Resource, Serialize, Deserialize)]
+#[derive(Resource, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) struct AlignmentState {
 	/// The currently selected/visible alignment
 	pub current_alignment: AlignmentId,
@@ -66,6 +71,92 @@ impl AlignmentState {
 			.alignments
 			.insert(id, Alignment::new(start, end, n_tangents));
 	}
+
+	/// Insert a tangent vertex at `pos`, ordered among the existing ones by `at_station`.
+	/// Returns the new segment index, or `None` if the alignment already has [`MAX_TURNS`] turns.
+	pub(crate) fn insert_vertex(
+		&mut self,
+		id: AlignmentId,
+		at_station: f32,
+		pos: Vec3,
+	) -> Option<usize> {
+		let alignment = self.alignments.get_mut(&id)?;
+		if alignment.turn_count() >= MAX_TURNS {
+			return None;
+		}
+		let geometry = calculate_alignment_geometry(alignment.start, alignment.end, alignment);
+		let segment_index = (0..alignment.segments.len())
+			.filter_map(|segment_index| alignment.segment_control_point(segment_index))
+			.take_while(|point| {
+				geometry
+					.nearest_station(Vec2::new(point.x, point.z))
+					.is_some_and(|(station, _)| station < at_station)
+			})
+			.count();
+		alignment.insert_turn(segment_index, pos, &GeometryTolerances::DEFAULT);
+		Some(segment_index)
+	}
+
+	/// Remove an intermediate vertex. Returns whether anything was removed.
+	pub(crate) fn remove_vertex(&mut self, id: AlignmentId, segment_index: usize) -> bool {
+		self
+			.alignments
+			.get_mut(&id)
+			.and_then(|alignment| alignment.remove_segment(segment_index, &GeometryTolerances::DEFAULT))
+			.is_some()
+	}
+
+	/// Delete an alignment, selecting the next remaining one if it was current. The linear
+	/// alignment (key 0) can't be deleted. Returns whether anything was removed.
+	pub(crate) fn delete_alignment(&mut self, id: AlignmentId) -> bool {
+		if id == 0 || self.alignments.remove(&id).is_none() {
+			return false;
+		}
+		if self.current_alignment == id {
+			self.current_alignment = self
+				.alignments
+				.keys()
+				.copied()
+				.filter(|key| *key > id)
+				.min()
+				.unwrap_or(0);
+		}
+		true
+	}
+}
+
+/// Stops a dragged intermediate pin from passing its neighbours along the start→end axis, which
+/// would scramble the tangent order and fold the geometry. Off allows free placement.
+#[derive(Resource, Debug, Clone, Copy, Default)]
+pub(crate) struct PinOrderSettings {
+	pub maintain_order: bool,
+}
+
+/// Intermediate pins picked with Ctrl+click, for editing several turns at once. Selections only
+/// ever cover one alignment.
+#[derive(Resource, Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct SelectedPins {
+	pub alignment_id: AlignmentId,
+	/// Indices into the alignment's segments.
+	pub segments: BTreeSet<usize>,
+}
+
+impl SelectedPins {
+	/// Add the pin if it isn't selected, otherwise drop it. Picking a pin on another alignment
+	/// starts a fresh selection there.
+	pub(crate) fn toggle(&mut self, alignment_id: AlignmentId, segment_index: usize) {
+		if alignment_id != self.alignment_id {
+			self.alignment_id = alignment_id;
+			self.segments.clear();
+		}
+		if !self.segments.remove(&segment_index) {
+			self.segments.insert(segment_index);
+		}
+	}
+
+	pub(crate) fn clear(&mut self) {
+		self.segments.clear();
+	}
 }
 
 pub(crate) const MIN_SNAP_ANGLE_DEGREES: f32 = 0.1;
@@ -228,12 +319,15 @@ pub(crate) fn extend_alignment_with_preview(
 	let next_anchor = preview_turn
 		.map(|turn| turn.tangent_vertex)
 		.unwrap_or(segment_end);
-	alignment.append_segment_boundary(segment_start, next_anchor);
+	alignment.append_segment_boundary(segment_start, next_anchor, &GeometryTolerances::DEFAULT);
 	if let Some(turn) = preview_turn {
 		alignment.segments.push(PathSegment::Turn(turn));
 	}
 	alignment.end = segment_end;
-	alignment_path::constraints::enforce_alignment_constraints(alignment);
+	alignment_path::constraints::enforce_alignment_constraints(
+		alignment,
+		&GeometryTolerances::DEFAULT,
+	);
 }
 
 fn normalize_xz(vector: Vec3) -> Option<Vec3> {
@@ -273,7 +367,7 @@ fn configure_preview_turn_tangent_consumption(turn: Option<&mut alignment_path::
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use alignment_path::{GeometrySegment, calculate_alignment_geometry};
+	use alignment_path::{GeometrySegment, StraightSegment, TurnSegment};
 
 	fn assert_vec3_approx_eq(actual: Vec3, expected: Vec3) {
 		let delta = actual.distance(expected);
@@ -283,6 +377,109 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn inserted_vertex_lands_between_neighbours_by_station() {
+		let mut state = AlignmentState::default();
+		let start = Vec3::new(0.0, 0.0, 0.0);
+		let end = Vec3::new(900.0, 0.0, 0.0);
+		state.alignments.insert(
+			0,
+			Alignment {
+				start,
+				end,
+				segments: vec![
+					PathSegment::Turn(TurnSegment::new(Vec3::new(300.0, 0.0, 100.0))),
+					PathSegment::Turn(TurnSegment::new(Vec3::new(600.0, 0.0, -100.0))),
+				],
+				..Alignment::default()
+			},
+		);
+		let geometry = calculate_alignment_geometry(start, end, &state.alignments[&0]);
+		let midway = geometry.total_length() / 2.0;
+
+		let inserted = state.insert_vertex(0, midway, Vec3::new(450.0, 0.0, 0.0));
+		assert_eq!(inserted, Some(1));
+		let alignment = &state.alignments[&0];
+		assert_eq!(alignment.turn_count(), 3);
+		assert_vec3_approx_eq(
+			alignment.segment_control_point(1).unwrap(),
+			Vec3::new(450.0, 0.0, 0.0),
+		);
+		assert_vec3_approx_eq(
+			alignment.segment_control_point(2).unwrap(),
+			Vec3::new(600.0, 0.0, -100.0),
+		);
+
+		assert!(state.remove_vertex(0, 1));
+		assert!(!state.remove_vertex(0, 5));
+		assert_eq!(state.alignments[&0].turn_count(), 2);
+	}
+
+	#[test]
+	fn removing_a_turn_reprojects_straight_points_onto_new_span() {
+		let mut alignment = Alignment::new(Vec3::ZERO, Vec3::new(400.0, 0.0, 0.0), 1);
+		alignment.set_segment_control_point(
+			0,
+			Vec3::new(200.0, 0.0, 80.0),
+			&GeometryTolerances::DEFAULT,
+		);
+		alignment
+			.segments
+			.push(PathSegment::Straight(StraightSegment::from_fraction(0.5)));
+		assert_vec3_approx_eq(
+			alignment.segment_control_point(1).unwrap(),
+			Vec3::new(300.0, 0.0, 40.0),
+		);
+
+		alignment.remove_segment(0, &GeometryTolerances::DEFAULT);
+		assert_vec3_approx_eq(
+			alignment.segment_control_point(0).unwrap(),
+			Vec3::new(300.0, 0.0, 0.0),
+		);
+	}
+
+	#[test]
+	fn simplify_polyline_keeps_the_sharpest_corner() {
+		let points = [
+			Vec3::new(0.0, 0.0, 0.0),
+			Vec3::new(1.0, 0.0, 0.1),
+			Vec3::new(5.0, 0.0, 5.0),
+			Vec3::new(9.0, 0.0, 0.1),
+			Vec3::new(10.0, 0.0, 0.0),
+		];
+		let simplified = simplify_polyline(&points, 1, 0.5);
+		assert_eq!(simplified, vec![points[0], points[2], points[4]]);
+		assert_eq!(
+			simplify_polyline(&points, 8, 10.0),
+			vec![points[0], points[4]]
+		);
+	}
+
+	#[test]
+	fn delete_alignment_keeps_linear_and_selects_next() {
+		let mut state = AlignmentState::default();
+		for id in [0, 1, 2, 3] {
+			state.add_alignment(id, Vec3::ZERO, Vec3::X * 100.0, id);
+		}
+		state.current_alignment = 2;
+
+		assert!(!state.delete_alignment(0));
+		assert!(state.alignments.contains_key(&0));
+
+		assert!(state.delete_alignment(2));
+		assert_eq!(state.current_alignment, 3);
+		assert!(state.delete_alignment(3));
+		assert_eq!(state.current_alignment, 0);
+		assert!(!state.delete_alignment(3));
+	}
+
+	#[test]
+	fn insert_vertex_respects_max_turns() {
+		let mut state = AlignmentState::default();
+		state.add_alignment(0, Vec3::ZERO, Vec3::new(1000.0, 0.0, 0.0), MAX_TURNS);
+		assert_eq!(state.insert_vertex(0, 10.0, Vec3::new(5.0, 0.0, 5.0)), None);
+	}
+
 	#[test]
 	fn extend_alignment_preserves_preview_tangent_vertex() {
 		let segment_start = Vec3::new(0.0, 0.0, 0.0);
@@ -553,6 +750,19 @@ mod tests {
 			"curve should begin at the last straight endpoint, got delta={distance_to_last_straight_end}",
 		);
 	}
+
+	#[test]
+	fn selecting_pins_toggles_and_stays_on_one_alignment() {
+		let mut selected = SelectedPins::default();
+		selected.toggle(1, 0);
+		selected.toggle(1, 2);
+		selected.toggle(1, 0);
+		assert_eq!(selected.segments, BTreeSet::from([2]));
+
+		selected.toggle(2, 1);
+		assert_eq!(selected.alignment_id, 2);
+		assert_eq!(selected.segments, BTreeSet::from([1]));
+	}
 }
 
 impl SaveableSettings for AlignmentState {
@@ -561,14 +771,98 @@ impl SaveableSettings for AlignmentState {
 	}
 }
 
+/// Build an alignment between `start` and `end` whose tangent vertices follow the least-cost
+/// valley or ridge line over the height map.
+pub(crate) fn traced_alignment(
+	start: Vec3,
+	end: Vec3,
+	heightmap: &terrain::HeightMap,
+	settings: &terrain::Settings,
+	kind: TraceKind,
+) -> Option<Alignment> {
+	let cells = least_cost_path(
+		heightmap,
+		terrain::world_to_grid(start, settings),
+		terrain::world_to_grid(end, settings),
+		kind,
+	)?;
+	let mut points: Vec<Vec3> = cells
+		.iter()
+		.map(|cell| {
+			let mut point = terrain::grid_to_world(cell.x, cell.y, settings);
+			point.y = terrain::calculate_terrain_height(point, heightmap, settings);
+			point
+		})
+		.collect();
+	// Keep the picked endpoints exactly rather than their nearest grid vertices
+	if let Some(first) = points.first_mut() {
+		*first = start;
+	}
+	if let Some(last) = points.last_mut() {
+		*last = end;
+	}
+
+	let cell_size = settings.world_x() / settings.grid_x() as f32;
+	let vertices = simplify_polyline(&points, MAX_TURNS, cell_size);
+	let mut alignment = Alignment::new(start, end, 0);
+	for vertex in &vertices[1..vertices.len() - 1] {
+		alignment.append_turn(*vertex);
+	}
+	Some(alignment)
+}
+
+/// Reduce a polyline to its endpoints plus at most `max_interior` of its most significant
+/// corners, ignoring corners closer than `tolerance` to the simplified line.
+pub(crate) fn simplify_polyline(points: &[Vec3], max_interior: usize, tolerance: f32) -> Vec<Vec3> {
+	if points.len() <= 2 {
+		return points.to_vec();
+	}
+	let mut kept = vec![0, points.len() - 1];
+	while kept.len() < max_interior + 2 {
+		let farthest = kept
+			.windows(2)
+			.flat_map(|span| {
+				let (a, b) = (points[span[0]].xz(), points[span[1]].xz());
+				(span[0] + 1..span[1]).map(move |i| (i, distance_to_segment(points[i].xz(), a, b)))
+			})
+			.max_by(|x, y| x.1.total_cmp(&y.1));
+		let Some((index, distance)) = farthest else {
+			break;
+		};
+		if distance <= tolerance {
+			break;
+		}
+		let position = kept.partition_point(|kept_index| *kept_index < index);
+		kept.insert(position, index);
+	}
+	kept.into_iter().map(|index| points[index]).collect()
+}
+
+fn distance_to_segment(point: Vec2, a: Vec2, b: Vec2) -> f32 {
+	let ab = b - a;
+	let t = if ab.length_squared() > f32::EPSILON {
+		((point - a).dot(ab) / ab.length_squared()).clamp(0.0, 1.0)
+	} else {
+		0.0
+	};
+	point.distance(a + ab * t)
+}
+
 pub(crate) fn load_alignment() -> AlignmentState {
-	let mut settings = AlignmentState::load_or_default();
+	prepare_loaded_alignments(AlignmentState::load_or_default())
+}
+
+/// Fills in the fields that aren't saved and re-applies constraints to freshly loaded alignments.
+pub(crate) fn prepare_loaded_alignments(mut settings: AlignmentState) -> AlignmentState {
 	// Ensure next_alignment_id is at least 1 (0 is reserved for the default alignment)
 	settings.next_alignment_id = settings.next_alignment_id.max(1);
 	// Initialize skipped fields
 	settings.ui_new_alignment_turns = 1;
 	for alignment in settings.alignments.values_mut() {
-		alignment_path::constraints::enforce_alignment_constraints(alignment);
+		alignment_path::constraints::enforce_alignment_constraints(
+			alignment,
+			&GeometryTolerances::DEFAULT,
+		);
 	}
 	settings
 }
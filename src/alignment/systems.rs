@@ -1,4 +1,6 @@
-use alignment_path::{PathSegment, constraints as path_constraints};
+use alignment_path::{
+	GeometryTolerances, PathSegment, calculate_alignment_geometry, constraints as path_constraints,
+};
 use bevy::{
 	color::palettes::css::YELLOW,
 	picking::{
@@ -11,15 +13,19 @@ use bevy::{
 };
 
 use crate::camera::PrimaryCamera3d;
+use crate::hud::HudToast;
 
 use crate::pin::{PinDragState, create_pin};
 use crate::terrain::{self, HeightMap, TerrainMesh, calculate_terrain_height};
 use terrain::spatial::world_size_for_height;
 
-use super::components::{AlignmentPoint, PointType};
+use super::GeometryDebugLevel;
+use super::components::{AlignmentGizmos, AlignmentPoint, PointType};
+use super::render::{CachedAlignmentGeometry, TerrainHeightSampler};
 use super::state::{
-	AlignmentState, DraftAlignment, TangentSnapSettings, TrackBuildingMode, alignment_end_tangent,
-	build_preview_alignment, extend_alignment_with_preview, snapped_segment_end_with_lock,
+	AlignmentState, DraftAlignment, PinOrderSettings, SelectedPins, TangentSnapSettings,
+	TrackBuildingMode, alignment_end_tangent, build_preview_alignment, extend_alignment_with_preview,
+	snapped_segment_end_with_lock,
 };
 
 pub(crate) fn toggle_track_building_mode(
@@ -34,6 +40,22 @@ pub(crate) fn toggle_track_building_mode(
 	}
 }
 
+/// `G` steps the geometry debug level up and `Shift+G` steps it down, wrapping at either end.
+pub(crate) fn cycle_geometry_debug_level(
+	keyboard_input: Res<ButtonInput<KeyCode>>,
+	mut debug_level: ResMut<GeometryDebugLevel>,
+	toast: Option<ResMut<HudToast>>,
+) {
+	if !keyboard_input.just_pressed(KeyCode::KeyG) {
+		return;
+	}
+	let shift = keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+	debug_level.0 = debug_level.cycled(!shift);
+	if let Some(mut toast) = toast {
+		toast.show(format!("Geometry debug level {}", debug_level.0));
+	}
+}
+
 pub(crate) fn update_alignment_from_pins(
 	alignment_pins: Query<
 		(&Transform, &AlignmentPoint),
@@ -173,6 +195,7 @@ pub(crate) fn update_alignment_from_intermediate_pins(
 	>,
 	mut alignment_state: ResMut<AlignmentState>,
 	track_building_mode: Res<TrackBuildingMode>,
+	pin_order: Res<PinOrderSettings>,
 ) {
 	if track_building_mode.active {
 		return;
@@ -190,11 +213,18 @@ pub(crate) fn update_alignment_from_intermediate_pins(
 				.alignments
 				.get_mut(&intermediate_point.alignment_id)
 			{
+				let target = if pin_order.maintain_order {
+					alignment.keep_between_neighbors(
+						segment_index,
+						transform.translation,
+						&GeometryTolerances::DEFAULT,
+					)
+				} else {
+					transform.translation
+				};
 				let had_control_point = alignment.segment_control_point(segment_index);
-				if had_control_point
-					.is_some_and(|point| point.distance_squared(transform.translation) > f32::EPSILON)
-				{
-					alignment.set_segment_control_point(segment_index, transform.translation);
+				if had_control_point.is_some_and(|point| point.distance_squared(target) > f32::EPSILON) {
+					alignment.set_segment_control_point(segment_index, target, &GeometryTolerances::DEFAULT);
 				}
 				// Keep straight-section pins visually snapped to their tangent span even while dragging.
 				if let Some(snapped_control_point) = alignment.segment_control_point(segment_index) {
@@ -346,6 +376,235 @@ pub(crate) fn update_draft_cursor_pin(
 	));
 }
 
+/// How close to the path a right-click must land to insert a vertex, as a fraction of world size.
+const PATH_PICK_TOLERANCE_FRACTION: f32 = 0.01;
+
+/// Right-clicking the rendered path inserts a tangent vertex at that station.
+pub(crate) fn insert_vertex_on_path_click(
+	mouse_button: Res<ButtonInput<MouseButton>>,
+	track_building_mode: Res<TrackBuildingMode>,
+	mut alignment_state: ResMut<AlignmentState>,
+	geometry_cache: Res<CachedAlignmentGeometry>,
+	terrain_mesh: Single<Entity, With<TerrainMesh>>,
+	terrain_heightmap: Single<&HeightMap>,
+	settings: Res<terrain::Settings>,
+	ray_map: Res<RayMap>,
+	mut raycast: MeshRayCast,
+	camera_query: Single<Entity, With<PrimaryCamera3d>>,
+	mut egui_contexts: bevy_egui::EguiContexts,
+) {
+	if track_building_mode.active || !mouse_button.just_pressed(MouseButton::Right) {
+		return;
+	}
+	if let Ok(ctx) = egui_contexts.ctx_mut() {
+		if ctx.wants_pointer_input() || ctx.is_pointer_over_area() {
+			return;
+		}
+	}
+	let Some((_, _, geometry)) = geometry_cache.get() else {
+		return;
+	};
+
+	let camera_entity = *camera_query;
+	let terrain_entity = *terrain_mesh;
+	let Some(ray) = ray_map
+		.iter()
+		.find(|(ray_id, _)| ray_id.pointer == PointerId::Mouse && ray_id.camera == camera_entity)
+		.map(|(_, ray)| *ray)
+	else {
+		return;
+	};
+	let filter = |entity: Entity| entity == terrain_entity;
+	let raycast_settings = MeshRayCastSettings::default().with_filter(&filter);
+	let Some(hit_point) = raycast
+		.cast_ray(ray, &raycast_settings)
+		.iter()
+		.find(|(entity, _)| *entity == terrain_entity)
+		.map(|(_, hit)| hit.point)
+	else {
+		return;
+	};
+
+	let tolerance = world_size_for_height(&settings) * PATH_PICK_TOLERANCE_FRACTION;
+	let Some((station, distance)) = geometry.nearest_station(Vec2::new(hit_point.x, hit_point.z))
+	else {
+		return;
+	};
+	if distance > tolerance {
+		return;
+	}
+	let Some(xz) = geometry.xz_at_station(station) else {
+		return;
+	};
+	let mut position = Vec3::new(xz.x, 0.0, xz.y);
+	position.y = calculate_terrain_height(position, &terrain_heightmap, &settings);
+
+	let current_id = alignment_state.current_alignment;
+	if alignment_state
+		.insert_vertex(current_id, station, position)
+		.is_none()
+	{
+		debug!("Alignment {current_id} already has the maximum number of turns");
+	}
+}
+
+/// Left-clicking near any rendered alignment makes it the current one.
+pub(crate) fn select_alignment_on_path_click(
+	mouse_button: Res<ButtonInput<MouseButton>>,
+	track_building_mode: Res<TrackBuildingMode>,
+	mut alignment_state: ResMut<AlignmentState>,
+	terrain_heightmap: Single<&HeightMap>,
+	settings: Res<terrain::Settings>,
+	ray_map: Res<RayMap>,
+	camera_query: Single<Entity, With<PrimaryCamera3d>>,
+	pin_interactions: Query<&PickingInteraction, With<AlignmentPoint>>,
+	mut egui_contexts: bevy_egui::EguiContexts,
+) {
+	if track_building_mode.active || !mouse_button.just_pressed(MouseButton::Left) {
+		return;
+	}
+	// Pins sit on the path; clicking one starts a drag instead
+	if pin_interactions
+		.iter()
+		.any(|interaction| *interaction != PickingInteraction::None)
+	{
+		return;
+	}
+	if let Ok(ctx) = egui_contexts.ctx_mut() {
+		if ctx.wants_pointer_input() || ctx.is_pointer_over_area() {
+			return;
+		}
+	}
+	let camera_entity = *camera_query;
+	let Some(ray) = ray_map
+		.iter()
+		.find(|(ray_id, _)| ray_id.pointer == PointerId::Mouse && ray_id.camera == camera_entity)
+		.map(|(_, ray)| *ray)
+	else {
+		return;
+	};
+
+	let sampler = TerrainHeightSampler {
+		heightmap: &terrain_heightmap,
+		settings: &settings,
+	};
+	let tolerance = world_size_for_height(&settings) * PATH_PICK_TOLERANCE_FRACTION;
+	let picked = alignment_state
+		.alignments
+		.iter()
+		.filter_map(|(id, alignment)| {
+			let geometry = calculate_alignment_geometry(alignment.start, alignment.end, alignment);
+			let (_, point) = geometry.raycast(ray.origin, *ray.direction, &sampler, tolerance)?;
+			Some((*id, ray.origin.distance(point)))
+		})
+		.min_by(|a, b| a.1.total_cmp(&b.1))
+		.map(|(id, _)| id);
+	if let Some(id) = picked
+		&& id != alignment_state.current_alignment
+	{
+		alignment_state.current_alignment = id;
+	}
+}
+
+/// Shift-clicking an intermediate pin removes that vertex.
+pub(crate) fn remove_vertex_on_pin_click(
+	click: On<Pointer<Click>>,
+	keyboard_input: Res<ButtonInput<KeyCode>>,
+	track_building_mode: Res<TrackBuildingMode>,
+	pins: Query<&AlignmentPoint, Without<DraftAlignmentPin>>,
+	mut alignment_state: ResMut<AlignmentState>,
+) {
+	if track_building_mode.active
+		|| click.button != PointerButton::Primary
+		|| !keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+	{
+		return;
+	}
+	let Ok(point) = pins.get(click.entity) else {
+		return;
+	};
+	if let PointType::Intermediate { segment_index } = point.point_type {
+		alignment_state.remove_vertex(point.alignment_id, segment_index);
+	}
+}
+
+/// Ctrl-clicking an intermediate pin adds it to or drops it from the multi-selection.
+pub(crate) fn toggle_pin_selection_on_click(
+	click: On<Pointer<Click>>,
+	keyboard_input: Res<ButtonInput<KeyCode>>,
+	track_building_mode: Res<TrackBuildingMode>,
+	pins: Query<&AlignmentPoint, Without<DraftAlignmentPin>>,
+	mut selected: ResMut<SelectedPins>,
+) {
+	if track_building_mode.active
+		|| click.button != PointerButton::Primary
+		|| !keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+		|| keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+	{
+		return;
+	}
+	let Ok(point) = pins.get(click.entity) else {
+		return;
+	};
+	if let PointType::Intermediate { segment_index } = point.point_type {
+		selected.toggle(point.alignment_id, segment_index);
+	}
+}
+
+/// Inserting or removing a vertex shifts segment indices, so the selection would point at the
+/// wrong turns; drop it instead.
+pub(crate) fn clear_selection_on_vertex_change(
+	alignment_state: Res<AlignmentState>,
+	mut selected: ResMut<SelectedPins>,
+	mut segment_count: Local<Option<(usize, Option<usize>)>>,
+) {
+	if !alignment_state.is_changed() && !selected.is_changed() {
+		return;
+	}
+	let id = selected.alignment_id;
+	let count = alignment_state
+		.alignments
+		.get(&id)
+		.map(|alignment| alignment.segments.len());
+	if let Some((previous_id, previous_count)) = *segment_count
+		&& previous_id == id
+		&& previous_count != count
+		&& alignment_state.is_changed()
+		&& !selected.segments.is_empty()
+	{
+		selected.clear();
+	}
+	*segment_count = Some((id, count));
+}
+
+/// Pin-scale-relative radius of the ring drawn around selected pins.
+const SELECTED_PIN_RING_RADIUS: f32 = 6.0;
+
+pub(crate) fn draw_selected_pin_markers(
+	mut gizmos: Gizmos<AlignmentGizmos>,
+	selected: Res<SelectedPins>,
+	pins: Query<(&Transform, &AlignmentPoint), Without<DraftAlignmentPin>>,
+) {
+	if selected.segments.is_empty() {
+		return;
+	}
+	for (transform, point) in &pins {
+		let PointType::Intermediate { segment_index } = point.point_type else {
+			continue;
+		};
+		if point.alignment_id == selected.alignment_id && selected.segments.contains(&segment_index) {
+			gizmos.circle(
+				Isometry3d::new(
+					transform.translation,
+					Quat::from_rotation_x(std::f32::consts::FRAC_PI_2),
+				),
+				SELECTED_PIN_RING_RADIUS * transform.scale.x,
+				YELLOW,
+			);
+		}
+	}
+}
+
 /// Marker component for draft alignment pins (start point being placed)
 #[derive(Component)]
 pub(crate) struct DraftAlignmentPin;
@@ -622,7 +881,10 @@ pub(crate) fn commit_first_segment(
 					draft_alignment.previous_tangent,
 					*snap_settings,
 				);
-				path_constraints::enforce_alignment_constraints(&mut new_alignment);
+				path_constraints::enforce_alignment_constraints(
+					&mut new_alignment,
+					&GeometryTolerances::DEFAULT,
+				);
 				let new_alignment_id = alignment_state.next_alignment_id;
 				alignment_state
 					.alignments
@@ -641,7 +903,10 @@ pub(crate) fn commit_first_segment(
 				draft_alignment.previous_tangent,
 				*snap_settings,
 			);
-			path_constraints::enforce_alignment_constraints(&mut new_alignment);
+			path_constraints::enforce_alignment_constraints(
+				&mut new_alignment,
+				&GeometryTolerances::DEFAULT,
+			);
 			let new_alignment_id = alignment_state.next_alignment_id;
 			alignment_state
 				.alignments
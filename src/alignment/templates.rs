@@ -0,0 +1,117 @@
+use alignment_path::{Alignment, PathSegment, TurnSegment};
+use bevy::prelude::*;
+
+use super::{MAX_ARC_RADIUS, MIN_ARC_RADIUS};
+
+/// Preset turn layouts laid out between the current start and end pins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AlignmentTemplate {
+	SCurve,
+	Chicane,
+	ReverseCurve,
+	Bend90,
+}
+
+/// A template vertex: distance along the chord and offset to its left, both as fractions of the
+/// chord length, plus the arc radius as a fraction of the chord length.
+struct TemplateVertex {
+	along: f32,
+	left: f32,
+	radius: f32,
+}
+
+const fn vertex(along: f32, left: f32, radius: f32) -> TemplateVertex {
+	TemplateVertex {
+		along,
+		left,
+		radius,
+	}
+}
+
+impl AlignmentTemplate {
+	pub(crate) const ALL: [Self; 4] = [
+		Self::SCurve,
+		Self::Chicane,
+		Self::ReverseCurve,
+		Self::Bend90,
+	];
+
+	pub(crate) const fn label(self) -> &'static str {
+		match self {
+			Self::SCurve => "S-curve",
+			Self::Chicane => "Chicane",
+			Self::ReverseCurve => "Reverse curve",
+			Self::Bend90 => "90° bend",
+		}
+	}
+
+	fn vertices(self) -> &'static [TemplateVertex] {
+		const S_CURVE: &[TemplateVertex] = &[vertex(0.25, 0.15, 0.2), vertex(0.75, -0.15, 0.2)];
+		const CHICANE: &[TemplateVertex] = &[
+			vertex(0.4, 0.0, 0.05),
+			vertex(0.45, 0.08, 0.05),
+			vertex(0.55, 0.08, 0.05),
+			vertex(0.6, 0.0, 0.05),
+		];
+		const REVERSE_CURVE: &[TemplateVertex] = &[vertex(0.4, 0.2, 0.3), vertex(0.6, -0.2, 0.3)];
+		const BEND_90: &[TemplateVertex] = &[vertex(0.5, 0.5, 0.25)];
+		match self {
+			Self::SCurve => S_CURVE,
+			Self::Chicane => CHICANE,
+			Self::ReverseCurve => REVERSE_CURVE,
+			Self::Bend90 => BEND_90,
+		}
+	}
+
+	/// Build the template between `start` and `end`, scaled to their horizontal distance.
+	pub(crate) fn instantiate(self, start: Vec3, end: Vec3) -> Alignment {
+		let chord = Vec3::new(end.x - start.x, 0.0, end.z - start.z);
+		let length = chord.length();
+		let mut alignment = Alignment::new(start, end, 0);
+		if length <= f32::EPSILON {
+			return alignment;
+		}
+		let along = chord / length;
+		let left = Vec3::Y.cross(along);
+		alignment.segments = self
+			.vertices()
+			.iter()
+			.map(|v| {
+				let mut position = start + along * v.along * length + left * v.left * length;
+				position.y = start.lerp(end, v.along).y;
+				PathSegment::Turn(TurnSegment {
+					circular_section_radius: (v.radius * length).clamp(MIN_ARC_RADIUS, MAX_ARC_RADIUS),
+					..TurnSegment::new(position)
+				})
+			})
+			.collect();
+		alignment
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn s_curve_turns_alternate_direction() {
+		let start = Vec3::ZERO;
+		let end = Vec3::new(1_000.0, 0.0, 0.0);
+		let alignment = AlignmentTemplate::SCurve.instantiate(start, end);
+		assert_eq!(alignment.turn_count(), 2);
+
+		let points = alignment.control_points();
+		let turn_signs: Vec<f32> = points
+			.windows(3)
+			.map(|w| {
+				let (incoming, outgoing) = (w[1] - w[0], w[2] - w[1]);
+				incoming
+					.x
+					.mul_add(outgoing.z, -(incoming.z * outgoing.x))
+					.signum()
+			})
+			.collect();
+		assert_eq!(turn_signs.len(), 2);
+		assert_eq!(turn_signs[0], -turn_signs[1]);
+	}
+}
@@ -1,17 +1,40 @@
-use crate::saveable::SaveableSettings;
+use std::path::Path;
+
+use crate::pin::PinDragState;
+use crate::saveable::{PendingFileDialog, SaveableSettings};
+use crate::terrain::least_cost::TraceKind;
 use crate::terrain::{self, calculate_terrain_height};
 use crate::ui_shell::{ActivePanel, AlignmentTab, UiShellState};
+use crate::units::{DisplayPrecision, Units};
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy_egui::{EguiContexts, egui};
 
 use alignment_path::elevation::{Pvi, PviProfile, TerrainSampledProfile, VerticalProfileData};
 use alignment_path::{
-	ElevationProfile as _, HeightSampler, PathSegment, calculate_alignment_geometry,
+	AlignmentGeometry, ElevationProfile as _, GeometrySegment, GeometryTolerances, HeightSampler,
+	PathSegment, TransitionSpec, TurnSegment, calculate_alignment_geometry,
 };
 
 use super::components::{AlignmentPoint, PointType};
-use super::constraints::compute_max_angle;
-use super::state::AlignmentState;
+use super::constraints::{
+	DesignSpeedSettings, check_cant_runoff, check_clothoid_parameter, check_min_radius,
+	check_tangent_overlap, compute_max_angle,
+};
+use super::export::{GeoOrigin, centerline_samples, export_all_geojson, export_geojson};
+use super::footprint::FootprintSettings;
+use super::history::{AlignmentHistory, history_diff_ui};
+use super::poi::{BoundEndpoint, Endpoint, PointOfInterest, PointsOfInterest};
+use super::profile_plot::{
+	ProfilePlotSettings, ProfileSeries, curvature_plot_ui, elevation_profile_plot_ui, sample_profile,
+};
+use super::render::{
+	CachedAlignmentGeometry, MIN_STATION_LABEL_INTERVAL, OffsetTrackSettings,
+	SpiralExaggerationSettings, StationLabelSettings, TrackRibbonSettings,
+};
+use super::sight_line::SightLineSettings;
+use super::state::{AlignmentState, SelectedPins, prepare_loaded_alignments, traced_alignment};
+use super::templates::AlignmentTemplate;
 use super::{
 	FRAC_PI_180, GeometryDebugLevel, MAX_ARC_RADIUS, MAX_GEOMETRY_DEBUG_LEVEL, MAX_TURNS,
 	MIN_ARC_RADIUS,
@@ -29,6 +52,13 @@ impl HeightSampler for TerrainSampler<'_> {
 }
 
 const PVI_SAMPLE_COUNT: usize = 11;
+const PROFILE_PLOT_SAMPLES: usize = 256;
+const CHORD_OFFSET_POINTS: usize = 11;
+const GEOMETRY_JSON_FILENAME: &str = "geometry.json";
+const GEOJSON_FILENAME: &str = "alignment.geojson";
+const ALL_ALIGNMENTS_GEOJSON_FILENAME: &str = "alignments.geojson";
+/// Station spacing of the curvature diagram, in meters.
+const CURVATURE_PLOT_SPACING: f32 = 1.0;
 
 fn sample_pvips_from_terrain(
 	alignment: &alignment_path::Alignment,
@@ -52,7 +82,118 @@ fn sample_pvips_from_terrain(
 			}
 		})
 		.collect();
-	PviProfile { points }
+	PviProfile {
+		points,
+		..Default::default()
+	}
+}
+
+/// Editing aids that outlive a single alignment edit.
+#[derive(SystemParam)]
+pub(crate) struct EditingState<'w> {
+	history: ResMut<'w, AlignmentHistory>,
+	selected: Res<'w, SelectedPins>,
+	points_of_interest: ResMut<'w, PointsOfInterest>,
+	geo_origin: Res<'w, GeoOrigin>,
+	file_dialog: ResMut<'w, PendingFileDialog<AlignmentState>>,
+	geometry_cache: Res<'w, CachedAlignmentGeometry>,
+}
+
+/// Display-only overlays drawn along the current alignment.
+#[derive(SystemParam)]
+pub(crate) struct OverlaySettings<'w> {
+	offset: ResMut<'w, OffsetTrackSettings>,
+	spiral_exaggeration: ResMut<'w, SpiralExaggerationSettings>,
+	footprint: ResMut<'w, FootprintSettings>,
+	station_labels: ResMut<'w, StationLabelSettings>,
+	ribbon: ResMut<'w, TrackRibbonSettings>,
+	sight_line: ResMut<'w, SightLineSettings>,
+}
+
+fn overlay_settings_ui(
+	ui: &mut egui::Ui,
+	overlays: &mut OverlaySettings,
+	units: Units,
+	footprint_area: Option<f32>,
+) {
+	let mut offset = *overlays.offset;
+	ui.horizontal(|ui| {
+		ui.checkbox(&mut offset.enabled, "Offset track");
+		ui.add_enabled(
+			offset.enabled,
+			units.length_drag_value(&mut offset.lateral_m).speed(0.1),
+		);
+	});
+	let mut ribbon = *overlays.ribbon;
+	ui.horizontal(|ui| {
+		ui.checkbox(&mut ribbon.enabled, "Track ribbon");
+		ui.add_enabled(
+			ribbon.enabled,
+			units.length_drag_value(&mut ribbon.half_width).speed(0.05),
+		)
+		.on_hover_text("Half-width of the track bed");
+	});
+	let mut footprint = *overlays.footprint;
+	ui.horizontal(|ui| {
+		ui.checkbox(&mut footprint.enabled, "Footprint");
+		ui.add_enabled(
+			footprint.enabled,
+			units
+				.length_drag_value(&mut footprint.half_width)
+				.speed(0.1),
+		)
+		.on_hover_text("Half-width of the corridor");
+		if let Some(area) = footprint_area {
+			ui.label(units.format_area(f64::from(area)));
+		}
+	});
+	let mut station_labels = *overlays.station_labels;
+	ui.horizontal(|ui| {
+		ui.checkbox(&mut station_labels.enabled, "Station labels every");
+		ui.add_enabled(
+			station_labels.enabled,
+			units
+				.length_drag_value(&mut station_labels.interval_m)
+				.range(MIN_STATION_LABEL_INTERVAL..=f32::MAX),
+		);
+	});
+	let mut sight_line = *overlays.sight_line;
+	ui.horizontal(|ui| {
+		ui.checkbox(&mut sight_line.enabled, "Sight line from");
+		ui.add_enabled_ui(sight_line.enabled, |ui| {
+			ui.add(units.length_drag_value(&mut sight_line.from_station));
+			ui.label("to");
+			ui.add(units.length_drag_value(&mut sight_line.to_station));
+		});
+	});
+	ui.add_enabled_ui(sight_line.enabled, |ui| {
+		ui.horizontal(|ui| {
+			ui.label("Eye height:");
+			ui.add(
+				units
+					.length_drag_value(&mut sight_line.eye_height)
+					.speed(0.1)
+					.range(0.0..=f32::MAX),
+			);
+		});
+	});
+	let mut spiral_exaggeration = *overlays.spiral_exaggeration;
+	ui.horizontal(|ui| {
+		ui.label("Spiral exaggeration (not to scale):");
+		ui.add(egui::Slider::new(
+			&mut spiral_exaggeration.factor,
+			1.0..=20.0,
+		))
+		.on_hover_text("Visual only, not to scale");
+	});
+
+	// Overlays rebuild their meshes on change, so only write back real edits
+	overlays.offset.set_if_neq(offset);
+	overlays.ribbon.set_if_neq(ribbon);
+	overlays.footprint.set_if_neq(footprint);
+	overlays.station_labels.set_if_neq(station_labels);
+	overlays.sight_line.set_if_neq(sight_line);
+	overlays.spiral_exaggeration.set_if_neq(spiral_exaggeration);
 }
 
 pub(crate) fn ui(
@@ -60,16 +201,30 @@ pub(crate) fn ui(
 	mut alignment_state: ResMut<AlignmentState>,
 	mut path_debug_level: ResMut<GeometryDebugLevel>,
 	mut ui_shell_state: ResMut<UiShellState>,
+	mut overlays: OverlaySettings,
+	mut plot_settings: ResMut<ProfilePlotSettings>,
+	mut design_speed: ResMut<DesignSpeedSettings>,
+	units: Res<Units>,
+	precision: Res<DisplayPrecision>,
 	alignment_pins: Query<(&Transform, &AlignmentPoint)>,
 	terrain_heightmap: Single<&terrain::HeightMap>,
 	terrain_settings: Res<terrain::Settings>,
+	drag_state: Res<PinDragState>,
+	mut editing: EditingState,
 ) {
 	if ui_shell_state.active_panel != ActivePanel::AlignmentProperties {
 		return;
 	}
 
+	let units = *units;
+	let precision = *precision;
 	let path_debug_level = &mut path_debug_level.0;
 	if let Ok(ctx) = contexts.ctx_mut() {
+		// Bound endpoints follow points of interest whenever they change, so only flag real edits
+		let points_before = editing.points_of_interest.clone();
+		// Likewise for the alignments, so the geometry cache only rebuilds after a real edit
+		let alignment_before = alignment_state.clone();
+		let alignment_ptr = alignment_state.bypass_change_detection();
 		egui::Window::new("Alignment Properties")
 			.fixed_pos(egui::pos2(8.0, 8.0))
 			.movable(false)
@@ -92,7 +247,7 @@ pub(crate) fn ui(
 				let mut start_pos = Vec3::ZERO;
 				let mut end_pos = Vec3::ZERO;
 				for (transform, alignment_point) in alignment_pins.iter() {
-					if alignment_point.alignment_id == alignment_state.current_alignment {
+					if alignment_point.alignment_id == alignment_ptr.current_alignment {
 						match alignment_point.point_type {
 							PointType::Start => start_pos = transform.translation,
 							PointType::End => end_pos = transform.translation,
@@ -113,38 +268,137 @@ pub(crate) fn ui(
 
 					ui.label(format!(
 						"Current alignment: {}",
-						alignment_state.current_alignment
+						alignment_ptr.current_alignment
 					));
 					ui.label(format!(
 						"Total alignments: {}",
-						alignment_state.alignments.len()
+						alignment_ptr.alignments.len()
 					));
 					ui.label(format!("Total pins: {}", alignment_pins.iter().count()));
 
 					ui.separator();
 
-					display_position(ui, "Start (Red)", start_pos);
-					display_position(ui, "End (Blue)", end_pos);
+					let sampler = TerrainSampler {
+						heightmap: &terrain_heightmap,
+						settings: &terrain_settings,
+					};
+					let current_id = alignment_ptr.current_alignment;
+					ui.label("Start (Red):");
+					if plan_position_edit(ui, &mut start_pos, units, precision, &sampler)
+						&& let Some(alignment) = alignment_ptr.alignments.get_mut(&current_id)
+					{
+						alignment.start = start_pos;
+					}
+					ui.label("End (Blue):");
+					if plan_position_edit(ui, &mut end_pos, units, precision, &sampler)
+						&& let Some(alignment) = alignment_ptr.alignments.get_mut(&current_id)
+					{
+						alignment.end = end_pos;
+					}
+					ui.separator();
+
+					let cached_geometry = editing.geometry_cache.get();
+					let footprint_area = cached_geometry
+						.filter(|_| overlays.footprint.enabled)
+						.map(|(_, _, geometry)| geometry.footprint_area(overlays.footprint.half_width));
+					overlay_settings_ui(ui, &mut overlays, units, footprint_area);
 					ui.separator();
 
 					ui.label("Select Alignment:");
-					alignment_selection_ui(ui, &mut alignment_state);
+					alignment_selection_ui(ui, alignment_ptr, drag_state.is_dragging_any());
+					if ui
+						.button("Export All GeoJSON")
+						.on_hover_text("Every alignment as one feature collection")
+						.clicked()
+					{
+						let path = Path::new(ALL_ALIGNMENTS_GEOJSON_FILENAME);
+						match export_all_geojson(alignment_ptr, &sampler, &editing.geo_origin, path) {
+							Ok(()) => debug!("Exported {ALL_ALIGNMENTS_GEOJSON_FILENAME}"),
+							Err(e) => error!("Failed to export {ALL_ALIGNMENTS_GEOJSON_FILENAME}: {e}"),
+						}
+					}
+					history_diff_ui(ui, &mut editing.history, alignment_ptr.current_alignment);
 					ui.separator();
 
 					ui.label("Vertices:");
-					vertex_properties_ui(ui, &mut alignment_state);
+					design_speed_ui(ui, &mut design_speed);
+					let clothoid_parameters = alignment_ptr
+						.alignments
+						.get(&alignment_ptr.current_alignment)
+						.zip(cached_geometry)
+						.map(|(alignment, (_, _, geometry))| {
+							clothoid_parameters_by_segment(alignment, geometry)
+						})
+						.unwrap_or_default();
+					vertex_properties_ui(
+						ui,
+						alignment_ptr,
+						&design_speed,
+						units,
+						precision,
+						&sampler,
+						&clothoid_parameters,
+					);
+					selected_turns_ui(ui, alignment_ptr, &editing.selected, units, precision);
+					if let Some(alignment) = alignment_ptr
+						.alignments
+						.get(&alignment_ptr.current_alignment)
+						&& let Some((cached_start, cached_end, geometry)) = cached_geometry
+					{
+						cant_runoff_warnings_ui(ui, geometry, &design_speed, units);
+						clothoid_parameter_warnings_ui(ui, geometry, units);
+						tangent_overlap_warnings_ui(ui, alignment, cached_start, cached_end, units);
+						chord_offsets_ui(ui, geometry, units);
+						if ui.button("Export Geometry JSON").clicked() {
+							match export_geometry_json(geometry, Path::new(GEOMETRY_JSON_FILENAME)) {
+								Ok(()) => debug!("Exported {GEOMETRY_JSON_FILENAME}"),
+								Err(e) => error!("Failed to export {GEOMETRY_JSON_FILENAME}: {e}"),
+							}
+						}
+						if ui.button("Export GeoJSON").clicked() {
+							let samples = centerline_samples(alignment, &sampler);
+							let path = Path::new(GEOJSON_FILENAME);
+							match export_geojson(&samples, &editing.geo_origin, path) {
+								Ok(()) => debug!("Exported {GEOJSON_FILENAME}"),
+								Err(e) => error!("Failed to export {GEOJSON_FILENAME}: {e}"),
+							}
+						}
+						ui.collapsing("Curvature diagram", |ui| {
+							curvature_plot_ui(ui, &geometry.curvature_profile(CURVATURE_PLOT_SPACING));
+						});
+					}
+					ui.separator();
+
+					ui.collapsing("Points of Interest", |ui| {
+						points_of_interest_ui(
+							ui,
+							editing.points_of_interest.bypass_change_detection(),
+							start_pos,
+							end_pos,
+							units,
+							precision,
+							&sampler,
+						);
+					});
 					ui.separator();
 
 					ui.label("Create New Alignment:");
-					alignment_creation_ui(ui, &mut alignment_state, start_pos, end_pos);
+					alignment_creation_ui(
+						ui,
+						alignment_ptr,
+						editing.points_of_interest.bypass_change_detection(),
+						start_pos,
+						end_pos,
+					);
+					terrain_trace_ui(ui, alignment_ptr, start_pos, end_pos, &sampler);
 				} else {
 					let sampler = TerrainSampler {
 						heightmap: &terrain_heightmap,
 						settings: &terrain_settings,
 					};
 					// Auto-initialize PVI from terrain on first visit
-					let current_id = alignment_state.current_alignment;
-					if let Some(alignment) = alignment_state.alignments.get_mut(&current_id) {
+					let current_id = alignment_ptr.current_alignment;
+					if let Some(alignment) = alignment_ptr.alignments.get_mut(&current_id) {
 						if matches!(
 							alignment.vertical_profile,
 							VerticalProfileData::TerrainSampled
@@ -154,29 +408,156 @@ pub(crate) fn ui(
 							));
 						}
 					}
-					vertical_profile_ui(ui, &mut alignment_state, start_pos, end_pos);
+					vertical_profile_ui(ui, alignment_ptr, start_pos, end_pos, units);
+					ui.separator();
+
+					if let Some(alignment) = alignment_ptr.alignments.get(&current_id) {
+						profile_comparison_ui(ui, alignment_ptr, &mut plot_settings);
+						let mut series = vec![ProfileSeries {
+							label: format!("Alignment {current_id}"),
+							samples: alignment_profile_samples(alignment, start_pos, end_pos, &sampler),
+						}];
+						let ground = if plot_settings.show_ground {
+							ground_profile_samples(alignment, start_pos, end_pos, &sampler)
+						} else {
+							Vec::new()
+						};
+						series.extend(plot_settings.compare_ids.iter().filter_map(|id| {
+							let other = alignment_ptr.alignments.get(id)?;
+							Some(ProfileSeries {
+								label: format!("Alignment {id}"),
+								samples: alignment_profile_samples(other, other.start, other.end, &sampler),
+							})
+						}));
+						elevation_profile_plot_ui(ui, &series, &ground, &mut plot_settings, units);
+					}
 				}
 
 				ui.separator();
-				let alignment_state: &AlignmentState = &alignment_state;
-				alignment_state.handle_save_operation_ui(ui, "Save Alignments");
+				alignment_ptr.handle_save_operation_ui(ui, "Save Alignments");
+				if let Some(loaded) = alignment_ptr.file_dialog_ui(ui, &mut editing.file_dialog) {
+					*alignment_ptr = prepare_loaded_alignments(loaded);
+				}
 			});
+		if *alignment_ptr != alignment_before {
+			alignment_state.set_changed();
+		}
+		if *editing.points_of_interest != points_before {
+			editing.points_of_interest.set_changed();
+		}
 	}
 }
 
-fn display_position(ui: &mut egui::Ui, label: &str, position: Vec3) {
-	ui.label(format!(
-		"{}: ({:.2},{:.2},{:.2})",
-		label, position.x, position.y, position.z
-	));
+fn alignment_profile_samples(
+	alignment: &alignment_path::Alignment,
+	start: Vec3,
+	end: Vec3,
+	sampler: &TerrainSampler<'_>,
+) -> Vec<(f32, f32)> {
+	let geometry = calculate_alignment_geometry(start, end, alignment);
+	match &alignment.vertical_profile {
+		VerticalProfileData::Pvi(profile) => sample_profile(&geometry, profile, PROFILE_PLOT_SAMPLES),
+		VerticalProfileData::TerrainSampled => {
+			let profile = TerrainSampledProfile {
+				sampler,
+				horizontal: &geometry,
+			};
+			sample_profile(&geometry, &profile, PROFILE_PLOT_SAMPLES)
+		}
+	}
 }
 
-fn alignment_selection_ui(ui: &mut egui::Ui, alignment_state: &mut AlignmentState) {
+/// Terrain directly under the centerline, at the same stations as [`alignment_profile_samples`].
+fn ground_profile_samples(
+	alignment: &alignment_path::Alignment,
+	start: Vec3,
+	end: Vec3,
+	sampler: &TerrainSampler<'_>,
+) -> Vec<(f32, f32)> {
+	let geometry = calculate_alignment_geometry(start, end, alignment);
+	let ground = TerrainSampledProfile {
+		sampler,
+		horizontal: &geometry,
+	};
+	sample_profile(&geometry, &ground, PROFILE_PLOT_SAMPLES)
+}
+
+fn profile_comparison_ui(
+	ui: &mut egui::Ui,
+	alignment_state: &AlignmentState,
+	plot_settings: &mut ProfilePlotSettings,
+) {
+	let mut ids: Vec<usize> = alignment_state
+		.alignments
+		.keys()
+		.copied()
+		.filter(|id| *id != alignment_state.current_alignment)
+		.collect();
+	if ids.is_empty() {
+		return;
+	}
+	ids.sort_unstable();
+	plot_settings.compare_ids.retain(|id| {
+		*id != alignment_state.current_alignment && alignment_state.alignments.contains_key(id)
+	});
+	ui.horizontal_wrapped(|ui| {
+		ui.label("Compare with:");
+		for id in ids {
+			let mut selected = plot_settings.compare_ids.contains(&id);
+			if ui.checkbox(&mut selected, id.to_string()).changed() {
+				if selected {
+					plot_settings.compare_ids.push(id);
+				} else {
+					plot_settings.compare_ids.retain(|other| *other != id);
+				}
+			}
+		}
+	});
+}
+
+/// X and Z fields for a plan position; Y follows the terrain. Returns whether it was edited.
+fn plan_position_edit(
+	ui: &mut egui::Ui,
+	position: &mut Vec3,
+	units: Units,
+	precision: DisplayPrecision,
+	sampler: &TerrainSampler<'_>,
+) -> bool {
+	let mut changed = false;
+	ui.horizontal(|ui| {
+		ui.label("X:");
+		changed |= ui
+			.add(
+				units
+					.length_drag_value(&mut position.x)
+					.fixed_decimals(precision.0),
+			)
+			.changed();
+		ui.label("Z:");
+		changed |= ui
+			.add(
+				units
+					.length_drag_value(&mut position.z)
+					.fixed_decimals(precision.0),
+			)
+			.changed();
+		if changed {
+			position.y = sampler.height_at(*position);
+		}
+		ui.label(format!(
+			"Y: {}{}",
+			precision.format(units.to_display(f64::from(position.y))),
+			units.length_suffix()
+		))
+		.on_hover_text(units.format_position(*position, precision));
+	});
+	changed
+}
+
+fn alignment_selection_ui(ui: &mut egui::Ui, alignment_state: &mut AlignmentState, dragging: bool) {
 	let mut alignment_entries: Vec<_> = alignment_state.alignments.iter().collect();
 	alignment_entries.sort_by_key(|(id, _)| *id);
 
-	let mut id_to_delete: Option<usize> = None;
-
 	for (&id, alignment) in alignment_entries {
 		let n_turns = alignment.turn_count();
 		let label = match n_turns {
@@ -184,36 +565,211 @@ fn alignment_selection_ui(ui: &mut egui::Ui, alignment_state: &mut AlignmentStat
 			1 => format!("Alignment {} (1 Turn)", id),
 			n => format!("Alignment {} ({} Turns)", id, n),
 		};
-		ui.horizontal(|ui| {
-			ui.radio_value(&mut alignment_state.current_alignment, id, label);
-			if ui.small_button("X").clicked() {
-				id_to_delete = Some(id);
-			}
-		});
+		ui.radio_value(&mut alignment_state.current_alignment, id, label);
 	}
 
-	// Delete alignment if requested
-	if let Some(id) = id_to_delete {
-		alignment_state.alignments.remove(&id);
-		// If we deleted the current alignment, switch to another one
-		if alignment_state.current_alignment == id {
-			alignment_state.current_alignment = alignment_state
-				.alignments
-				.keys()
-				.next()
-				.copied()
-				.unwrap_or(0);
+	let current_id = alignment_state.current_alignment;
+	ui.horizontal(|ui| {
+		if ui
+			.add_enabled(!dragging, egui::Button::new("Reverse"))
+			.on_hover_text("Swap start and end, walking the vertices backwards")
+			.clicked()
+			&& let Some(alignment) = alignment_state.alignments.get_mut(&current_id)
+		{
+			alignment.reverse(&GeometryTolerances::DEFAULT);
+		}
+
+		let deletable = current_id != 0 && !dragging;
+		let response = ui.add_enabled(deletable, egui::Button::new("Delete Alignment"));
+		let response = if current_id == 0 {
+			response.on_disabled_hover_text("The linear alignment can't be deleted")
+		} else {
+			response.on_disabled_hover_text("Finish dragging before deleting")
+		};
+		if response.clicked() {
+			alignment_state.delete_alignment(current_id);
 		}
+	});
+}
+
+fn design_speed_ui(ui: &mut egui::Ui, design_speed: &mut DesignSpeedSettings) {
+	ui.horizontal(|ui| {
+		ui.label("Design speed:");
+		ui.add(
+			egui::DragValue::new(&mut design_speed.design_speed_kmh)
+				.range(0.0..=400.0)
+				.suffix(" km/h"),
+		);
+		ui.label("Max cant:");
+		ui.add(
+			egui::DragValue::new(&mut design_speed.max_cant_mm)
+				.range(0.0..=200.0)
+				.suffix(" mm"),
+		);
+	});
+	ui.horizontal(|ui| {
+		ui.label("Max cant gradient:");
+		ui.add(
+			egui::DragValue::new(&mut design_speed.max_cant_gradient)
+				.range(0.1..=10.0)
+				.speed(0.1)
+				.suffix(" mm/m"),
+		);
+		ui.label("Ruling grade:");
+		let mut percent = design_speed.max_gradient * 100.0;
+		if ui
+			.add(
+				egui::DragValue::new(&mut percent)
+					.range(0.1..=20.0)
+					.speed(0.05)
+					.suffix(" %"),
+			)
+			.changed()
+		{
+			design_speed.max_gradient = percent / 100.0;
+		}
+	});
+}
+
+fn cant_runoff_warnings_ui(
+	ui: &mut egui::Ui,
+	geometry: &AlignmentGeometry,
+	design_speed: &DesignSpeedSettings,
+	units: Units,
+) {
+	let shortfalls = check_cant_runoff(
+		geometry,
+		design_speed.design_speed_kmh,
+		design_speed.max_cant_mm,
+		design_speed.max_cant_gradient,
+	);
+	for shortfall in shortfalls {
+		let turn_number = geometry.segments[..shortfall.segment_index]
+			.iter()
+			.filter(|segment| matches!(segment, GeometrySegment::Turn(_)))
+			.count()
+			+ 1;
+		ui.colored_label(
+			egui::Color32::RED,
+			format!(
+				"Turn {turn_number}: transition {} is shorter than the cant runoff {}",
+				units.format_length(f64::from(shortfall.clothoid_length)),
+				units.format_length(f64::from(shortfall.required_length)),
+			),
+		);
 	}
 }
 
-fn vertex_properties_ui(ui: &mut egui::Ui, alignment_state: &mut AlignmentState) {
+fn clothoid_parameter_warnings_ui(ui: &mut egui::Ui, geometry: &AlignmentGeometry, units: Units) {
+	for warning in check_clothoid_parameter(geometry) {
+		let turn_number = geometry.segments[..warning.segment_index]
+			.iter()
+			.filter(|segment| matches!(segment, GeometrySegment::Turn(_)))
+			.count()
+			+ 1;
+		let problem = if warning.parameter < warning.radius / 3.0 {
+			"below R/3, transition too short"
+		} else {
+			"above R, transition too long"
+		};
+		ui.colored_label(
+			egui::Color32::YELLOW,
+			format!(
+				"Turn {turn_number}: clothoid A {} is {problem}",
+				units.format_length(warning.parameter),
+			),
+		);
+	}
+}
+
+fn tangent_overlap_warnings_ui(
+	ui: &mut egui::Ui,
+	alignment: &alignment_path::Alignment,
+	start: Vec3,
+	end: Vec3,
+	units: Units,
+) {
+	for overlap in check_tangent_overlap(alignment, start, end, &GeometryTolerances::DEFAULT) {
+		ui.colored_label(
+			egui::Color32::RED,
+			format!(
+				"Tangent {}: transitions need {} but the leg is only {}",
+				overlap.leg_index + 1,
+				units.format_length(f64::from(overlap.required_length)),
+				units.format_length(f64::from(overlap.available_length)),
+			),
+		);
+	}
+}
+
+/// Switches how a turn's transition is specified, seeding the new mode from the current curve.
+fn transition_spec_combo(ui: &mut egui::Ui, id: usize, turn: &mut TurnSegment, deflection: f32) {
+	let clothoid_length = turn.clothoid_length(deflection);
+	let options = [
+		TransitionSpec::ByAngle,
+		TransitionSpec::ByLength(clothoid_length),
+		TransitionSpec::ByParameter((turn.circular_section_radius * clothoid_length).sqrt()),
+	];
+	egui::ComboBox::from_id_salt(("transition_spec", id))
+		.selected_text(turn.transition.label())
+		.show_ui(ui, |ui| {
+			for option in options {
+				let selected = std::mem::discriminant(&turn.transition) == std::mem::discriminant(&option);
+				if ui.selectable_label(selected, option.label()).clicked() && !selected {
+					turn.transition = option;
+				}
+			}
+		});
+}
+
+/// Clothoid parameter A of each of the alignment's segments, `None` for straights and for turns
+/// the geometry dropped as degenerate.
+fn clothoid_parameters_by_segment(
+	alignment: &alignment_path::Alignment,
+	geometry: &AlignmentGeometry,
+) -> Vec<Option<f64>> {
+	let mut turns = geometry
+		.segments
+		.iter()
+		.filter_map(|segment| match segment {
+			GeometrySegment::Turn(turn) => Some(turn),
+			GeometrySegment::Straight(_) => None,
+		})
+		.peekable();
+	alignment
+		.segments
+		.iter()
+		.map(|segment| {
+			let vertex = segment.as_turn()?.tangent_vertex;
+			turns
+				.next_if(|turn| turn.tangent_vertex == vertex)
+				.map(|turn| turn.clothoid_parameter())
+		})
+		.collect()
+}
+
+/// `clothoid_parameters` holds the clothoid parameter A of each segment, indexed like
+/// `Alignment::segments`.
+fn vertex_properties_ui(
+	ui: &mut egui::Ui,
+	alignment_state: &mut AlignmentState,
+	design_speed: &DesignSpeedSettings,
+	units: Units,
+	precision: DisplayPrecision,
+	sampler: &TerrainSampler<'_>,
+	clothoid_parameters: &[Option<f64>],
+) {
 	// Only show vertex properties if the alignment has intermediate tangent points
 	if let Some(alignment) = &mut alignment_state
 		.alignments
 		.get_mut(&alignment_state.current_alignment)
 		&& alignment.turn_count() > 0
 	{
+		let below_min_radius = check_min_radius(
+			alignment,
+			design_speed.design_speed_kmh,
+			design_speed.max_cant_mm,
+		);
 		let control_points = alignment.control_points();
 		let segments: &mut [PathSegment] = &mut alignment.segments;
 
@@ -223,18 +779,14 @@ fn vertex_properties_ui(ui: &mut egui::Ui, alignment_state: &mut AlignmentState)
 				continue;
 			};
 			turn_index += 1;
-			let vertex = turn.tangent_vertex;
 			egui::Grid::new(format!("turn_{i}"))
 				.num_columns(2)
 				.spacing(egui::Vec2::splat(2.0))
 				.show(ui, |ui| {
 					ui.label(format!("Turn {}:", turn_index));
-					ui.label(format!(
-						"({:.2}, {:.2}, {:.2})",
-						vertex.x, vertex.y, vertex.z,
-					));
+					plan_position_edit(ui, &mut turn.tangent_vertex, units, precision, sampler);
 					ui.end_row();
-					ui.label("Angle:");
+					let vertex = turn.tangent_vertex;
 					// Use shared constraints helper to determine slider max
 					let prev = control_points[i];
 					let next = control_points[i + 2];
@@ -245,33 +797,245 @@ fn vertex_properties_ui(ui: &mut egui::Ui, alignment_state: &mut AlignmentState)
 					if turn.circular_section_angle > max_angle {
 						turn.circular_section_angle = max_angle;
 					}
-					ui.add(
-						egui::Slider::new(&mut turn.circular_section_angle, 0.0..=max_angle)
-							.step_by(FRAC_PI_180)
-							.custom_parser(|s| s.parse::<f64>().ok().map(|f| f.to_radians()))
-							.custom_formatter(|val, _| format!("{:.0?}°", val.to_degrees())),
-					);
+					ui.label("Transition by:");
+					transition_spec_combo(ui, i, turn, max_angle);
 					ui.end_row();
+					match &mut turn.transition {
+						TransitionSpec::ByAngle => {
+							ui.label("Angle:");
+							ui.add(
+								egui::Slider::new(&mut turn.circular_section_angle, 0.0..=max_angle)
+									.step_by(FRAC_PI_180)
+									.custom_parser(|s| s.parse::<f64>().ok().map(|f| f.to_radians()))
+									.custom_formatter(|val, _| precision.format_degrees(val)),
+							);
+						}
+						TransitionSpec::ByLength(length) => {
+							ui.label("Length:");
+							ui.add(units.length_drag_value(length).range(0.0..=MAX_ARC_RADIUS));
+						}
+						TransitionSpec::ByParameter(parameter) => {
+							ui.label("A:");
+							ui.add(
+								units
+									.length_drag_value(parameter)
+									.range(0.0..=MAX_ARC_RADIUS),
+							);
+						}
+					}
+					ui.end_row();
+					if turn.transition != TransitionSpec::ByAngle {
+						ui.label("Angle:");
+						ui.label(precision.format_degrees(f64::from(turn.circular_section_angle)));
+						ui.end_row();
+					}
 					ui.label("Radius:");
 					// Enforce a minimum positive radius to avoid degenerate cases
 					if !turn.circular_section_radius.is_finite() || turn.circular_section_radius <= 0.0 {
 						turn.circular_section_radius = MIN_ARC_RADIUS;
 					}
-					ui.add(egui::Slider::new(
-						&mut turn.circular_section_radius,
-						MIN_ARC_RADIUS..=MAX_ARC_RADIUS,
-					));
+					ui.scope(|ui| {
+						let too_tight = below_min_radius.contains(&i);
+						if too_tight {
+							ui.visuals_mut().selection.bg_fill = egui::Color32::RED;
+						}
+						let response = ui.add(
+							egui::Slider::new(
+								&mut turn.circular_section_radius,
+								MIN_ARC_RADIUS..=MAX_ARC_RADIUS,
+							)
+							.custom_formatter(|val, _| units.format_radius(val))
+							.custom_parser(|s| s.parse::<f64>().ok().map(|v| units.meters_from_display(v)))
+							.trailing_fill(too_tight),
+						);
+						if too_tight {
+							response.on_hover_text("Radius is below the minimum for the design speed");
+						}
+					});
+					ui.end_row();
+					if let Some(parameter) = clothoid_parameters.get(i).copied().flatten() {
+						ui.label("Clothoid A:");
+						ui.label(units.format_length(parameter))
+							.on_hover_text("Transition parameter, A² = R·L");
+					}
 				});
 		}
 	}
 }
 
+/// The first value, and whether any of the others differ from it.
+fn common_value(mut values: impl Iterator<Item = f32>) -> (f32, bool) {
+	let first = values.next().unwrap_or_default();
+	(first, values.any(|value| value != first))
+}
+
+/// One radius and one angle slider for all Ctrl+clicked turns. A slider stays blank while the
+/// turns disagree, until it is dragged and every turn takes the new value.
+fn selected_turns_ui(
+	ui: &mut egui::Ui,
+	alignment_state: &mut AlignmentState,
+	selected: &SelectedPins,
+	units: Units,
+	precision: DisplayPrecision,
+) {
+	if selected.segments.len() < 2 || selected.alignment_id != alignment_state.current_alignment {
+		return;
+	}
+	let Some(alignment) = alignment_state.alignments.get_mut(&selected.alignment_id) else {
+		return;
+	};
+	let control_points = alignment.control_points();
+	let indices: Vec<usize> = selected
+		.segments
+		.iter()
+		.copied()
+		.filter(|i| {
+			alignment
+				.segments
+				.get(*i)
+				.is_some_and(|s| s.as_turn().is_some())
+		})
+		.collect();
+	let mut turns: Vec<(&mut TurnSegment, f32)> = alignment
+		.segments
+		.iter_mut()
+		.enumerate()
+		.filter(|(i, _)| indices.contains(i))
+		.filter_map(|(i, segment)| {
+			let turn = segment.as_turn_mut()?;
+			let max_angle = compute_max_angle(
+				control_points[i],
+				turn.tangent_vertex,
+				control_points[i + 2],
+			);
+			Some((turn, max_angle))
+		})
+		.collect();
+	if turns.len() < 2 {
+		return;
+	}
+
+	ui.separator();
+	ui.label(format!("{} selected turns:", turns.len()));
+	egui::Grid::new("selected_turns")
+		.num_columns(2)
+		.spacing(egui::Vec2::splat(2.0))
+		.show(ui, |ui| {
+			ui.label("Radius:");
+			let (mut radius, mixed) = common_value(turns.iter().map(|(t, _)| t.circular_section_radius));
+			let response = ui.add(
+				egui::Slider::new(&mut radius, MIN_ARC_RADIUS..=MAX_ARC_RADIUS)
+					.custom_formatter(|val, _| {
+						if mixed {
+							String::new()
+						} else {
+							units.format_radius(val)
+						}
+					})
+					.custom_parser(|s| s.parse::<f64>().ok().map(|v| units.meters_from_display(v))),
+			);
+			if response.changed() {
+				for (turn, _) in &mut turns {
+					turn.circular_section_radius = radius;
+				}
+			}
+			ui.end_row();
+
+			// Only turns transitioned by angle take the angle directly
+			let mut by_angle: Vec<_> = turns
+				.iter_mut()
+				.filter(|(t, _)| t.transition == TransitionSpec::ByAngle)
+				.collect();
+			if by_angle.is_empty() {
+				return;
+			}
+			ui.label("Angle:");
+			let max_angle = by_angle
+				.iter()
+				.map(|(_, max)| *max)
+				.fold(f32::INFINITY, f32::min);
+			let (angle, mixed) = common_value(by_angle.iter().map(|(t, _)| t.circular_section_angle));
+			let mut angle = angle.min(max_angle);
+			let response = ui.add(
+				egui::Slider::new(&mut angle, 0.0..=max_angle)
+					.step_by(FRAC_PI_180)
+					.custom_parser(|s| s.parse::<f64>().ok().map(|f| f.to_radians()))
+					.custom_formatter(|val, _| {
+						if mixed {
+							String::new()
+						} else {
+							precision.format_degrees(val)
+						}
+					}),
+			);
+			if response.changed() {
+				for (turn, _) in &mut by_angle {
+					turn.circular_section_angle = angle;
+				}
+			}
+			ui.end_row();
+		});
+}
+
+/// Writes the computed segments, clothoid parameters and arc centers included.
+fn export_geometry_json(geometry: &AlignmentGeometry, path: &Path) -> anyhow::Result<()> {
+	std::fs::write(path, serde_json::to_string_pretty(geometry)?)?;
+	Ok(())
+}
+
+fn chord_offsets_ui(ui: &mut egui::Ui, geometry: &AlignmentGeometry, units: Units) {
+	let arcs = geometry
+		.segments
+		.iter()
+		.filter_map(|segment| match segment {
+			GeometrySegment::Turn(turn) => Some(&turn.circular_arc),
+			GeometrySegment::Straight(_) => None,
+		});
+	for (turn_index, arc) in arcs.enumerate() {
+		ui.collapsing(format!("Turn {} chord offsets", turn_index + 1), |ui| {
+			egui::Grid::new(format!("chord_offsets_{turn_index}"))
+				.num_columns(2)
+				.spacing(egui::Vec2::splat(2.0))
+				.show(ui, |ui| {
+					ui.label("Along chord");
+					ui.label("Offset");
+					ui.end_row();
+					for (along, offset) in arc.chord_offsets(CHORD_OFFSET_POINTS) {
+						ui.label(units.format_length(f64::from(along)));
+						ui.label(units.format_length(f64::from(offset)));
+						ui.end_row();
+					}
+				});
+		});
+	}
+}
+
 fn alignment_creation_ui(
 	ui: &mut egui::Ui,
 	alignment_state: &mut AlignmentState,
+	points_of_interest: &mut PointsOfInterest,
 	start_pos: Vec3,
 	end_pos: Vec3,
 ) {
+	if !points_of_interest.points.is_empty() {
+		let alignment_id = alignment_state.current_alignment;
+		egui::Grid::new("endpoint_pois")
+			.num_columns(2)
+			.show(ui, |ui| {
+				for (label, endpoint) in [("Start at:", Endpoint::Start), ("End at:", Endpoint::End)] {
+					ui.label(label);
+					endpoint_poi_combo(
+						ui,
+						points_of_interest,
+						BoundEndpoint {
+							alignment_id,
+							endpoint,
+						},
+					);
+					ui.end_row();
+				}
+			});
+	}
 	ui.horizontal(|ui| {
 		ui.label("Turns:");
 
@@ -302,6 +1066,127 @@ fn alignment_creation_ui(
 			alignment_state.current_alignment = new_id;
 		}
 	});
+
+	ui.horizontal(|ui| {
+		ui.label("Templates:");
+		egui::ComboBox::from_id_salt("alignment_template")
+			.selected_text("Add from template")
+			.show_ui(ui, |ui| {
+				for template in AlignmentTemplate::ALL {
+					if ui.selectable_label(false, template.label()).clicked() {
+						let new_id = alignment_state.next_alignment_id;
+						alignment_state
+							.alignments
+							.insert(new_id, template.instantiate(start_pos, end_pos));
+						alignment_state.next_alignment_id += 1;
+						alignment_state.current_alignment = new_id;
+					}
+				}
+			});
+	});
+}
+
+/// Pick the point of interest an endpoint follows; "Free" leaves it where it is, draggable.
+fn endpoint_poi_combo(
+	ui: &mut egui::Ui,
+	points_of_interest: &mut PointsOfInterest,
+	endpoint: BoundEndpoint,
+) {
+	let current = points_of_interest.bound_to(endpoint);
+	let selected_text = current.map_or("Free", |index| {
+		points_of_interest.points[index].name.as_str()
+	});
+	let mut picked = current;
+	egui::ComboBox::from_id_salt(("endpoint_poi", endpoint.endpoint))
+		.selected_text(selected_text)
+		.show_ui(ui, |ui| {
+			ui.selectable_value(&mut picked, None, "Free");
+			for (index, point) in points_of_interest.points.iter().enumerate() {
+				ui.selectable_value(&mut picked, Some(index), point.name.as_str());
+			}
+		});
+	if picked != current {
+		points_of_interest.bind(endpoint, picked);
+	}
+}
+
+/// Add points of interest at the current start or end pin, then rename, move or delete them.
+fn points_of_interest_ui(
+	ui: &mut egui::Ui,
+	points_of_interest: &mut PointsOfInterest,
+	start_pos: Vec3,
+	end_pos: Vec3,
+	units: Units,
+	precision: DisplayPrecision,
+	sampler: &TerrainSampler<'_>,
+) {
+	let mut removed = None;
+	for (index, point) in points_of_interest.points.iter_mut().enumerate() {
+		ui.horizontal(|ui| {
+			ui.add(egui::TextEdit::singleline(&mut point.name).desired_width(100.0));
+			if ui.small_button("X").clicked() {
+				removed = Some(index);
+			}
+		});
+		plan_position_edit(ui, &mut point.position, units, precision, sampler);
+		if !point.bound.is_empty() {
+			ui.label(format!("Moves {} alignment endpoint(s)", point.bound.len()));
+		}
+	}
+	if let Some(index) = removed {
+		points_of_interest.points.remove(index);
+	}
+
+	ui.horizontal(|ui| {
+		ui.add(egui::TextEdit::singleline(&mut points_of_interest.new_name).hint_text("Name"));
+		let name = points_of_interest.new_name.trim().to_owned();
+		for (label, position) in [("Add at start", start_pos), ("Add at end", end_pos)] {
+			if ui
+				.add_enabled(!name.is_empty(), egui::Button::new(label))
+				.clicked()
+			{
+				points_of_interest.points.push(PointOfInterest {
+					name: name.clone(),
+					position,
+					bound: Vec::new(),
+				});
+				points_of_interest.new_name.clear();
+			}
+		}
+	});
+	points_of_interest.handle_save_operation_ui(ui, "Save Points of Interest");
+}
+
+/// Create an alignment between the current endpoints that follows a valley or ridge line.
+fn terrain_trace_ui(
+	ui: &mut egui::Ui,
+	alignment_state: &mut AlignmentState,
+	start_pos: Vec3,
+	end_pos: Vec3,
+	sampler: &TerrainSampler<'_>,
+) {
+	ui.horizontal(|ui| {
+		ui.label("Trace:");
+		for kind in [TraceKind::Valley, TraceKind::Ridge] {
+			if !ui.button(kind.label()).clicked() {
+				continue;
+			}
+			let Some(alignment) = traced_alignment(
+				start_pos,
+				end_pos,
+				sampler.heightmap,
+				sampler.settings,
+				kind,
+			) else {
+				warn!("No {} path found between the endpoints", kind.label());
+				continue;
+			};
+			let new_id = alignment_state.next_alignment_id;
+			alignment_state.alignments.insert(new_id, alignment);
+			alignment_state.next_alignment_id += 1;
+			alignment_state.current_alignment = new_id;
+		}
+	});
 }
 
 fn vertical_profile_ui(
@@ -309,6 +1194,7 @@ fn vertical_profile_ui(
 	alignment_state: &mut AlignmentState,
 	start_pos: Vec3,
 	end_pos: Vec3,
+	units: Units,
 ) {
 	let current_id = alignment_state.current_alignment;
 	let Some(alignment) = alignment_state.alignments.get_mut(&current_id) else {
@@ -317,40 +1203,64 @@ fn vertical_profile_ui(
 	};
 
 	let total_length = calculate_alignment_geometry(start_pos, end_pos, alignment).total_length();
+	ui.label(format!(
+		"Length: {}",
+		units.format_distance(f64::from(total_length))
+	));
 
 	let VerticalProfileData::Pvi(profile) = &mut alignment.vertical_profile else {
 		return;
 	};
 
+	ui.horizontal(|ui| {
+		ui.label("Vertical curve length:")
+			.on_hover_text("Parabola rounded over each grade change; zero leaves sharp breaks");
+		ui.add(
+			units
+				.length_drag_value(&mut profile.vertical_curve_length)
+				.speed(1.0)
+				.range(0.0..=total_length),
+		);
+	});
+
 	let mut index_to_delete: Option<usize> = None;
 	let mut changed = false;
 
 	egui::Grid::new("pvi_table")
-		.num_columns(3)
+		.num_columns(4)
 		.spacing(egui::Vec2::splat(2.0))
 		.show(ui, |ui| {
 			ui.label("Station");
 			ui.label("Elevation");
+			ui.label("Grade");
 			ui.label("");
 			ui.end_row();
 
+			let mut previous: Option<(f32, f32)> = None;
 			for (i, pvi) in profile.points.iter_mut().enumerate() {
 				if ui
 					.add(
-						egui::DragValue::new(&mut pvi.station)
+						units
+							.length_drag_value(&mut pvi.station)
 							.speed(1.0)
-							.suffix(" m")
 							.range(0.0..=total_length),
 					)
 					.changed()
 				{
 					changed = true;
 				}
-				ui.add(
-					egui::DragValue::new(&mut pvi.elevation)
-						.speed(0.1)
-						.suffix(" m"),
-				);
+				ui.add(units.length_drag_value(&mut pvi.elevation).speed(0.1));
+				// Grade of the tangent arriving at this PVI
+				match previous {
+					Some((prev_station, prev_elevation)) if pvi.station > prev_station => {
+						let grade = (pvi.elevation - prev_elevation) / (pvi.station - prev_station);
+						ui.label(units.format_grade(f64::from(grade)));
+					}
+					_ => {
+						ui.label("");
+					}
+				}
+				previous = Some((pvi.station, pvi.elevation));
 				if ui.small_button("X").clicked() {
 					index_to_delete = Some(i);
 				}
@@ -0,0 +1,182 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, egui};
+
+use crate::alignment::{AlignmentState, prepare_loaded_alignments};
+use crate::saveable::SaveableSettings;
+use crate::terrain;
+
+pub(crate) const MIN_AUTOSAVE_INTERVAL_SECS: f32 = 5.0;
+
+/// Periodically writes alignments and terrain settings to `*.autosave.json` sidecar files, and
+/// offers to restore them on startup when they hold newer work than the saved files.
+pub(crate) struct AutosavePlugin;
+
+impl Plugin for AutosavePlugin {
+	fn build(&self, app: &mut App) {
+		app
+			.init_resource::<AutosaveSettings>()
+			// After the alignment startup systems have filled in the default alignment
+			.add_systems(PostStartup, find_newer_autosaves)
+			.add_systems(Update, autosave)
+			.add_systems(bevy_egui::EguiPrimaryContextPass, restore_autosave_dialog);
+	}
+}
+
+#[derive(Resource, Debug, Clone)]
+pub(crate) struct AutosaveSettings {
+	pub enabled: bool,
+	pub interval_secs: f32,
+	timer: Timer,
+}
+
+impl Default for AutosaveSettings {
+	fn default() -> Self {
+		let interval_secs = 60.0;
+		Self {
+			enabled: true,
+			interval_secs,
+			timer: Timer::from_seconds(interval_secs, TimerMode::Repeating),
+		}
+	}
+}
+
+/// Autosaves found at startup that the user hasn't restored or discarded yet.
+#[derive(Resource, Default)]
+struct PendingAutosaveRestore {
+	alignments: Option<AlignmentState>,
+	terrain: Option<terrain::Settings>,
+}
+
+/// The autosave, if it was written after the main file and holds something other than `current`.
+fn newer_autosave<T: SaveableSettings>(current: &T) -> Option<T> {
+	if !T::autosave_is_newer() {
+		return None;
+	}
+	let autosaved = T::load_autosave()
+		.inspect_err(|e| error!("Failed to read {}: {e}", T::autosave_filename()))
+		.ok()?;
+	let differs = serde_json::to_value(&autosaved).ok() != serde_json::to_value(current).ok();
+	differs.then_some(autosaved)
+}
+
+fn find_newer_autosaves(
+	mut commands: Commands,
+	alignment_state: Res<AlignmentState>,
+	terrain_settings: Res<terrain::Settings>,
+) {
+	let pending = PendingAutosaveRestore {
+		alignments: newer_autosave(&*alignment_state),
+		terrain: newer_autosave(&*terrain_settings),
+	};
+	if pending.alignments.is_some() || pending.terrain.is_some() {
+		commands.insert_resource(pending);
+	}
+}
+
+fn autosave(
+	time: Res<Time>,
+	mut settings: ResMut<AutosaveSettings>,
+	alignment_state: Res<AlignmentState>,
+	terrain_settings: Res<terrain::Settings>,
+	pending: Option<Res<PendingAutosaveRestore>>,
+) {
+	// Don't overwrite an autosave the user hasn't decided about yet
+	if !settings.enabled || pending.is_some() {
+		return;
+	}
+	let interval = Duration::from_secs_f32(settings.interval_secs.max(MIN_AUTOSAVE_INTERVAL_SECS));
+	if settings.timer.duration() != interval {
+		settings.timer.set_duration(interval);
+	}
+	if !settings.timer.tick(time.delta()).just_finished() {
+		return;
+	}
+	for (label, result) in [
+		("alignments", alignment_state.save_autosave()),
+		("terrain settings", terrain_settings.save_autosave()),
+	] {
+		if let Err(e) = result {
+			error!("Failed to autosave {label}: {e}");
+		}
+	}
+	debug!("Autosaved alignments and terrain settings");
+}
+
+fn restore_autosave_dialog(
+	mut commands: Commands,
+	mut contexts: EguiContexts,
+	mut pending: Option<ResMut<PendingAutosaveRestore>>,
+	mut alignment_state: ResMut<AlignmentState>,
+	mut terrain_settings: ResMut<terrain::Settings>,
+) {
+	let Some(pending) = pending.as_mut() else {
+		return;
+	};
+	let Ok(ctx) = contexts.ctx_mut() else {
+		return;
+	};
+	egui::Window::new("Restore autosave?")
+		.anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+		.collapsible(false)
+		.resizable(false)
+		.show(ctx, |ui| {
+			ui.label("Autosaves newer than your saved files were found for:");
+			if pending.alignments.is_some() {
+				ui.label(format!(
+					"• Alignments ({})",
+					AlignmentState::autosave_filename()
+				));
+			}
+			if pending.terrain.is_some() {
+				ui.label(format!(
+					"• Terrain settings ({})",
+					terrain::Settings::autosave_filename()
+				));
+			}
+			ui.horizontal(|ui| {
+				if ui.button("Restore").clicked() {
+					if let Some(alignments) = pending.alignments.take() {
+						*alignment_state = prepare_loaded_alignments(alignments);
+					}
+					if let Some(settings) = pending.terrain.take() {
+						*terrain_settings = settings;
+					}
+					commands.remove_resource::<PendingAutosaveRestore>();
+				}
+				if ui.button("Discard").clicked() {
+					commands.remove_resource::<PendingAutosaveRestore>();
+				}
+			});
+		});
+}
+
+pub(crate) fn autosave_settings_ui(ui: &mut egui::Ui, settings: &mut AutosaveSettings) {
+	ui.horizontal(|ui| {
+		ui.checkbox(&mut settings.enabled, "");
+		ui.add_enabled(
+			settings.enabled,
+			egui::DragValue::new(&mut settings.interval_secs)
+				.range(MIN_AUTOSAVE_INTERVAL_SECS..=3600.0)
+				.suffix(" s"),
+		);
+	});
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn autosave_sidecar_sits_next_to_the_main_file() {
+		assert_eq!(
+			AlignmentState::autosave_filename(),
+			"alignments.autosave.json"
+		);
+		assert_eq!(
+			terrain::Settings::autosave_filename(),
+			"terrain_settings.autosave.json"
+		);
+	}
+}
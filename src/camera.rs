@@ -1,9 +1,17 @@
-use bevy::{camera::visibility::RenderLayers, prelude::Mut, prelude::*};
+use bevy::{
+	camera::{ScalingMode, visibility::RenderLayers},
+	prelude::Mut,
+	prelude::*,
+};
+use bevy_egui::EguiContexts;
 use bevy_panorbit_camera::{PanOrbitCamera, PanOrbitCameraPlugin};
 use bevy_tweening::{AnimTarget, Lens, Tween, TweenAnim, TweeningPlugin};
+use serde::{Deserialize, Serialize};
 use std::f32::consts::PI;
 use std::time::Duration;
 
+use crate::alignment::CachedAlignmentGeometry;
+use crate::saveable::SaveableSettings;
 use crate::terrain;
 
 /// Marker component for the primary 3D camera used for raycasting and picking.
@@ -20,11 +28,18 @@ impl Plugin for CameraPlugin {
 			.add_plugins(TweeningPlugin)
 			// .add_plugins(crate::hud::CameraDebugHud)
 			.insert_resource(CameraMode::default())
+			.insert_resource(CameraBookmarks::load_or_default())
+			.insert_resource(SavedCameraView::load_or_default())
 			.add_systems(Startup, setup)
+			.add_systems(Last, save_camera_view_on_exit)
 			.add_systems(
 				Update,
 				(
 					toggle_camera,
+					level_camera_roll,
+					fit_view_to_alignment,
+					store_camera_bookmark,
+					restore_camera_bookmark,
 					cleanup_completed_tweens,
 					disable_camera_during_transition,
 				),
@@ -32,26 +47,43 @@ impl Plugin for CameraPlugin {
 	}
 }
 
-fn setup(mut commands: Commands, settings: Res<terrain::Settings>) {
-	let world_size = terrain::spatial::world_size(&settings);
-	let (transform, perspective) = create_perspective_angled_state(world_size + 4206.9); // Just a random value to test its smooth
+fn setup(
+	mut commands: Commands,
+	settings: Res<terrain::Settings>,
+	saved_view: Res<SavedCameraView>,
+	mut camera_mode: ResMut<CameraMode>,
+) {
+	let mut pan_orbit = PanOrbitCamera {
+		button_orbit: MouseButton::Right,
+		button_pan: MouseButton::Left,
+		modifier_pan: Some(KeyCode::ShiftLeft),
+		..default()
+	};
+	let (transform, projection) = if let Some(view) = saved_view.view {
+		view.apply_to(&mut pan_orbit);
+		// Settle straight into the saved mode; a transition would fly away from the saved view
+		(camera_mode.current_mode, camera_mode.orthographic_size) = match view.projection {
+			BookmarkProjection::Perspective { .. } => (CameraState::Perspective, None),
+			BookmarkProjection::Orthographic { width } => (CameraState::Orthographic, Some(width)),
+		};
+		(view.transform(), view.create_projection())
+	} else {
+		let world_size = terrain::spatial::world_size(&settings);
+		let (transform, perspective) = create_perspective_angled_state(world_size + 4206.9); // Just a random value to test its smooth
+		(transform, Projection::from(perspective))
+	};
 
 	commands.spawn((
 		PrimaryCamera3d,
 		transform,
-		Projection::from(perspective),
+		projection,
 		//Camera3d::default(),
 		Camera {
 			order: 0,
 			..default()
 		},
 		RenderLayers::layer(0),
-		PanOrbitCamera {
-			button_orbit: MouseButton::Right,
-			button_pan: MouseButton::Left,
-			modifier_pan: Some(KeyCode::ShiftLeft),
-			..default()
-		},
+		pan_orbit,
 	));
 
 	commands.spawn((
@@ -84,6 +116,8 @@ pub struct CameraMode {
 	transition_timer: Timer,
 	pub user_enabled: bool,
 	active_tweens: Vec<Entity>,
+	/// View width to rest at once an orthographic transition ends; `None` fits the terrain.
+	orthographic_size: Option<f32>,
 }
 
 impl CameraMode {
@@ -116,6 +150,7 @@ impl Default for CameraMode {
 			transition_timer: Timer::from_seconds(0.0, TimerMode::Repeating),
 			user_enabled: true,
 			active_tweens: Vec::new(),
+			orthographic_size: None,
 		}
 	}
 }
@@ -130,6 +165,47 @@ impl CameraMode {
 	fn register_active_tweens(&mut self, tweens: impl IntoIterator<Item = Entity>) {
 		self.active_tweens = tweens.into_iter().collect();
 	}
+
+	/// Tweens the camera's roll away, holding off the orbit controls and other transitions
+	/// until it is done.
+	fn begin_roll_correction(
+		&mut self,
+		commands: &mut Commands,
+		camera_entity: Entity,
+		start: Quat,
+		end: Quat,
+	) {
+		self.is_transitioning = true;
+		self.transition_timer = Timer::from_seconds(LEVEL_ROLL_TIME, TimerMode::Once);
+		self.clear_active_tweens(commands);
+		let tween = Tween::new::<Transform, _>(
+			EaseFunction::SmoothStep,
+			Duration::from_secs_f32(LEVEL_ROLL_TIME),
+			RollCorrectionLens { start, end },
+		);
+		let tween_entity = commands
+			.spawn((
+				TweenAnim::new(tween),
+				AnimTarget::component::<Transform>(camera_entity),
+			))
+			.id();
+		self.register_active_tweens([tween_entity]);
+	}
+
+	fn begin_transition(
+		&mut self,
+		commands: &mut Commands,
+		camera_entity: Entity,
+		new_mode: CameraState,
+		transition: DollyTransition,
+	) {
+		self.is_transitioning = true;
+		self.transition_timer = Timer::from_seconds(TOTAL_TRANSITION_TIME, TimerMode::Once);
+		self.clear_active_tweens(commands);
+		let tweens = transition.spawn_tweens(commands, camera_entity);
+		self.register_active_tweens(tweens);
+		self.current_mode = new_mode;
+	}
 }
 
 #[derive(PartialEq, Debug, Clone, Copy, Default)]
@@ -156,9 +232,11 @@ struct DollyZoomLens {
 	end_rot: Quat,
 	start_size: f32,
 	end_size: f32,
+	start_focus: Vec3,
+	end_focus: Vec3,
 }
 
-const PADDING: f32 = 500.0;
+pub(crate) const PADDING: f32 = 500.0;
 
 impl Lens<Transform> for DollyZoomLens {
 	fn lerp(&mut self, mut target: Mut<Transform>, ratio: f32) {
@@ -167,8 +245,9 @@ impl Lens<Transform> for DollyZoomLens {
 		let distance = dolly_zoom_distance(size, fov);
 		let rot = self.start_rot.slerp(self.end_rot, ratio);
 		let direction = rot * Vec3::Z;
+		let focus = self.start_focus.lerp(self.end_focus, ratio);
 		*target = Transform {
-			translation: direction * distance,
+			translation: focus + direction * distance,
 			rotation: rot,
 			scale: Vec3::ONE,
 		};
@@ -183,6 +262,8 @@ struct PanOrbitCameraLens {
 	end_rot: Quat,
 	start_size: f32,
 	end_size: f32,
+	start_focus: Vec3,
+	end_focus: Vec3,
 }
 
 impl Lens<PanOrbitCamera> for PanOrbitCameraLens {
@@ -200,15 +281,16 @@ impl Lens<PanOrbitCamera> for PanOrbitCameraLens {
 		let yaw = (end_yaw - start_yaw).mul_add(ratio, start_yaw);
 		let pitch = (end_pitch - start_pitch).mul_add(ratio, start_pitch);
 		let radius = distance;
+		let focus = self.start_focus.lerp(self.end_focus, ratio);
 
 		target.yaw = Some(yaw);
 		target.pitch = Some(pitch);
 		target.radius = Some(radius);
-		target.focus = Vec3::ZERO;
+		target.focus = focus;
 		target.target_yaw = yaw;
 		target.target_pitch = pitch;
 		target.target_radius = radius;
-		target.target_focus = Vec3::ZERO;
+		target.target_focus = focus;
 	}
 }
 
@@ -233,16 +315,404 @@ impl Lens<Projection> for ProjectionFovLens {
 	}
 }
 
+/// Only rotates about the view direction, so position and heading are untouched.
+#[derive(Debug)]
+struct RollCorrectionLens {
+	start: Quat,
+	end: Quat,
+}
+
+impl Lens<Transform> for RollCorrectionLens {
+	fn lerp(&mut self, mut target: Mut<Transform>, ratio: f32) {
+		target.rotation = self.start.slerp(self.end, ratio);
+	}
+}
+
+const LEVEL_ROLL_KEY: KeyCode = KeyCode::KeyL;
+const LEVEL_ROLL_TIME: f32 = 0.3;
+
+fn level_camera_roll(
+	keyboard_input: Res<ButtonInput<KeyCode>>,
+	mut camera_mode: ResMut<CameraMode>,
+	mut commands: Commands,
+	camera_query: Single<(Entity, &Transform), With<PrimaryCamera3d>>,
+	mut egui_contexts: EguiContexts,
+) {
+	if !keyboard_input.just_pressed(LEVEL_ROLL_KEY)
+		|| camera_mode.is_transitioning
+		|| egui_wants_keyboard(&mut egui_contexts)
+	{
+		return;
+	}
+	let (camera_entity, transform) = *camera_query;
+	let start = transform.rotation;
+	let end = level_roll(start);
+	if start.angle_between(end) <= f32::EPSILON {
+		return;
+	}
+	camera_mode.begin_roll_correction(&mut commands, camera_entity, start, end);
+}
+
+// Removes any roll so the camera's up vector lies in the plane of +Y and the view direction.
+// Looking straight up or down has no defined roll, so the rotation is returned unchanged.
+fn level_roll(rotation: Quat) -> Quat {
+	let forward = rotation * Vec3::NEG_Z;
+	if forward.cross(Vec3::Y).length_squared() <= f32::EPSILON {
+		return rotation;
+	}
+	Transform::default().looking_to(forward, Vec3::Y).rotation
+}
+
+/// Moves the orbit focus and radius together, keeping the pan-orbit targets in step so it
+/// doesn't pull the camera back afterwards.
+#[derive(Debug)]
+struct FocusRadiusLens {
+	start_focus: Vec3,
+	end_focus: Vec3,
+	start_radius: f32,
+	end_radius: f32,
+}
+
+impl Lens<PanOrbitCamera> for FocusRadiusLens {
+	fn lerp(&mut self, mut target: Mut<PanOrbitCamera>, ratio: f32) {
+		let focus = self.start_focus.lerp(self.end_focus, ratio);
+		let radius = (self.end_radius - self.start_radius).mul_add(ratio, self.start_radius);
+		target.focus = focus;
+		target.target_focus = focus;
+		target.radius = Some(radius);
+		target.target_radius = radius;
+	}
+}
+
+const FIT_VIEW_KEY: KeyCode = KeyCode::Home;
+const FIT_VIEW_TIME: f32 = 0.6;
+/// Framed width as a multiple of the alignment's bounding-box diagonal.
+const FIT_VIEW_MARGIN: f32 = 1.2;
+/// Keeps very short alignments from being framed from inside the track.
+const MIN_FIT_VIEW_SIZE: f32 = 50.0;
+
+/// Centers the orbit on the alignment and, in perspective, backs off until its bounds fill the
+/// view.
+fn fit_view_to_alignment(
+	keyboard_input: Res<ButtonInput<KeyCode>>,
+	camera_mode: Res<CameraMode>,
+	mut commands: Commands,
+	camera_query: Single<(Entity, &Projection, &PanOrbitCamera), With<PrimaryCamera3d>>,
+	geometry_cache: Res<CachedAlignmentGeometry>,
+	terrain_heightmap: Single<&terrain::HeightMap>,
+	terrain_settings: Res<terrain::Settings>,
+	mut egui_contexts: EguiContexts,
+) {
+	if !keyboard_input.just_pressed(FIT_VIEW_KEY)
+		|| camera_mode.is_transitioning
+		|| egui_wants_keyboard(&mut egui_contexts)
+	{
+		return;
+	}
+	let Some((_, _, geometry)) = geometry_cache.get() else {
+		return;
+	};
+	let (camera_entity, projection, pan_orbit) = *camera_query;
+	let (min, max) = geometry.bounds();
+	let center = (min + max) / 2.0;
+	let end_focus = center.with_y(terrain::calculate_terrain_height(
+		center,
+		&terrain_heightmap,
+		&terrain_settings,
+	));
+	let start_radius = pan_orbit.radius.unwrap_or(pan_orbit.target_radius);
+	// Orthographic zoom is the projection width, so only the focus moves there.
+	let end_radius = match projection {
+		Projection::Perspective(persp) => {
+			let size = ((max - min).length() * FIT_VIEW_MARGIN).max(MIN_FIT_VIEW_SIZE);
+			dolly_zoom_distance(size, persp.fov)
+		}
+		_ => start_radius,
+	};
+	let tween = Tween::new::<PanOrbitCamera, _>(
+		EaseFunction::SmoothStep,
+		Duration::from_secs_f32(FIT_VIEW_TIME),
+		FocusRadiusLens {
+			start_focus: pan_orbit.focus,
+			end_focus,
+			start_radius,
+			end_radius,
+		},
+	);
+	commands.spawn((
+		TweenAnim::new(tween),
+		AnimTarget::component::<PanOrbitCamera>(camera_entity),
+	));
+}
+
+/// Keys `1`..`9`; hold Ctrl to store the current view, press alone to fly back to it.
+const BOOKMARK_KEYS: [KeyCode; 9] = [
+	KeyCode::Digit1,
+	KeyCode::Digit2,
+	KeyCode::Digit3,
+	KeyCode::Digit4,
+	KeyCode::Digit5,
+	KeyCode::Digit6,
+	KeyCode::Digit7,
+	KeyCode::Digit8,
+	KeyCode::Digit9,
+];
+
+/// The parts of a [`Projection`] a bookmark needs to restore.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum BookmarkProjection {
+	Perspective { fov: f32 },
+	Orthographic { width: f32 },
+}
+
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CameraBookmarks {
+	/// Slot `i` belongs to key `i + 1`.
+	pub slots: Vec<Option<SavedView>>,
+}
+
+impl CameraBookmarks {
+	pub fn get(&self, slot: usize) -> Option<&SavedView> {
+		self.slots.get(slot).and_then(Option::as_ref)
+	}
+
+	pub fn set(&mut self, slot: usize, bookmark: SavedView) {
+		if self.slots.len() <= slot {
+			self.slots.resize(slot + 1, None);
+		}
+		self.slots[slot] = Some(bookmark);
+	}
+}
+
+impl SaveableSettings for CameraBookmarks {
+	fn filename() -> &'static str {
+		"camera_bookmarks.json"
+	}
+}
+
+/// A pan-orbit view and its projection: what a bookmark flies back to, and where the camera was
+/// looking when the app last exited.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SavedView {
+	pub projection: BookmarkProjection,
+	pub yaw: f32,
+	pub pitch: f32,
+	pub radius: f32,
+	pub focus: Vec3,
+}
+
+impl SavedView {
+	fn capture(pan_orbit: &PanOrbitCamera, projection: &Projection) -> Option<Self> {
+		let projection = match projection {
+			Projection::Perspective(p) => BookmarkProjection::Perspective { fov: p.fov },
+			Projection::Orthographic(o) => BookmarkProjection::Orthographic {
+				width: o.area.width(),
+			},
+			Projection::Custom(_) => return None,
+		};
+		Some(Self {
+			projection,
+			yaw: pan_orbit.yaw?,
+			pitch: pan_orbit.pitch?,
+			radius: pan_orbit.radius?,
+			focus: pan_orbit.focus,
+		})
+	}
+
+	fn apply_to(&self, pan_orbit: &mut PanOrbitCamera) {
+		pan_orbit.yaw = Some(self.yaw);
+		pan_orbit.pitch = Some(self.pitch);
+		pan_orbit.radius = Some(self.radius);
+		pan_orbit.focus = self.focus;
+		pan_orbit.target_yaw = self.yaw;
+		pan_orbit.target_pitch = self.pitch;
+		pan_orbit.target_radius = self.radius;
+		pan_orbit.target_focus = self.focus;
+	}
+
+	/// The camera transform the pan-orbit controller derives from these angles.
+	fn transform(&self) -> Transform {
+		let rotation = Quat::from_euler(EulerRot::YXZ, self.yaw, -self.pitch, 0.0);
+		Transform {
+			translation: self.focus + rotation * Vec3::Z * self.radius,
+			rotation,
+			scale: Vec3::ONE,
+		}
+	}
+
+	/// View width at the focus, which is what the dolly-zoom tweens interpolate.
+	fn size(&self) -> f32 {
+		match self.projection {
+			BookmarkProjection::Perspective { fov } => dolly_zoom_width(self.radius, fov),
+			BookmarkProjection::Orthographic { width } => width,
+		}
+	}
+
+	/// Dolly and pan from the current camera state to this view.
+	fn transition_from(
+		&self,
+		start_fov: f32,
+		start_rot: Quat,
+		start_size: f32,
+		start_focus: Vec3,
+	) -> DollyTransition {
+		let end_fov = match self.projection {
+			BookmarkProjection::Perspective { fov } => fov,
+			BookmarkProjection::Orthographic { .. } => CLOSE_TO_ORTHOGRAPHIC_FOV,
+		};
+		DollyTransition {
+			start_fov,
+			end_fov,
+			start_rot,
+			end_rot: self.transform().rotation,
+			start_size,
+			end_size: self.size(),
+			start_focus,
+			end_focus: self.focus,
+		}
+	}
+
+	fn create_projection(&self) -> Projection {
+		match self.projection {
+			BookmarkProjection::Perspective { fov } => {
+				Projection::from(create_perspective_projection(fov))
+			}
+			BookmarkProjection::Orthographic { width } => {
+				let (orthographic, distance) = create_orthographic_projection(width);
+				// The far plane has to reach past the focus from wherever the view was left
+				Projection::Orthographic(OrthographicProjection {
+					far: orthographic.far.max(2.0 * self.radius.max(distance)),
+					..orthographic
+				})
+			}
+		}
+	}
+}
+
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SavedCameraView {
+	pub view: Option<SavedView>,
+}
+
+impl SaveableSettings for SavedCameraView {
+	fn filename() -> &'static str {
+		"camera_state.json"
+	}
+}
+
+fn save_camera_view_on_exit(
+	mut exits: MessageReader<AppExit>,
+	camera_mode: Res<CameraMode>,
+	camera_query: Single<(&PanOrbitCamera, &Projection), With<PrimaryCamera3d>>,
+) {
+	if exits.read().last().is_none() {
+		return;
+	}
+	// Mid-transition the view is neither mode, so keep whatever was saved before
+	if camera_mode.is_transitioning {
+		return;
+	}
+	let (pan_orbit, projection) = *camera_query;
+	let Some(view) = SavedView::capture(pan_orbit, projection) else {
+		return;
+	};
+	if let Err(e) = (SavedCameraView { view: Some(view) }).save() {
+		error!("Failed to save camera view: {e}");
+	}
+}
+
+/// Whether egui has keyboard focus, in which case shortcut keys are text being typed.
+fn egui_wants_keyboard(contexts: &mut EguiContexts) -> bool {
+	contexts
+		.ctx_mut()
+		.is_ok_and(|ctx| ctx.wants_keyboard_input())
+}
+
+fn pressed_bookmark_slot(keyboard_input: &ButtonInput<KeyCode>) -> Option<usize> {
+	BOOKMARK_KEYS
+		.iter()
+		.position(|key| keyboard_input.just_pressed(*key))
+}
+
+fn store_camera_bookmark(
+	keyboard_input: Res<ButtonInput<KeyCode>>,
+	mut bookmarks: ResMut<CameraBookmarks>,
+	camera_query: Single<(&PanOrbitCamera, &Projection), With<PrimaryCamera3d>>,
+	mut egui_contexts: EguiContexts,
+) {
+	if !keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+		|| egui_wants_keyboard(&mut egui_contexts)
+	{
+		return;
+	}
+	let Some(slot) = pressed_bookmark_slot(&keyboard_input) else {
+		return;
+	};
+	let (pan_orbit, projection) = *camera_query;
+	let Some(bookmark) = SavedView::capture(pan_orbit, projection) else {
+		return;
+	};
+	bookmarks.set(slot, bookmark);
+	if let Err(e) = bookmarks.save() {
+		error!("Failed to save camera bookmarks: {e}");
+	} else {
+		debug!("Stored camera bookmark {}", slot + 1);
+	}
+}
+
+fn restore_camera_bookmark(
+	keyboard_input: Res<ButtonInput<KeyCode>>,
+	bookmarks: Res<CameraBookmarks>,
+	mut camera_mode: ResMut<CameraMode>,
+	mut commands: Commands,
+	camera_query: Single<(Entity, &Transform, &Projection, &PanOrbitCamera), With<PrimaryCamera3d>>,
+	mut egui_contexts: EguiContexts,
+) {
+	if camera_mode.is_transitioning
+		|| keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+		|| egui_wants_keyboard(&mut egui_contexts)
+	{
+		return;
+	}
+	let Some(bookmark) = pressed_bookmark_slot(&keyboard_input).and_then(|slot| bookmarks.get(slot))
+	else {
+		return;
+	};
+	let (camera_entity, current_transform, current_projection, pan_orbit) = *camera_query;
+	let (start_fov, start_size) = resume_dolly_start(
+		&mut commands,
+		camera_entity,
+		current_transform,
+		current_projection,
+		pan_orbit.focus,
+	);
+	let transition = bookmark.transition_from(
+		start_fov,
+		current_transform.rotation,
+		start_size,
+		pan_orbit.focus,
+	);
+	let (new_mode, orthographic_size) = match bookmark.projection {
+		BookmarkProjection::Perspective { .. } => (CameraState::Perspective, None),
+		BookmarkProjection::Orthographic { width } => (CameraState::Orthographic, Some(width)),
+	};
+	camera_mode.orthographic_size = orthographic_size;
+	camera_mode.begin_transition(&mut commands, camera_entity, new_mode, transition);
+}
+
 // Here's how the state transition works:
 // Whenever the user presses the toggle key, the camera will transition to the next state.
 // Perspective → Orthographic: Animate FOV to a very small value and move the camera to a top-down
-// position in a single tween. Orthographic → Perspective: Animate FOV to the angled value and move
-// the camera to the angled position in a single tween. There is no explicit orthographic projection
-// anymore; everything is handled with perspective projection and FOV/transform tweening.
+// position in a single tween. Once the tween finishes, swap to a real orthographic projection so
+// the resting top-down view doesn't suffer from the depth precision of a nearly-zero FOV.
+// Orthographic → Perspective: Swap back to the nearly-zero FOV perspective projection, then
+// animate FOV to the angled value and move the camera to the angled position in a single tween.
 
 // Transition timing constants
 const TOTAL_TRANSITION_TIME: f32 = 1.0;
 const CLOSE_TO_ORTHOGRAPHIC_FOV: f32 = 1e-3;
+/// Orthographic camera distance from the focus, as a multiple of the view width.
+const ORTHOGRAPHIC_DISTANCE_FACTOR: f32 = 1.0;
 
 fn toggle_camera(
 	keyboard_input: Res<ButtonInput<KeyCode>>,
@@ -252,164 +722,166 @@ fn toggle_camera(
 	settings: Res<terrain::Settings>,
 ) {
 	if keyboard_input.just_pressed(KeyCode::KeyT) && !camera_mode.is_transitioning {
-		let (camera_entity, current_transform, current_projection, _panorbit_camera) = *camera_query;
+		let (camera_entity, current_transform, current_projection, pan_orbit) = *camera_query;
 		let new_mode = camera_mode.current_mode.next();
-		camera_mode.is_transitioning = true;
-
-		camera_mode.transition_timer = Timer::from_seconds(TOTAL_TRANSITION_TIME, TimerMode::Once);
 
 		let world_size = terrain::spatial::world_size(&settings);
 
-		match (camera_mode.current_mode, new_mode) {
+		let transition = match (camera_mode.current_mode, new_mode) {
 			// Perspective → Orthographic: 1-stage transition
 			(CameraState::Perspective, CameraState::Orthographic) => {
 				let end_size = world_size + PADDING; // TODO: Use settings.terrain.world_length().max(settings.terrain.world_width()) instead of hardcoded value
-				let end_fov = CLOSE_TO_ORTHOGRAPHIC_FOV;
 				let start_fov = if let Projection::Perspective(p) = current_projection {
 					p.fov
 				} else {
 					panic!("Expected perspective projection");
 				};
-				let start_rot = current_transform.rotation;
-				let end_rot = Quat::from_axis_angle(Vec3::Y, 90.0_f32.to_radians())
-					* Quat::from_axis_angle(Vec3::X, -89.9_f32.to_radians());
 				// Calculate current camera's effective size from its position and FOV
-				let current_distance = current_transform.translation.length();
-				let current_size = dolly_zoom_width(current_distance, start_fov);
-				let transform_tween = Tween::new::<Transform, _>(
-					EaseFunction::SmoothStep,
-					Duration::from_secs_f32(TOTAL_TRANSITION_TIME),
-					DollyZoomLens {
-						start_fov,
-						end_fov,
-						start_rot,
-						end_rot,
-						start_size: current_size,
-						end_size,
-					},
-				);
-				let fov_tween = Tween::new::<Projection, _>(
-					EaseFunction::SmoothStep,
-					Duration::from_secs_f32(TOTAL_TRANSITION_TIME),
-					ProjectionFovLens {
-						start: start_fov,
-						end: end_fov,
-					},
-				);
-				let pan_orbit_tween = Tween::new::<PanOrbitCamera, _>(
-					EaseFunction::SmoothStep,
-					Duration::from_secs_f32(TOTAL_TRANSITION_TIME),
-					PanOrbitCameraLens {
-						start_fov,
-						end_fov,
-						start_rot,
-						end_rot,
-						start_size: current_size,
-						end_size,
-					},
-				);
-				camera_mode.clear_active_tweens(&mut commands);
-				let transform_tween_entity = commands
-					.spawn((
-						TweenAnim::new(transform_tween),
-						AnimTarget::component::<Transform>(camera_entity),
-					))
-					.id();
-				let fov_tween_entity = commands
-					.spawn((
-						TweenAnim::new(fov_tween),
-						AnimTarget::component::<Projection>(camera_entity),
-					))
-					.id();
-				let pan_orbit_tween_entity = commands
-					.spawn((
-						TweenAnim::new(pan_orbit_tween),
-						AnimTarget::component::<PanOrbitCamera>(camera_entity),
-					))
-					.id();
-				camera_mode.register_active_tweens([
-					transform_tween_entity,
-					fov_tween_entity,
-					pan_orbit_tween_entity,
-				]);
+				let current_distance = current_transform.translation.distance(pan_orbit.focus);
+				DollyTransition {
+					start_fov,
+					end_fov: CLOSE_TO_ORTHOGRAPHIC_FOV,
+					start_rot: current_transform.rotation,
+					end_rot: Quat::from_axis_angle(Vec3::Y, 90.0_f32.to_radians())
+						* Quat::from_axis_angle(Vec3::X, -89.9_f32.to_radians()),
+					start_size: dolly_zoom_width(current_distance, start_fov),
+					end_size,
+					start_focus: pan_orbit.focus,
+					end_focus: Vec3::ZERO,
+				}
 			}
 			// Orthographic → Perspective: 1-stage transition
 			(CameraState::Orthographic, CameraState::Perspective) => {
 				let end_size = world_size + PADDING; // TODO: Use settings.terrain.world_length().max(settings.terrain.world_width()) instead of hardcoded value
 				let (angled_transform, angled_projection) = create_perspective_angled_state(end_size);
-				let start_fov = if let Projection::Perspective(p) = current_projection {
-					p.fov
-				} else {
-					panic!("Expected perspective projection");
-				};
-				let end_fov = angled_projection.fov;
-				let start_rot = current_transform.rotation;
-				let end_transform = angled_transform;
-				let end_rot = end_transform.rotation;
-				// Calculate current camera's effective size from its position and FOV
-				let current_distance = current_transform.translation.length();
-				let current_size = dolly_zoom_width(current_distance, start_fov);
-				let transform_tween = Tween::new::<Transform, _>(
-					EaseFunction::SmoothStep,
-					Duration::from_secs_f32(TOTAL_TRANSITION_TIME),
-					DollyZoomLens {
-						start_fov,
-						end_fov,
-						start_rot,
-						end_rot,
-						start_size: current_size,
-						end_size,
-					},
+				let (start_fov, start_size) = resume_dolly_start(
+					&mut commands,
+					camera_entity,
+					current_transform,
+					current_projection,
+					pan_orbit.focus,
 				);
-				let fov_tween = Tween::new::<Projection, _>(
-					EaseFunction::SmoothStep,
-					Duration::from_secs_f32(TOTAL_TRANSITION_TIME),
-					ProjectionFovLens {
-						start: start_fov,
-						end: end_fov,
-					},
-				);
-				let pan_orbit_tween = Tween::new::<PanOrbitCamera, _>(
-					EaseFunction::SmoothStep,
-					Duration::from_secs_f32(TOTAL_TRANSITION_TIME),
-					PanOrbitCameraLens {
-						start_fov,
-						end_fov,
-						start_rot,
-						end_rot,
-						start_size: current_size,
-						end_size,
-					},
-				);
-				camera_mode.clear_active_tweens(&mut commands);
-				let transform_tween_entity = commands
-					.spawn((
-						TweenAnim::new(transform_tween),
-						AnimTarget::component::<Transform>(camera_entity),
-					))
-					.id();
-				let fov_tween_entity = commands
-					.spawn((
-						TweenAnim::new(fov_tween),
-						AnimTarget::component::<Projection>(camera_entity),
-					))
-					.id();
-				let pan_orbit_tween_entity = commands
-					.spawn((
-						TweenAnim::new(pan_orbit_tween),
-						AnimTarget::component::<PanOrbitCamera>(camera_entity),
-					))
-					.id();
-				camera_mode.register_active_tweens([
-					transform_tween_entity,
-					fov_tween_entity,
-					pan_orbit_tween_entity,
-				]);
+				DollyTransition {
+					start_fov,
+					end_fov: angled_projection.fov,
+					start_rot: current_transform.rotation,
+					end_rot: angled_transform.rotation,
+					start_size,
+					end_size,
+					start_focus: pan_orbit.focus,
+					end_focus: Vec3::ZERO,
+				}
 			}
 			_ => unreachable!(),
+		};
+
+		camera_mode.orthographic_size = None;
+		camera_mode.begin_transition(&mut commands, camera_entity, new_mode, transition);
+	}
+}
+
+/// Start FOV and view width at `focus` for a dolly tween from the camera's current state.
+/// An orthographic camera is swapped back to the nearly-zero FOV it ended its transition at.
+fn resume_dolly_start(
+	commands: &mut Commands,
+	camera_entity: Entity,
+	current_transform: &Transform,
+	current_projection: &Projection,
+	focus: Vec3,
+) -> (f32, f32) {
+	match current_projection {
+		Projection::Perspective(p) => {
+			// Calculate current camera's effective size from its position and FOV
+			let current_distance = current_transform.translation.distance(focus);
+			(p.fov, dolly_zoom_width(current_distance, p.fov))
+		}
+		Projection::Orthographic(o) => {
+			commands
+				.entity(camera_entity)
+				.insert(Projection::from(create_perspective_projection(
+					CLOSE_TO_ORTHOGRAPHIC_FOV,
+				)));
+			(CLOSE_TO_ORTHOGRAPHIC_FOV, o.area.width())
 		}
+		Projection::Custom(_) => panic!("Expected perspective or orthographic projection"),
+	}
+}
 
-		camera_mode.current_mode = new_mode;
+/// Start and end of a dolly-zoom camera move, panning the orbit focus along the way.
+#[derive(Debug, Clone, Copy)]
+struct DollyTransition {
+	start_fov: f32,
+	end_fov: f32,
+	start_rot: Quat,
+	end_rot: Quat,
+	start_size: f32,
+	end_size: f32,
+	start_focus: Vec3,
+	end_focus: Vec3,
+}
+
+impl DollyTransition {
+	/// Spawns the transform, FOV and pan-orbit tweens that together perform the move.
+	fn spawn_tweens(self, commands: &mut Commands, camera_entity: Entity) -> [Entity; 3] {
+		let transform_tween = Tween::new::<Transform, _>(
+			EaseFunction::SmoothStep,
+			Duration::from_secs_f32(TOTAL_TRANSITION_TIME),
+			DollyZoomLens {
+				start_fov: self.start_fov,
+				end_fov: self.end_fov,
+				start_rot: self.start_rot,
+				end_rot: self.end_rot,
+				start_size: self.start_size,
+				end_size: self.end_size,
+				start_focus: self.start_focus,
+				end_focus: self.end_focus,
+			},
+		);
+		let fov_tween = Tween::new::<Projection, _>(
+			EaseFunction::SmoothStep,
+			Duration::from_secs_f32(TOTAL_TRANSITION_TIME),
+			ProjectionFovLens {
+				start: self.start_fov,
+				end: self.end_fov,
+			},
+		);
+		let pan_orbit_tween = Tween::new::<PanOrbitCamera, _>(
+			EaseFunction::SmoothStep,
+			Duration::from_secs_f32(TOTAL_TRANSITION_TIME),
+			PanOrbitCameraLens {
+				start_fov: self.start_fov,
+				end_fov: self.end_fov,
+				start_rot: self.start_rot,
+				end_rot: self.end_rot,
+				start_size: self.start_size,
+				end_size: self.end_size,
+				start_focus: self.start_focus,
+				end_focus: self.end_focus,
+			},
+		);
+		let transform_tween_entity = commands
+			.spawn((
+				TweenAnim::new(transform_tween),
+				AnimTarget::component::<Transform>(camera_entity),
+			))
+			.id();
+		let fov_tween_entity = commands
+			.spawn((
+				TweenAnim::new(fov_tween),
+				AnimTarget::component::<Projection>(camera_entity),
+			))
+			.id();
+		let pan_orbit_tween_entity = commands
+			.spawn((
+				TweenAnim::new(pan_orbit_tween),
+				AnimTarget::component::<PanOrbitCamera>(camera_entity),
+			))
+			.id();
+		[
+			transform_tween_entity,
+			fov_tween_entity,
+			pan_orbit_tween_entity,
+		]
 	}
 }
 
@@ -417,12 +889,29 @@ fn cleanup_completed_tweens(
 	time: Res<Time>,
 	mut commands: Commands,
 	mut camera_mode: ResMut<CameraMode>,
+	camera_query: Single<
+		(&mut Transform, &mut Projection, &mut PanOrbitCamera),
+		With<PrimaryCamera3d>,
+	>,
+	settings: Res<terrain::Settings>,
 ) {
 	if camera_mode.is_transitioning {
 		camera_mode.transition_timer.tick(time.delta());
 		if camera_mode.transition_timer.is_finished() {
 			camera_mode.clear_active_tweens(&mut commands);
 			camera_mode.is_transitioning = false;
+
+			if camera_mode.current_mode == CameraState::Orthographic {
+				let (mut transform, mut projection, mut pan_orbit) = camera_query.into_inner();
+				let size = camera_mode
+					.orthographic_size
+					.unwrap_or_else(|| terrain::spatial::world_size(&settings) + PADDING);
+				let (orthographic, distance) = create_orthographic_projection(size);
+				transform.translation = pan_orbit.focus + transform.rotation * Vec3::Z * distance;
+				*projection = Projection::Orthographic(orthographic);
+				pan_orbit.radius = Some(distance);
+				pan_orbit.target_radius = distance;
+			}
 		}
 	}
 }
@@ -457,6 +946,21 @@ fn dolly_zoom_width(distance: f32, fov: f32) -> f32 {
 	distance * 2.0 * (0.5 * fov).tan()
 }
 
+/// Orthographic projection showing `size` meters across, and the camera distance to use with it.
+pub(crate) fn create_orthographic_projection(size: f32) -> (OrthographicProjection, f32) {
+	let distance = size * ORTHOGRAPHIC_DISTANCE_FACTOR;
+	let projection = OrthographicProjection {
+		scaling_mode: ScalingMode::FixedHorizontal {
+			viewport_width: size,
+		},
+		near: 0.0,
+		// Far enough to see past the focus down to anything below the terrain
+		far: 2.0 * distance,
+		..OrthographicProjection::default_3d()
+	};
+	(projection, distance)
+}
+
 fn create_perspective_projection(fov: f32) -> PerspectiveProjection {
 	PerspectiveProjection {
 		fov,
@@ -464,3 +968,117 @@ fn create_perspective_projection(fov: f32) -> PerspectiveProjection {
 		..default()
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn level_roll_removes_roll_and_keeps_view_direction() {
+		let level = Transform::default()
+			.looking_to(Vec3::new(1.0, -0.5, 0.3), Vec3::Y)
+			.rotation;
+		let rolled = level * Quat::from_rotation_z(0.05);
+		let corrected = level_roll(rolled);
+
+		let forward_before = rolled * Vec3::NEG_Z;
+		let forward_after = corrected * Vec3::NEG_Z;
+		assert!(forward_before.distance(forward_after) < 1e-5);
+		// A level camera's right vector is horizontal.
+		assert!((corrected * Vec3::X).y.abs() < 1e-5);
+		assert!(corrected.angle_between(level) < 1e-4);
+	}
+
+	#[test]
+	fn orthographic_projection_spans_requested_width_and_sees_the_focus() {
+		let size = 1500.0;
+		let (projection, distance) = create_orthographic_projection(size);
+		assert!(matches!(
+			projection.scaling_mode,
+			ScalingMode::FixedHorizontal { viewport_width } if viewport_width == size
+		));
+		assert!(projection.near <= 0.0);
+		assert!(
+			projection.far > distance,
+			"focus must be inside the far plane"
+		);
+	}
+
+	#[test]
+	fn bookmark_slots_grow_on_demand_and_round_trip_through_json() {
+		let mut bookmarks = CameraBookmarks::default();
+		let bookmark = SavedView {
+			projection: BookmarkProjection::Perspective {
+				fov: 60.0_f32.to_radians(),
+			},
+			yaw: 0.0,
+			pitch: 0.6,
+			radius: 500.0,
+			focus: Vec3::new(120.0, 15.0, -80.0),
+		};
+		bookmarks.set(4, bookmark);
+		assert_eq!(bookmarks.slots.len(), 5);
+		assert!(bookmarks.get(0).is_none());
+
+		let json = serde_json::to_string(&bookmarks).unwrap();
+		let loaded: CameraBookmarks = serde_json::from_str(&json).unwrap();
+		assert_eq!(loaded.get(4), Some(&bookmark));
+		// Restoring a perspective bookmark dollies back to the same view width, around its focus.
+		let expected_size = dolly_zoom_width(500.0, 60.0_f32.to_radians());
+		assert!((bookmark.size() - expected_size).abs() < 1e-3);
+		let transition = bookmark.transition_from(1.0, Quat::IDENTITY, 100.0, Vec3::ZERO);
+		assert_eq!(transition.end_focus, bookmark.focus);
+	}
+
+	#[test]
+	fn roll_correction_holds_the_camera_until_its_timer_runs_out() {
+		let mut world = World::new();
+		let camera = world.spawn(Transform::default()).id();
+		let mut mode = CameraMode::default();
+		let level = Transform::default()
+			.looking_to(Vec3::new(1.0, -0.5, 0.3), Vec3::Y)
+			.rotation;
+		mode.begin_roll_correction(
+			&mut world.commands(),
+			camera,
+			level * Quat::from_rotation_z(0.05),
+			level,
+		);
+		world.flush();
+
+		assert!(mode.is_camera_transitioning());
+		assert_eq!(mode.active_tweens.len(), 1);
+		assert!(world.get::<TweenAnim>(mode.active_tweens[0]).is_some());
+		assert_eq!(
+			mode.transition_timer.duration(),
+			Duration::from_secs_f32(LEVEL_ROLL_TIME)
+		);
+	}
+
+	#[test]
+	fn level_roll_leaves_straight_down_view_unchanged() {
+		let down = Quat::from_rotation_x(-PI / 2.0);
+		assert_eq!(level_roll(down), down);
+	}
+
+	#[test]
+	fn saved_view_places_the_camera_like_the_orbit_controls() {
+		let view = SavedView {
+			projection: BookmarkProjection::Orthographic { width: 1200.0 },
+			yaw: 0.4,
+			pitch: 0.7,
+			radius: 900.0,
+			focus: Vec3::new(50.0, 0.0, -20.0),
+		};
+		let transform = view.transform();
+		let (yaw, pitch) = direction_to_spherical(transform.translation - view.focus);
+		assert!((yaw - view.yaw).abs() < 1e-5);
+		assert!((pitch - view.pitch).abs() < 1e-5);
+		assert!((transform.translation.distance(view.focus) - view.radius).abs() < 1e-2);
+
+		let Projection::Orthographic(orthographic) = view.create_projection() else {
+			panic!("an orthographic view restores an orthographic projection");
+		};
+		assert!(orthographic.far > view.radius);
+	}
+}
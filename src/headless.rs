@@ -0,0 +1,99 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, bail};
+
+use crate::terrain;
+
+/// `--headless` run: generate terrain from default settings and write the exports, no window.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct HeadlessOptions {
+	pub seed: Option<u32>,
+	/// Overrides `Settings::base_grid_resolution`.
+	pub resolution: Option<u32>,
+	pub exports: Vec<PathBuf>,
+}
+
+impl HeadlessOptions {
+	/// `None` when `--headless` isn't among `args`, so the app starts as usual.
+	pub(crate) fn parse(args: impl IntoIterator<Item = String>) -> anyhow::Result<Option<Self>> {
+		let args: Vec<String> = args.into_iter().collect();
+		if !args.iter().any(|arg| arg == "--headless") {
+			return Ok(None);
+		}
+		let mut options = Self::default();
+		let mut args = args.into_iter();
+		while let Some(arg) = args.next() {
+			let mut value = || args.next().with_context(|| format!("{arg} needs a value"));
+			match arg.as_str() {
+				"--headless" => {}
+				"--seed" => options.seed = Some(value()?.parse().context("--seed")?),
+				"--resolution" => {
+					let resolution: u32 = value()?.parse().context("--resolution")?;
+					if resolution == 0 {
+						bail!("--resolution must be at least 1");
+					}
+					options.resolution = Some(resolution);
+				}
+				"--export" => options.exports.push(PathBuf::from(value()?)),
+				_ => bail!("unknown argument {arg}"),
+			}
+		}
+		if options.exports.is_empty() {
+			bail!("nothing to do: pass --export <file.obj|file.png>");
+		}
+		Ok(Some(options))
+	}
+
+	pub(crate) fn settings(&self) -> terrain::Settings {
+		let defaults = terrain::Settings::default();
+		terrain::Settings {
+			seed: self.seed.unwrap_or(defaults.seed),
+			base_grid_resolution: self.resolution.unwrap_or(defaults.base_grid_resolution),
+			..defaults
+		}
+	}
+}
+
+/// Generates the terrain once and writes every requested export.
+pub(crate) fn run(options: &HeadlessOptions) -> anyhow::Result<()> {
+	let settings = options.settings();
+	let terrain = terrain::generate_terrain(&settings);
+	for path in &options.exports {
+		terrain::export_height_map(&settings, &terrain.height_map, path)
+			.with_context(|| format!("exporting {}", path.display()))?;
+		println!("Wrote {}", path.display());
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn args(line: &str) -> Vec<String> {
+		line.split_whitespace().map(String::from).collect()
+	}
+
+	#[test]
+	fn parses_headless_flags_and_ignores_normal_runs() {
+		assert_eq!(HeadlessOptions::parse(args("")).unwrap(), None);
+		let options = HeadlessOptions::parse(args(
+			"--headless --seed 7 --resolution 64 --export terrain.obj --export heights.png",
+		))
+		.unwrap()
+		.unwrap();
+		assert_eq!(options.seed, Some(7));
+		assert_eq!(options.resolution, Some(64));
+		assert_eq!(
+			options.exports,
+			vec![PathBuf::from("terrain.obj"), PathBuf::from("heights.png")]
+		);
+		assert_eq!(options.settings().seed, 7);
+		assert_eq!(options.settings().base_grid_resolution, 64);
+
+		assert!(HeadlessOptions::parse(args("--headless")).is_err());
+		assert!(HeadlessOptions::parse(args("--headless --seed x --export a.obj")).is_err());
+		assert!(HeadlessOptions::parse(args("--headless --export")).is_err());
+		assert!(HeadlessOptions::parse(args("--headless --bogus --export a.obj")).is_err());
+	}
+}
@@ -1,30 +1,83 @@
 use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
+use bevy::picking::{
+	backend::ray::RayMap, mesh_picking::ray_cast::MeshRayCast, pointer::PointerId,
+};
 use bevy::prelude::*;
 
+use crate::alignment::{AlignmentRenderTimings, CachedAlignmentGeometry};
 use crate::camera::PrimaryCamera3d;
+use crate::pin::{pointer_ray, raycast_terrain_point};
+use crate::terrain::{self, TerrainMesh, calculate_terrain_height};
+use crate::units::Units;
 
 #[derive(Component)]
 struct HudText;
 
+/// Root node of the HUD, hidden as a whole.
+#[derive(Component)]
+struct HudRoot;
+
+/// Whether the debug HUD is shown; toggled with F3.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct HudVisible(pub bool);
+
+impl Default for HudVisible {
+	fn default() -> Self {
+		Self(true)
+	}
+}
+
+/// How long a toast stays on screen.
+const TOAST_SECS: f32 = 1.5;
+
+/// Short-lived message shown at the top of the window, independent of the debug HUD.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct HudToast {
+	message: String,
+	timer: Option<Timer>,
+}
+
+impl HudToast {
+	/// Show `message`, replacing whatever toast is up.
+	pub(crate) fn show(&mut self, message: impl Into<String>) {
+		self.message = message.into();
+		self.timer = Some(Timer::from_seconds(TOAST_SECS, TimerMode::Once));
+	}
+}
+
+#[derive(Component)]
+struct HudToastText;
+
 pub(crate) struct CameraDebugHud;
 
 impl Plugin for CameraDebugHud {
 	fn build(&self, app: &mut App) {
 		app
+			.init_resource::<HudVisible>()
+			.init_resource::<HudToast>()
 			.add_systems(Startup, setup_hud)
-			.add_systems(Update, update_hud)
+			.add_systems(
+				Update,
+				(
+					(toggle_hud, apply_hud_visibility, update_hud).chain(),
+					update_hud_toast,
+				),
+			)
 			.add_plugins(FrameTimeDiagnosticsPlugin::default());
 	}
 }
 
 fn setup_hud(mut commands: Commands) {
 	commands
-		.spawn(Node {
-			padding: UiRect::all(Val::Px(10.0)),
-			justify_self: JustifySelf::End,
-			align_self: AlignSelf::Start,
-			..default()
-		})
+		.spawn((
+			Node {
+				padding: UiRect::all(Val::Px(10.0)),
+				justify_self: JustifySelf::End,
+				align_self: AlignSelf::Start,
+				..default()
+			},
+			HudRoot,
+		))
 		.with_child((
 			Text::new("Loading..."),
 			HudText,
@@ -36,14 +89,85 @@ fn setup_hud(mut commands: Commands) {
 			TextLayout::new_with_justify(Justify::Left),
 			Node { ..default() },
 		));
+	commands.spawn((
+		Text::default(),
+		HudToastText,
+		TextFont {
+			font_size: 16.0,
+			..default()
+		},
+		TextColor(Color::WHITE),
+		Node {
+			justify_self: JustifySelf::Center,
+			align_self: AlignSelf::Start,
+			margin: UiRect::top(Val::Px(40.0)),
+			..default()
+		},
+		Visibility::Hidden,
+	));
+}
+
+fn update_hud_toast(
+	time: Res<Time>,
+	mut toast: ResMut<HudToast>,
+	toast_text: Single<(&mut Text, &mut Visibility), With<HudToastText>>,
+) {
+	let (mut text, mut visibility) = toast_text.into_inner();
+	if toast.is_changed() {
+		text.0.clone_from(&toast.message);
+	}
+	// Tick without tripping change detection, so only `show` refreshes the text
+	let toast = toast.bypass_change_detection();
+	let showing = toast
+		.timer
+		.as_mut()
+		.is_some_and(|timer| !timer.tick(time.delta()).is_finished());
+	if !showing {
+		toast.timer = None;
+	}
+	visibility.set_if_neq(if showing {
+		Visibility::Inherited
+	} else {
+		Visibility::Hidden
+	});
+}
+
+fn toggle_hud(keyboard_input: Res<ButtonInput<KeyCode>>, mut visible: ResMut<HudVisible>) {
+	if keyboard_input.just_pressed(KeyCode::F3) {
+		visible.0 = !visible.0;
+	}
+}
+
+fn apply_hud_visibility(
+	visible: Res<HudVisible>,
+	mut hud_root: Single<&mut Visibility, With<HudRoot>>,
+) {
+	if visible.is_changed() {
+		**hud_root = if visible.0 {
+			Visibility::Inherited
+		} else {
+			Visibility::Hidden
+		};
+	}
 }
 
 fn update_hud(
+	visible: Res<HudVisible>,
 	mut hud_text: Single<&mut Text, With<HudText>>,
-	camera_query: Single<(&Transform, &Projection), With<PrimaryCamera3d>>,
+	camera_query: Single<(Entity, &Transform, &Projection), With<PrimaryCamera3d>>,
+	terrain: Single<(Entity, &terrain::HeightMap), With<TerrainMesh>>,
+	terrain_settings: Res<terrain::Settings>,
+	ray_map: Res<RayMap>,
+	mut raycast: MeshRayCast,
 	diagnostics: Res<DiagnosticsStore>,
+	render_timings: Option<Res<AlignmentRenderTimings>>,
+	geometry_cache: Res<CachedAlignmentGeometry>,
+	units: Res<Units>,
 ) {
-	let (camera_transform, camera_projection) = *camera_query;
+	if !visible.0 {
+		return;
+	}
+	let (camera_entity, camera_transform, camera_projection) = *camera_query;
 	let translation = camera_transform.translation;
 	let (tr_x, tr_y, tr_z) = (translation.x, translation.y, translation.z);
 	let rotation = camera_transform.rotation;
@@ -68,6 +192,32 @@ fn update_hud(
 		text.push_str(&format!("\n\tFOV: {fov:.2} deg"));
 	}
 
+	let (terrain_entity, heightmap) = *terrain;
+	let cursor = pointer_ray(&ray_map, PointerId::Mouse, camera_entity)
+		.and_then(|ray| raycast_terrain_point(ray, terrain_entity, &mut raycast));
+	match cursor {
+		Some(point) => {
+			let height = calculate_terrain_height(point, heightmap, &terrain_settings);
+			text.push_str(&format!(
+				"\nCursor: ({:.2}, {:.2}) @ height {height:.2}",
+				point.x, point.z
+			));
+		}
+		None => text.push_str("\nCursor: off-terrain"),
+	}
+
+	if let Some((_, _, geometry)) = geometry_cache.get() {
+		let min_radius = geometry.min_radius().map_or_else(
+			|| "none".to_string(),
+			|radius| units.format_length(f64::from(radius)),
+		);
+		text.push_str(&format!(
+			"\nAlignment: {} long, {} turns, min radius {min_radius}",
+			units.format_length(f64::from(geometry.total_length())),
+			geometry.turn_count(),
+		));
+	}
+
 	if let Some(fps) = diagnostics
 		.get(&FrameTimeDiagnosticsPlugin::FPS)
 		.and_then(|fps_diag| fps_diag.smoothed())
@@ -75,5 +225,14 @@ fn update_hud(
 		text.push_str(&format!("\nFPS: {fps:.1}"));
 	};
 
+	if let Some(timings) = render_timings {
+		text.push_str("\nAlignment render:");
+		for (label, timing) in [("Geometry", &timings.geometry), ("Draw", &timings.draw)] {
+			if let Some(average) = timing.average_micros() {
+				text.push_str(&format!("\n\t{label}: {average:.0} us"));
+			}
+		}
+	}
+
 	hud_text.0 = text;
 }
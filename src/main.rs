@@ -9,24 +9,47 @@ use bevy::{
 use bevy_egui::EguiPlugin;
 
 mod alignment;
+mod autosave;
 mod camera;
 mod debug_frame_limiter;
+#[cfg(not(target_arch = "wasm32"))]
+mod headless;
 mod hud;
+mod measure;
 mod pin;
 mod saveable;
+mod screenshot;
 mod terrain;
 mod ui_shell;
+mod units;
 
 use crate::alignment::AlignmentPlugin;
+use crate::autosave::AutosavePlugin;
 use crate::camera::CameraPlugin;
 use crate::debug_frame_limiter::FrameLimiterPlugin;
+use crate::measure::MeasurePlugin;
 use crate::pin::PinPlugin;
+use crate::screenshot::ScreenshotPlugin;
 use crate::terrain::TerrainPlugin;
 use crate::ui_shell::UiShellPlugin;
 
-const HUD: bool = true;
-
 fn main() {
+	#[cfg(not(target_arch = "wasm32"))]
+	match headless::HeadlessOptions::parse(std::env::args().skip(1)) {
+		Ok(Some(options)) => {
+			if let Err(e) = headless::run(&options) {
+				eprintln!("Error: {e:#}");
+				std::process::exit(1);
+			}
+			return;
+		}
+		Ok(None) => {}
+		Err(e) => {
+			eprintln!("Error: {e:#}");
+			std::process::exit(2);
+		}
+	}
+
 	#[cfg(not(target_arch = "wasm32"))]
 	let wgpu_settings = WgpuSettings {
 		features: WgpuFeatures::POLYGON_MODE_LINE,
@@ -65,6 +88,9 @@ fn main() {
 		.add_plugins(TerrainPlugin)
 		.add_plugins(PinPlugin)
 		.add_plugins(AlignmentPlugin)
+		.add_plugins(AutosavePlugin)
+		.add_plugins(ScreenshotPlugin)
+		.add_plugins(MeasurePlugin)
 		.add_plugins(WireframePlugin::default())
 		.insert_resource(WireframeConfig {
 			global: false,
@@ -74,9 +100,7 @@ fn main() {
 
 	app.add_plugins(FrameLimiterPlugin);
 
-	if HUD {
-		app.add_plugins(hud::CameraDebugHud);
-	}
+	app.add_plugins(hud::CameraDebugHud);
 
 	app.run();
 }
@@ -0,0 +1,176 @@
+use bevy::color::palettes::css::{FUCHSIA, WHITE};
+use bevy::picking::{
+	backend::ray::RayMap, mesh_picking::ray_cast::MeshRayCast, pointer::PointerId,
+};
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, egui};
+
+use crate::camera::PrimaryCamera3d;
+use crate::pin::{pointer_ray, raycast_terrain_point};
+use crate::terrain::{self, TerrainMesh, calculate_terrain_height};
+use crate::units::{DisplayPrecision, Units};
+
+const MEASURE_TOGGLE: KeyCode = KeyCode::KeyM;
+/// Radius of the markers on the measured points.
+const MARKER_RADIUS: f32 = 2.0;
+
+/// Click two terrain points to measure between them; a third click starts over.
+pub(crate) struct MeasurePlugin;
+
+impl Plugin for MeasurePlugin {
+	fn build(&self, app: &mut App) {
+		app
+			.init_resource::<MeasureTool>()
+			.add_systems(
+				Update,
+				(toggle_measure_tool, place_measure_point, draw_measurement).chain(),
+			)
+			.add_systems(bevy_egui::EguiPrimaryContextPass, measurement_ui);
+	}
+}
+
+#[derive(Resource, Debug, Default)]
+pub(crate) struct MeasureTool {
+	pub active: bool,
+	/// Up to two picked terrain points.
+	points: Vec<Vec3>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Measurement {
+	distance: f32,
+	plan_distance: f32,
+	/// Elevation of the second point minus the first.
+	rise: f32,
+	/// Direction from the first point to the second in plan, counterclockwise from +X towards +Z,
+	/// in radians within `0..TAU`.
+	bearing: f32,
+}
+
+impl Measurement {
+	fn between(a: Vec3, b: Vec3) -> Self {
+		let delta = b - a;
+		Self {
+			distance: delta.length(),
+			plan_distance: delta.xz().length(),
+			rise: delta.y,
+			bearing: delta.z.atan2(delta.x).rem_euclid(std::f32::consts::TAU),
+		}
+	}
+}
+
+fn toggle_measure_tool(keyboard_input: Res<ButtonInput<KeyCode>>, mut tool: ResMut<MeasureTool>) {
+	if keyboard_input.just_pressed(MEASURE_TOGGLE) {
+		tool.active = !tool.active;
+		tool.points.clear();
+	}
+}
+
+fn place_measure_point(
+	mouse_button: Res<ButtonInput<MouseButton>>,
+	mut tool: ResMut<MeasureTool>,
+	camera: Single<Entity, With<PrimaryCamera3d>>,
+	terrain: Single<(Entity, &terrain::HeightMap), With<TerrainMesh>>,
+	settings: Res<terrain::Settings>,
+	ray_map: Res<RayMap>,
+	mut raycast: MeshRayCast,
+	mut egui_contexts: EguiContexts,
+) {
+	if !tool.active || !mouse_button.just_pressed(MouseButton::Left) {
+		return;
+	}
+	if let Ok(ctx) = egui_contexts.ctx_mut()
+		&& (ctx.wants_pointer_input() || ctx.is_pointer_over_area())
+	{
+		return;
+	}
+	let (terrain_entity, heightmap) = *terrain;
+	let Some(hit) = pointer_ray(&ray_map, PointerId::Mouse, *camera)
+		.and_then(|ray| raycast_terrain_point(ray, terrain_entity, &mut raycast))
+	else {
+		return;
+	};
+	if tool.points.len() >= 2 {
+		tool.points.clear();
+	}
+	let height = calculate_terrain_height(hit, heightmap, &settings);
+	tool.points.push(hit.with_y(height));
+}
+
+fn draw_measurement(mut gizmos: Gizmos, tool: Res<MeasureTool>) {
+	if !tool.active {
+		return;
+	}
+	for point in &tool.points {
+		gizmos.sphere(Isometry3d::from_translation(*point), MARKER_RADIUS, FUCHSIA);
+	}
+	if let [a, b] = tool.points[..] {
+		gizmos.line(a, b, FUCHSIA);
+		// Plan distance and rise as the legs of a right triangle
+		let corner = b.with_y(a.y);
+		gizmos.line(a, corner, WHITE);
+		gizmos.line(corner, b, WHITE);
+	}
+}
+
+fn measurement_ui(
+	mut contexts: EguiContexts,
+	tool: Res<MeasureTool>,
+	units: Res<Units>,
+	precision: Res<DisplayPrecision>,
+) {
+	if !tool.active {
+		return;
+	}
+	let Ok(ctx) = contexts.ctx_mut() else {
+		return;
+	};
+	egui::Area::new(egui::Id::new("measure_tool"))
+		.anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -48.0))
+		.show(ctx, |ui| {
+			egui::Frame::popup(ui.style()).show(ui, |ui| {
+				let [a, b] = tool.points[..] else {
+					let step = if tool.points.is_empty() {
+						"first"
+					} else {
+						"second"
+					};
+					ui.label(format!("Measure: click the {step} point (M to exit)"));
+					return;
+				};
+				let measurement = Measurement::between(a, b);
+				ui.label(format!(
+					"Distance: {}",
+					units.format_distance(f64::from(measurement.distance))
+				));
+				ui.label(format!(
+					"Plan distance: {}",
+					units.format_distance(f64::from(measurement.plan_distance))
+				));
+				ui.label(format!(
+					"Elevation difference: {}",
+					units.format_length(f64::from(measurement.rise))
+				));
+				ui.label(format!(
+					"Bearing from +X: {}",
+					precision.format_degrees(f64::from(measurement.bearing))
+				));
+			});
+		});
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn measurement_splits_distance_into_plan_and_rise() {
+		let measurement = Measurement::between(Vec3::new(10.0, 5.0, 0.0), Vec3::new(13.0, 17.0, 4.0));
+		assert_eq!(measurement.plan_distance, 5.0);
+		assert_eq!(measurement.rise, 12.0);
+		assert_eq!(measurement.distance, 13.0);
+
+		let toward_negative_z = Measurement::between(Vec3::ZERO, Vec3::NEG_Z);
+		assert!((toward_negative_z.bearing.to_degrees() - 270.0).abs() < 1e-4);
+	}
+}
@@ -26,7 +26,9 @@ impl Plugin for PinPlugin {
 	fn build(&self, app: &mut App) {
 		app
 			.init_resource::<PinDragState>()
-			//.add_systems(Startup, startup)
+			.init_resource::<SnapSettings>()
+			.init_resource::<PinDisplaySettings>()
+			.add_systems(Startup, fit_pin_display_to_world)
 			.add_systems(
 				Update,
 				(
@@ -48,6 +50,82 @@ impl Plugin for PinPlugin {
 #[derive(Component)]
 pub struct Pin;
 
+/// Snaps dragged pins to a square grid in plan. Hold Alt while dragging to place freely.
+#[derive(Resource, Debug, Clone, Copy)]
+pub(crate) struct SnapSettings {
+	pub enabled: bool,
+	pub spacing: f32,
+}
+
+impl Default for SnapSettings {
+	fn default() -> Self {
+		Self {
+			enabled: false,
+			spacing: 25.0,
+		}
+	}
+}
+
+/// How big pins are drawn. Pins keep `base_size` up to `reference_distance` from the camera,
+/// then grow with distance so they stay visible when zoomed out.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct PinDisplaySettings {
+	pub reference_distance: f32,
+	pub min_scale: f32,
+	pub base_size: f32,
+}
+
+/// Reference distance as a multiple of the terrain's largest extent.
+const REFERENCE_DISTANCE_PER_WORLD_SIZE: f32 = 3.0;
+/// FOV the pin scale is calibrated for; narrower views shrink pins to match.
+const REFERENCE_FOV_DEGREES: f32 = 60.0;
+
+impl PinDisplaySettings {
+	pub(crate) fn for_world_size(world_size: f32) -> Self {
+		Self {
+			reference_distance: world_size * REFERENCE_DISTANCE_PER_WORLD_SIZE,
+			min_scale: 1.0,
+			base_size: 1.0,
+		}
+	}
+
+	/// Uniform scale for a pin `distance` from a camera with vertical field of view `fov`.
+	pub(crate) fn pin_scale(&self, distance: f32, fov: f32) -> f32 {
+		// Apparent size goes with tan(FOV/2), so narrow (orthographic-like) views scale pins down
+		let fov_scale_factor = (fov * 0.5).tan() / (REFERENCE_FOV_DEGREES.to_radians() * 0.5).tan();
+		let distance_scale_factor =
+			(distance / self.reference_distance.max(f32::EPSILON)).max(self.min_scale);
+		distance_scale_factor * fov_scale_factor * self.base_size
+	}
+}
+
+impl Default for PinDisplaySettings {
+	fn default() -> Self {
+		Self::for_world_size(terrain::spatial::world_size(&terrain::Settings::default()))
+	}
+}
+
+fn fit_pin_display_to_world(
+	mut display: ResMut<PinDisplaySettings>,
+	settings: Res<terrain::Settings>,
+) {
+	*display = PinDisplaySettings::for_world_size(terrain::spatial::world_size(&settings));
+}
+
+const SNAP_OVERRIDE_KEYS: [KeyCode; 2] = [KeyCode::AltLeft, KeyCode::AltRight];
+
+/// Round X and Z to the nearest multiple of `spacing`, leaving Y for the caller to resolve.
+pub(crate) fn snap_to_grid(point: Vec3, spacing: f32) -> Vec3 {
+	if !spacing.is_finite() || spacing <= 0.0 {
+		return point;
+	}
+	Vec3::new(
+		(point.x / spacing).round() * spacing,
+		point.y,
+		(point.z / spacing).round() * spacing,
+	)
+}
+
 #[derive(Default, Resource)]
 pub(crate) struct PinDragState {
 	entries: HashMap<Entity, PinDragData>,
@@ -57,6 +135,10 @@ impl PinDragState {
 	pub(crate) fn is_dragging(&self, entity: Entity) -> bool {
 		self.entries.contains_key(&entity)
 	}
+
+	pub(crate) fn is_dragging_any(&self) -> bool {
+		!self.entries.is_empty()
+	}
 }
 
 #[derive(Clone, Copy)]
@@ -224,6 +306,8 @@ fn on_pin_drag_update(
 	ray_map: Res<RayMap>,
 	mut raycast: MeshRayCast,
 	drag_state: Res<PinDragState>,
+	snap_settings: Res<SnapSettings>,
+	keyboard_input: Res<ButtonInput<KeyCode>>,
 ) {
 	let Ok(mut pin_transform) = pin_transform_query.get_mut(drag.entity) else {
 		return;
@@ -245,7 +329,16 @@ fn on_pin_drag_update(
 				*terrain_heightmap,
 				&settings,
 			);
-			pin_transform.translation = terrain_point + drag_data.offset;
+			// Keep the pin over the mesh so alignment endpoints stay within the height map
+			let mut target =
+				terrain::clamp_to_terrain_bounds(terrain_point + drag_data.offset, &settings);
+			if snap_settings.enabled && !keyboard_input.any_pressed(SNAP_OVERRIDE_KEYS) {
+				target =
+					terrain::clamp_to_terrain_bounds(snap_to_grid(target, snap_settings.spacing), &settings);
+				target.y =
+					calculate_terrain_height(target, *terrain_heightmap, &settings) + drag_data.offset.y;
+			}
+			pin_transform.translation = target;
 		}
 	}
 }
@@ -254,6 +347,7 @@ fn on_pin_drag_update(
 fn scale_pins_by_distance(
 	mut pin_query: Query<&mut Transform, With<Pin>>,
 	camera_query: Single<(&GlobalTransform, &Projection), With<crate::camera::PrimaryCamera3d>>,
+	display: Res<PinDisplaySettings>,
 ) {
 	let (camera_transform, camera_projection) = *camera_query;
 	let camera_pos = camera_transform.translation();
@@ -266,30 +360,17 @@ fn scale_pins_by_distance(
 		return;
 	};
 
-	let reference_distance = 3000.0; // Distance at which pins have base scale
-	let reference_fov = 60.0_f32.to_radians(); // Reference FOV (normal perspective mode)
-	let min_scale = 1.0;
-
-	// Calculate FOV-based scale factor
-	// When FOV is very small (orthographic-like), objects appear larger, so we need to scale down
-	// The relationship is: apparent_size ∝ tan(FOV/2)
-	// Thank you LLMs. Would've taken me ages to figure out the math.
-	let fov_scale_factor = (current_fov * 0.5).tan() / (reference_fov * 0.5).tan();
-
 	for mut pin_transform in &mut pin_query {
 		let distance = camera_pos.distance(pin_transform.translation);
-
-		// Calculate scale factor based on distance
-		// As distance increases, scale increases to maintain visual size
-		let distance_scale_factor = (distance / reference_distance).max(min_scale);
-
-		// Combine distance and FOV scale factors
-		let scale_factor = distance_scale_factor * fov_scale_factor;
-		pin_transform.scale = Vec3::splat(scale_factor);
+		pin_transform.scale = Vec3::splat(display.pin_scale(distance, current_fov));
 	}
 }
 
-fn pointer_ray(ray_map: &RayMap, pointer_id: PointerId, camera: Entity) -> Option<Ray3d> {
+pub(crate) fn pointer_ray(
+	ray_map: &RayMap,
+	pointer_id: PointerId,
+	camera: Entity,
+) -> Option<Ray3d> {
 	ray_map
 		.iter()
 		.find(|(ray_id, _)| ray_id.pointer == pointer_id && ray_id.camera == camera)
@@ -306,7 +387,7 @@ fn calculate_terrain_point_from_raycast(
 	Vec3::new(raycast_point.x, terrain_height, raycast_point.z)
 }
 
-fn raycast_terrain_point(
+pub(crate) fn raycast_terrain_point(
 	ray: Ray3d,
 	terrain_entity: Entity,
 	raycast: &mut MeshRayCast,
@@ -319,3 +400,32 @@ fn raycast_terrain_point(
 		.find(|(entity, _)| *entity == terrain_entity)
 		.map(|(_, hit)| hit.point)
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn pin_scale_is_calibrated_to_the_world_size() {
+		let small = PinDisplaySettings::for_world_size(1000.0);
+		let large = PinDisplaySettings::for_world_size(8000.0);
+		let fov = REFERENCE_FOV_DEGREES.to_radians();
+		// Same framing of each world gives the same on-screen pin size
+		assert!((small.pin_scale(6000.0, fov) - large.pin_scale(48_000.0, fov)).abs() < 1e-5);
+		// Up close pins stay at their base size
+		assert_eq!(small.pin_scale(10.0, fov), 1.0);
+		let doubled = PinDisplaySettings {
+			base_size: 2.0,
+			..small
+		};
+		assert_eq!(doubled.pin_scale(10.0, fov), 2.0);
+	}
+
+	#[test]
+	fn snap_rounds_plan_position_to_nearest_multiple() {
+		let snapped = snap_to_grid(Vec3::new(37.4, 12.0, -12.6), 25.0);
+		assert_eq!(snapped, Vec3::new(25.0, 12.0, -25.0));
+		let unchanged = Vec3::new(1.0, 2.0, 3.0);
+		assert_eq!(snap_to_grid(unchanged, 0.0), unchanged);
+	}
+}
@@ -1,7 +1,112 @@
-use anyhow::Result;
+use anyhow::{Result, anyhow};
+use bevy::prelude::*;
+#[cfg(not(target_arch = "wasm32"))]
+use bevy::tasks::{IoTaskPool, Task, block_on, futures_lite::future};
 use bevy_egui::egui;
 use log::{debug, error};
 use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+use std::path::Path;
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DialogKind {
+	SaveAs,
+	Open,
+}
+
+/// The "Save As…" or "Open…" dialog of one saveable resource. The native dialog runs on the IO
+/// task pool and [`poll_file_dialog`] picks up the chosen path, so the frame loop never blocks
+/// on it.
+#[derive(Resource)]
+pub struct PendingFileDialog<T> {
+	#[cfg(not(target_arch = "wasm32"))]
+	task: Option<(DialogKind, Task<Option<std::path::PathBuf>>)>,
+	/// Value read by a finished "Open…", waiting for the UI to apply it.
+	opened: Option<T>,
+}
+
+impl<T> Default for PendingFileDialog<T> {
+	fn default() -> Self {
+		Self {
+			#[cfg(not(target_arch = "wasm32"))]
+			task: None,
+			opened: None,
+		}
+	}
+}
+
+impl<T> PendingFileDialog<T> {
+	fn is_open(&self) -> bool {
+		#[cfg(not(target_arch = "wasm32"))]
+		{
+			self.task.is_some()
+		}
+		#[cfg(target_arch = "wasm32")]
+		false
+	}
+
+	#[cfg(not(target_arch = "wasm32"))]
+	fn spawn(
+		&mut self,
+		kind: DialogKind,
+		dialog: impl Future<Output = Option<rfd::FileHandle>> + Send + 'static,
+	) {
+		let task = IoTaskPool::get()
+			.spawn(async move { dialog.await.map(|handle| handle.path().to_path_buf()) });
+		self.task = Some((kind, task));
+	}
+}
+
+/// Registers [`PendingFileDialog`] for `T` and the system that finishes its dialogs.
+pub struct FileDialogPlugin<T>(PhantomData<fn() -> T>);
+
+impl<T> Default for FileDialogPlugin<T> {
+	fn default() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: SaveableSettings + Resource> Plugin for FileDialogPlugin<T> {
+	fn build(&self, app: &mut App) {
+		app
+			.init_resource::<PendingFileDialog<T>>()
+			.add_systems(Update, poll_file_dialog::<T>);
+	}
+}
+
+/// Saves to the path a finished "Save As…" returned, or reads the file a finished "Open…" chose.
+pub fn poll_file_dialog<T: SaveableSettings + Resource>(
+	mut pending: ResMut<PendingFileDialog<T>>,
+	value: Res<T>,
+) {
+	#[cfg(not(target_arch = "wasm32"))]
+	{
+		let Some((kind, task)) = pending.task.as_mut() else {
+			return;
+		};
+		let kind = *kind;
+		let Some(path) = block_on(future::poll_once(task)) else {
+			return;
+		};
+		pending.task = None;
+		let Some(path) = path else {
+			return;
+		};
+		match kind {
+			DialogKind::SaveAs => match value.save_as(&path) {
+				Ok(()) => debug!("Saved {}", path.display()),
+				Err(e) => error!("Failed to save {}: {e}", path.display()),
+			},
+			DialogKind::Open => match T::load_from(&path) {
+				Ok(loaded) => pending.opened = Some(loaded),
+				Err(e) => error!("Failed to open {}: {e}", path.display()),
+			},
+		}
+	}
+	#[cfg(target_arch = "wasm32")]
+	let _ = (&mut pending, value);
+}
 
 /// Trait for structs that can be saved to and loaded from JSON files
 /// with consistent error handling and UI integration
@@ -11,25 +116,54 @@ pub trait SaveableSettings: Serialize + for<'de> Deserialize<'de> + Default {
 
 	/// Save the struct to its JSON file
 	fn save(&self) -> Result<()> {
-		#[cfg(not(target_arch = "wasm32"))]
-		{
-			let json = serde_json::to_string_pretty(self)?;
-			std::fs::write(Self::filename(), json)?;
-		}
-		Ok(())
+		self.save_as(Path::new(Self::filename()))
 	}
 
 	/// Load the struct from its JSON file, returning an error if it fails
 	fn load() -> Result<Self> {
+		Ok(read_json(Path::new(Self::filename()))?.unwrap_or_default())
+	}
+
+	/// Save the struct to a user-chosen JSON file
+	fn save_as(&self, path: &Path) -> Result<()> {
+		write_json(path, self)
+	}
+
+	/// Load the struct from a user-chosen JSON file, which unlike [`Self::load`] must exist
+	fn load_from(path: &Path) -> Result<Self> {
+		read_json(path)?.ok_or_else(|| anyhow!("{} does not exist", path.display()))
+	}
+
+	/// Sidecar file for periodic autosaves, e.g. `alignments.autosave.json`
+	fn autosave_filename() -> String {
+		let filename = Self::filename();
+		let stem = filename.strip_suffix(".json").unwrap_or(filename);
+		format!("{stem}.autosave.json")
+	}
+
+	fn save_autosave(&self) -> Result<()> {
+		self.save_as(Path::new(&Self::autosave_filename()))
+	}
+
+	fn load_autosave() -> Result<Self> {
+		Ok(read_json(Path::new(&Self::autosave_filename()))?.unwrap_or_default())
+	}
+
+	/// Whether there is an autosave written after the main file was last saved
+	fn autosave_is_newer() -> bool {
 		#[cfg(not(target_arch = "wasm32"))]
 		{
-			let filename = Self::filename();
-			if std::path::Path::new(filename).exists() {
-				let json = std::fs::read_to_string(filename)?;
-				return Ok(serde_json::from_str(&json)?);
+			let modified = |path: &str| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+			match (
+				modified(&Self::autosave_filename()),
+				modified(Self::filename()),
+			) {
+				(Some(autosaved), Some(saved)) => autosaved > saved,
+				(autosaved, _) => autosaved.is_some(),
 			}
 		}
-		Ok(Self::default())
+		#[cfg(target_arch = "wasm32")]
+		false
 	}
 
 	/// Load the struct from its JSON file with error handling and logging
@@ -64,4 +198,59 @@ pub trait SaveableSettings: Serialize + for<'de> Deserialize<'de> + Default {
 			}
 		}
 	}
+
+	/// "Save As…" and "Open…" buttons using the native file dialog, disabled while one is open.
+	/// Returns the value a finished "Open…" read, for the caller to apply.
+	fn file_dialog_ui(
+		&self,
+		ui: &mut egui::Ui,
+		pending: &mut PendingFileDialog<Self>,
+	) -> Option<Self> {
+		#[cfg(not(target_arch = "wasm32"))]
+		{
+			let dialog = || {
+				rfd::AsyncFileDialog::new()
+					.add_filter("JSON", &["json"])
+					.set_file_name(Self::filename())
+			};
+			ui.add_enabled_ui(!pending.is_open(), |ui| {
+				ui.horizontal(|ui| {
+					if ui.button("Save As…").clicked() {
+						pending.spawn(DialogKind::SaveAs, dialog().save_file());
+					}
+					if ui.button("Open…").clicked() {
+						pending.spawn(DialogKind::Open, dialog().pick_file());
+					}
+				});
+			});
+		}
+		#[cfg(target_arch = "wasm32")]
+		let _ = ui;
+		pending.opened.take()
+	}
+}
+
+fn write_json(path: &Path, value: &impl Serialize) -> Result<()> {
+	#[cfg(not(target_arch = "wasm32"))]
+	{
+		let json = serde_json::to_string_pretty(value)?;
+		std::fs::write(path, json)?;
+	}
+	#[cfg(target_arch = "wasm32")]
+	let _ = (path, value);
+	Ok(())
+}
+
+/// `None` when the file doesn't exist
+fn read_json<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<Option<T>> {
+	#[cfg(not(target_arch = "wasm32"))]
+	{
+		if path.exists() {
+			let json = std::fs::read_to_string(path)?;
+			return Ok(Some(serde_json::from_str(&json)?));
+		}
+	}
+	#[cfg(target_arch = "wasm32")]
+	let _ = path;
+	Ok(None)
 }
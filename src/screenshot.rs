@@ -0,0 +1,149 @@
+use bevy::asset::RenderAssetUsages;
+use bevy::camera::{RenderTarget, visibility::RenderLayers};
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages};
+use bevy::render::view::screenshot::{Screenshot, save_to_disk};
+
+use crate::camera::{PADDING, create_orthographic_projection};
+use crate::terrain;
+
+const SCREENSHOT_KEY: KeyCode = KeyCode::F12;
+/// Width in pixels of a plan export; the height follows the terrain's aspect ratio.
+const PLAN_EXPORT_WIDTH: u32 = 2048;
+/// Frames the plan camera renders before capture, so its target has been drawn at least once.
+const PLAN_EXPORT_WARMUP_FRAMES: u32 = 2;
+
+/// `F12` saves the window to `screenshot_<timestamp>.png`; `Shift+F12` saves a north-up
+/// orthographic plan of the whole terrain to `plan_<timestamp>.png` without moving the camera.
+pub(crate) struct ScreenshotPlugin;
+
+impl Plugin for ScreenshotPlugin {
+	fn build(&self, app: &mut App) {
+		app.add_systems(Update, (take_screenshot, capture_plan_export));
+	}
+}
+
+/// Offscreen camera rendering the plan, waiting to be captured.
+#[derive(Component)]
+struct PlanExportCamera {
+	target: Handle<Image>,
+	frames_rendered: u32,
+	path: String,
+}
+
+/// `<prefix>_<seconds since the Unix epoch>.png`
+fn timestamped_png(prefix: &str) -> String {
+	#[cfg(not(target_arch = "wasm32"))]
+	{
+		let seconds = std::time::SystemTime::now()
+			.duration_since(std::time::UNIX_EPOCH)
+			.map_or(0, |elapsed| elapsed.as_secs());
+		format!("{prefix}_{seconds}.png")
+	}
+	// No system clock on the web; the browser de-duplicates download names itself
+	#[cfg(target_arch = "wasm32")]
+	format!("{prefix}.png")
+}
+
+/// Pixel size of a plan export covering `world_x` by `world_z` meters plus padding.
+fn plan_export_size(world_x: f32, world_z: f32) -> UVec2 {
+	let aspect = (world_z + PADDING) / (world_x + PADDING);
+	UVec2::new(
+		PLAN_EXPORT_WIDTH,
+		((PLAN_EXPORT_WIDTH as f32 * aspect).round() as u32).max(1),
+	)
+}
+
+fn plan_target_image(size: UVec2) -> Image {
+	let mut image = Image::new_fill(
+		Extent3d {
+			width: size.x,
+			height: size.y,
+			depth_or_array_layers: 1,
+		},
+		TextureDimension::D2,
+		&[0, 0, 0, 255],
+		TextureFormat::Rgba8UnormSrgb,
+		RenderAssetUsages::default(),
+	);
+	image.texture_descriptor.usage |=
+		TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC | TextureUsages::TEXTURE_BINDING;
+	image
+}
+
+fn take_screenshot(
+	mut commands: Commands,
+	keyboard_input: Res<ButtonInput<KeyCode>>,
+	mut images: ResMut<Assets<Image>>,
+	settings: Res<terrain::Settings>,
+	pending: Query<(), With<PlanExportCamera>>,
+) {
+	if !keyboard_input.just_pressed(SCREENSHOT_KEY) {
+		return;
+	}
+	if !keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+		let path = timestamped_png("screenshot");
+		debug!("Saving {path}");
+		commands
+			.spawn(Screenshot::primary_window())
+			.observe(save_to_disk(path));
+		return;
+	}
+	if !pending.is_empty() {
+		return;
+	}
+
+	let (world_x, world_z) = (settings.world_x(), settings.world_z());
+	let target = images.add(plan_target_image(plan_export_size(world_x, world_z)));
+	let (projection, distance) = create_orthographic_projection(world_x + PADDING);
+	commands.spawn((
+		Camera3d::default(),
+		Camera {
+			// Before the main camera, so it never draws over the window
+			order: -1,
+			..default()
+		},
+		RenderTarget::Image(target.clone().into()),
+		Projection::Orthographic(projection),
+		// North (-Z) up, east (+X) right
+		Transform::from_xyz(0.0, distance, 0.0).looking_at(Vec3::ZERO, Vec3::NEG_Z),
+		RenderLayers::layer(0),
+		PlanExportCamera {
+			target,
+			frames_rendered: 0,
+			path: timestamped_png("plan"),
+		},
+	));
+}
+
+fn capture_plan_export(
+	mut commands: Commands,
+	mut cameras: Query<(Entity, &mut PlanExportCamera)>,
+) {
+	for (entity, mut camera) in &mut cameras {
+		camera.frames_rendered += 1;
+		if camera.frames_rendered < PLAN_EXPORT_WARMUP_FRAMES {
+			continue;
+		}
+		debug!("Saving {}", camera.path);
+		commands
+			.spawn(Screenshot::image(camera.target.clone()))
+			.observe(save_to_disk(camera.path.clone()));
+		// The screenshot holds its own handle, so the target outlives the camera
+		commands.entity(entity).despawn();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn plan_export_keeps_the_terrain_aspect_ratio() {
+		let square = plan_export_size(1000.0, 1000.0);
+		assert_eq!(square, UVec2::splat(PLAN_EXPORT_WIDTH));
+		let tall = plan_export_size(1000.0, 2000.0 + PADDING);
+		assert_eq!(tall.x, PLAN_EXPORT_WIDTH);
+		assert!(tall.y > PLAN_EXPORT_WIDTH);
+	}
+}
@@ -0,0 +1,83 @@
+use bevy::{color::palettes::css::GRAY, prelude::*};
+use std::f32::consts::FRAC_PI_2;
+
+use super::Settings;
+
+/// Draws a faint reference grid at Y=0 that extends past the terrain edges.
+pub struct BackgroundGridPlugin;
+
+impl Plugin for BackgroundGridPlugin {
+	fn build(&self, app: &mut App) {
+		app
+			.init_resource::<BackgroundGridSettings>()
+			.add_systems(Update, draw_background_grid);
+	}
+}
+
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct BackgroundGridSettings {
+	pub enabled: bool,
+	/// Grid size as a multiple of the terrain size.
+	pub margin_factor: f32,
+	pub cell_size: f32,
+}
+
+impl Default for BackgroundGridSettings {
+	fn default() -> Self {
+		Self {
+			enabled: false,
+			margin_factor: 3.0,
+			cell_size: 100.0,
+		}
+	}
+}
+
+const GRID_ALPHA: f32 = 0.25;
+
+/// Full XZ size of the grid plane, centered on the terrain.
+pub fn grid_plane_extent(world_x: f32, world_z: f32, margin_factor: f32) -> Vec2 {
+	Vec2::new(world_x, world_z) * margin_factor.max(1.0)
+}
+
+fn draw_background_grid(
+	mut gizmos: Gizmos,
+	grid_settings: Res<BackgroundGridSettings>,
+	settings: Res<Settings>,
+) {
+	if !grid_settings.enabled || grid_settings.cell_size <= 0.0 {
+		return;
+	}
+	let extent = grid_plane_extent(
+		settings.world_x(),
+		settings.world_z(),
+		grid_settings.margin_factor,
+	);
+	let cells = (extent / grid_settings.cell_size).ceil().as_uvec2();
+	gizmos.grid(
+		Isometry3d::from_rotation(Quat::from_rotation_x(FRAC_PI_2)),
+		cells,
+		Vec2::splat(grid_settings.cell_size),
+		GRAY.with_alpha(GRID_ALPHA),
+	);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn grid_extent_scales_terrain_size_by_margin() {
+		assert_eq!(
+			grid_plane_extent(1000.0, 500.0, 3.0),
+			Vec2::new(3000.0, 1500.0)
+		);
+	}
+
+	#[test]
+	fn grid_extent_never_smaller_than_terrain() {
+		assert_eq!(
+			grid_plane_extent(1000.0, 500.0, 0.5),
+			Vec2::new(1000.0, 500.0)
+		);
+	}
+}
@@ -0,0 +1,43 @@
+use bevy::color::palettes::css::ORANGE;
+use bevy::gizmos::config::{GizmoConfigGroup, GizmoConfigStore};
+use bevy::prelude::*;
+
+use super::Settings;
+
+/// Wireframe box around the terrain extent, from sea level to the highest possible terrain.
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct TerrainBoundsGizmos;
+
+pub(super) struct TerrainBoundsPlugin;
+
+impl Plugin for TerrainBoundsPlugin {
+	fn build(&self, app: &mut App) {
+		app
+			.init_gizmo_group::<TerrainBoundsGizmos>()
+			.add_systems(Startup, hide_terrain_bounds)
+			.add_systems(Update, (toggle_terrain_bounds, draw_terrain_bounds));
+	}
+}
+
+fn hide_terrain_bounds(mut config_store: ResMut<GizmoConfigStore>) {
+	config_store.config_mut::<TerrainBoundsGizmos>().0.enabled = false;
+}
+
+/// `B` shows or hides the terrain bounds.
+fn toggle_terrain_bounds(
+	keyboard_input: Res<ButtonInput<KeyCode>>,
+	mut config_store: ResMut<GizmoConfigStore>,
+) {
+	if keyboard_input.just_pressed(KeyCode::KeyB) {
+		let (config, _) = config_store.config_mut::<TerrainBoundsGizmos>();
+		config.enabled = !config.enabled;
+	}
+}
+
+fn draw_terrain_bounds(mut gizmos: Gizmos<TerrainBoundsGizmos>, settings: Res<Settings>) {
+	let (min, max) = settings.world_bounds();
+	gizmos.cube(
+		Transform::from_translation((min + max) / 2.0).with_scale(max - min),
+		ORANGE,
+	);
+}
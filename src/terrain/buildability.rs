@@ -0,0 +1,63 @@
+use bevy_egui::egui;
+
+/// Steepest ground counted as buildable by default, in degrees (about a 10% grade).
+pub const DEFAULT_MAX_BUILDABLE_SLOPE_DEG: f32 = 5.7;
+
+/// Binary "where can I build" overlay: ground no steeper than `max_slope_deg` is shaded green,
+/// everything else red. Drawn by the contour overlay chunks, over the elevation tint.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BuildabilityMask {
+	pub enabled: bool,
+	pub max_slope_deg: f32,
+}
+
+impl Default for BuildabilityMask {
+	fn default() -> Self {
+		Self {
+			enabled: false,
+			max_slope_deg: DEFAULT_MAX_BUILDABLE_SLOPE_DEG,
+		}
+	}
+}
+
+impl BuildabilityMask {
+	/// Smallest vertical component a unit surface normal can have and still be buildable, which
+	/// is what the shader compares against.
+	pub fn min_normal_y(&self) -> f32 {
+		self.max_slope_deg.clamp(0.0, 90.0).to_radians().cos()
+	}
+}
+
+pub(super) fn buildability_ui(ui: &mut egui::Ui, mask: &mut BuildabilityMask) {
+	ui.checkbox(&mut mask.enabled, "Show buildable ground");
+	ui.label("Steepest buildable slope:");
+	ui.add_enabled(
+		mask.enabled,
+		egui::Slider::new(&mut mask.max_slope_deg, 0.0..=45.0).suffix("°"),
+	);
+	let grade = mask.max_slope_deg.to_radians().tan() * 100.0;
+	ui.label(format!("That is a {grade:.1}% grade"));
+}
+
+#[cfg(test)]
+mod tests {
+	use bevy::math::Vec3;
+
+	use super::*;
+
+	#[test]
+	fn slopes_past_the_threshold_fall_below_the_normal_cutoff() {
+		let mask = BuildabilityMask {
+			enabled: true,
+			max_slope_deg: 10.0,
+		};
+		// Unit normal of ground rising `grade` along x
+		let normal_y = |grade: f32| Vec3::new(-grade, 1.0, 0.0).normalize().y;
+		assert!(normal_y(0.15) >= mask.min_normal_y());
+		assert!(normal_y(0.2) < mask.min_normal_y());
+		assert_eq!(
+			BuildabilityMask::default().min_normal_y(),
+			5.7f32.to_radians().cos()
+		);
+	}
+}
@@ -1,6 +1,19 @@
+use crate::camera::PrimaryCamera3d;
 use crate::terrain;
+use crate::terrain::buildability::BuildabilityMask;
+use crate::terrain::contour_ramp::{ContourRamp, MAX_RAMP_STOPS, contour_ramp_ui, ramp_position};
+use crate::terrain::hypsometric::{HypsometricTint, MAX_TINT_STOPS, hypsometric_ui};
+use crate::terrain::marching_squares::{ContourLines, generate_contour_lines};
 use crate::ui_shell::{ActivePanel, UiShellState};
+use crate::units::Units;
+use alignment_path::GeometryTolerances;
+use bevy::asset::RenderAssetUsages;
+use bevy::camera::primitives::{Aabb, Frustum, MeshAabb};
+use bevy::gizmos::config::{GizmoConfigGroup, GizmoConfigStore};
+use bevy::math::Affine3A;
+use bevy::mesh::{Indices, PrimitiveTopology};
 use bevy::pbr::MaterialPlugin;
+use bevy::platform::collections::HashSet;
 use bevy::prelude::*;
 use bevy::render::render_resource::{
 	AsBindGroup, Extent3d, ShaderType, TextureDimension, TextureFormat,
@@ -8,6 +21,7 @@ use bevy::render::render_resource::{
 use bevy::shader::ShaderRef;
 use bevy_egui::{EguiContexts, egui};
 use log::debug;
+use std::ops::Range;
 
 pub struct ContourLinePlugin;
 
@@ -16,16 +30,30 @@ impl Plugin for ContourLinePlugin {
 		// Ensure the terrain plugin has a buffer to read elevation samples from.
 		app
 			.init_resource::<ContourState>()
+			.init_resource::<ContourChunks>()
+			.init_resource::<ContourLineCache>()
+			.init_gizmo_group::<MinorContourGizmos>()
+			.init_gizmo_group::<MajorContourGizmos>()
 			.add_plugins(MaterialPlugin::<ContourMaterial>::default())
 			.add_systems(Startup, create_placeholder_texture)
-			.add_systems(PostStartup, setup_contour_terrain_material)
 			.add_systems(bevy_egui::EguiPrimaryContextPass, contour_controls_ui)
 			.add_systems(
 				Update,
 				(
+					sync_contour_ramp_range.run_if(resource_changed::<terrain::Settings>),
 					update_contour_materials,
 					toggle_material_system,
-					apply_material_from_contour_state,
+					rebuild_contour_chunks.after(terrain::TerrainUpdateSet),
+					sync_contour_chunk_overlays
+						.after(rebuild_contour_chunks)
+						.after(toggle_material_system),
+					(
+						configure_contour_gizmos,
+						rebuild_contour_line_cache,
+						draw_contour_lines,
+					)
+						.chain()
+						.after(terrain::TerrainUpdateSet),
 				),
 			);
 	}
@@ -58,77 +86,321 @@ fn create_placeholder_texture(mut commands: Commands, mut images: ResMut<Assets<
 	debug!("Created placeholder texture for ContourMaterial");
 }
 
-/// System to add contour overlay material as a child entity
-/// This runs on startup to ensure terrain gets an overlay
-/// Keeps the StandardMaterial on the parent and adds contour overlay as a child
-fn setup_contour_terrain_material(
+/// Terrain cells per side of one contour overlay chunk.
+const CONTOUR_CHUNK_CELLS: u32 = 32;
+
+/// Overlay child covering one chunk of the terrain grid, so off-screen chunks are skipped.
+#[derive(Component)]
+struct ContourChunk {
+	index: usize,
+}
+
+struct ContourChunkData {
+	mesh: Handle<Mesh>,
+	aabb: Aabb,
+}
+
+/// Chunk meshes cut from the current terrain mesh, plus the one material they share.
+#[derive(Resource, Default)]
+struct ContourChunks {
+	chunks: Vec<ContourChunkData>,
+	material: Option<Handle<ContourMaterial>>,
+}
+
+/// Split a `grid_x` by `grid_z` cell grid into ranges of at most `chunk_cells` cells per side.
+fn chunk_ranges(grid_x: u32, grid_z: u32, chunk_cells: u32) -> Vec<(Range<u32>, Range<u32>)> {
+	let chunk_cells = chunk_cells.max(1);
+	let mut ranges = Vec::new();
+	for z0 in (0..grid_z).step_by(chunk_cells as usize) {
+		for x0 in (0..grid_x).step_by(chunk_cells as usize) {
+			ranges.push((
+				x0..(x0 + chunk_cells).min(grid_x),
+				z0..(z0 + chunk_cells).min(grid_z),
+			));
+		}
+	}
+	ranges
+}
+
+/// Cells along X and Z of a row-major terrain mesh, read from the vertices themselves since the
+/// mesh may be a coarser stride than the settings' grid or lag behind a settings change.
+fn mesh_grid_cells(positions: &[[f32; 3]]) -> Option<(u32, u32)> {
+	let first_z = positions.first()?[2];
+	let columns = positions.iter().take_while(|p| p[2] == first_z).count();
+	if columns < 2 || !positions.len().is_multiple_of(columns) || positions.len() / columns < 2 {
+		return None;
+	}
+	Some(((columns - 1) as u32, (positions.len() / columns - 1) as u32))
+}
+
+/// Copy the given cells out of the terrain mesh, keeping its normals so shading matches.
+fn build_chunk_mesh(
+	terrain_mesh: &Mesh,
+	grid_x: u32,
+	cells_x: &Range<u32>,
+	cells_z: &Range<u32>,
+) -> Option<Mesh> {
+	let positions = terrain_mesh
+		.attribute(Mesh::ATTRIBUTE_POSITION)?
+		.as_float3()?;
+	let normals = terrain_mesh
+		.attribute(Mesh::ATTRIBUTE_NORMAL)?
+		.as_float3()?;
+	let row = grid_x + 1;
+
+	let mut chunk_positions = Vec::new();
+	let mut chunk_normals = Vec::new();
+	for z in cells_z.start..=cells_z.end {
+		for x in cells_x.start..=cells_x.end {
+			let index = (z * row + x) as usize;
+			chunk_positions.push(*positions.get(index)?);
+			chunk_normals.push(*normals.get(index)?);
+		}
+	}
+
+	let vertices_x = cells_x.end - cells_x.start + 1;
+	let mut indices = Vec::new();
+	for z in 0..cells_z.end - cells_z.start {
+		for x in 0..vertices_x - 1 {
+			let current = z * vertices_x + x;
+			let next_x = current + 1;
+			let next_z = current + vertices_x;
+			let next_both = next_z + 1;
+			// Same winding as the terrain mesh
+			indices.extend_from_slice(&[current, next_z, next_x]);
+			indices.extend_from_slice(&[next_x, next_z, next_both]);
+		}
+	}
+
+	Some(
+		Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::all())
+			.with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, chunk_positions)
+			.with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, chunk_normals)
+			.with_inserted_indices(Indices::U32(indices)),
+	)
+}
+
+/// Indices of the chunks whose bounds touch the camera frustum.
+fn visible_chunk_indices<'a>(
+	chunk_bounds: impl IntoIterator<Item = &'a Aabb>,
+	frustum: &Frustum,
+) -> Vec<usize> {
+	chunk_bounds
+		.into_iter()
+		.enumerate()
+		.filter(|(_, aabb)| frustum.intersects_obb(aabb, &Affine3A::IDENTITY, true, true))
+		.map(|(index, _)| index)
+		.collect()
+}
+
+/// Re-cut the chunk meshes whenever the terrain mesh is replaced.
+fn rebuild_contour_chunks(
+	mut commands: Commands,
+	mut chunks: ResMut<ContourChunks>,
+	mut meshes: ResMut<Assets<Mesh>>,
+	mut removals: ResMut<terrain::DeferredMeshRemovals>,
+	terrain_query: Query<&Mesh3d, (With<terrain::TerrainMesh>, Changed<Mesh3d>)>,
+	overlays: Query<Entity, With<ContourChunk>>,
+) {
+	let Ok(terrain_mesh) = terrain_query.single() else {
+		return;
+	};
+	let Some(mesh) = meshes.get(&terrain_mesh.0).cloned() else {
+		return;
+	};
+
+	for overlay in &overlays {
+		commands.entity(overlay).despawn();
+	}
+	for old_chunk in chunks.chunks.drain(..) {
+		removals.defer(old_chunk.mesh);
+	}
+
+	let Some((grid_x, grid_z)) = mesh
+		.attribute(Mesh::ATTRIBUTE_POSITION)
+		.and_then(|positions| positions.as_float3())
+		.and_then(mesh_grid_cells)
+	else {
+		warn!("Terrain mesh isn't a vertex grid, skipping contour overlay");
+		return;
+	};
+	for (cells_x, cells_z) in chunk_ranges(grid_x, grid_z, CONTOUR_CHUNK_CELLS) {
+		let Some(chunk_mesh) = build_chunk_mesh(&mesh, grid_x, &cells_x, &cells_z) else {
+			warn!("Terrain mesh doesn't match the terrain grid, skipping contour overlay");
+			for chunk in chunks.chunks.drain(..) {
+				removals.defer(chunk.mesh);
+			}
+			return;
+		};
+		let Some(aabb) = chunk_mesh.compute_aabb() else {
+			continue;
+		};
+		chunks.chunks.push(ContourChunkData {
+			mesh: meshes.add(chunk_mesh),
+			aabb,
+		});
+	}
+	debug!("Built {} contour overlay chunk(s)", chunks.chunks.len());
+}
+
+/// Keep exactly one overlay child per visible chunk while any overlay layer is shown.
+fn sync_contour_chunk_overlays(
 	mut commands: Commands,
+	mut chunks: ResMut<ContourChunks>,
 	mut contour_materials: ResMut<Assets<ContourMaterial>>,
 	contour_state: Res<ContourState>,
-	placeholder_texture: Option<Res<PlaceholderTextureResource>>,
-	terrain_query: Query<
-		(Entity, &Mesh3d),
-		(
-			With<terrain::TerrainMesh>,
-			Without<ContourMaterialApplied>,
-			Without<StandardMaterialApplied>,
-		),
-	>,
+	placeholder_texture: Res<PlaceholderTextureResource>,
+	camera: Single<&Frustum, With<PrimaryCamera3d>>,
+	terrain: Single<Entity, With<terrain::TerrainMesh>>,
+	overlays: Query<(Entity, &ContourChunk)>,
 ) {
-	let placeholder_handle = placeholder_texture
-		.map(|r| r.handle.clone())
-		.unwrap_or_else(Handle::default);
-
-	let mut count = 0;
-	for (entity, mesh_handle) in &terrain_query {
-		// Ensure parent keeps StandardMaterial (from lib.rs setup_terrain)
-		// Just add the marker to indicate we've processed it
-		commands.entity(entity).insert(StandardMaterialApplied);
-
-		// Create a child entity with the same mesh but contour material overlay
-		if contour_state.enabled() {
-			let contour_material = ContourMaterial {
+	let visible: HashSet<usize> = if contour_state.overlay_shown() {
+		visible_chunk_indices(chunks.chunks.iter().map(|chunk| &chunk.aabb), *camera)
+			.into_iter()
+			.collect()
+	} else {
+		HashSet::default()
+	};
+
+	let mut existing = HashSet::default();
+	for (entity, chunk) in &overlays {
+		if visible.contains(&chunk.index) {
+			existing.insert(chunk.index);
+		} else {
+			commands.entity(entity).despawn();
+		}
+	}
+	if visible.len() == existing.len() {
+		return;
+	}
+
+	let material = chunks
+		.material
+		.get_or_insert_with(|| {
+			contour_materials.add(ContourMaterial {
 				settings: contour_state.material_settings(),
-				placeholder_texture: placeholder_handle.clone(),
-			};
-
-			let material_handle = contour_materials.add(contour_material);
-
-			// Spawn child entity with contour overlay
-			// mesh_handle is &Mesh3d which derefs to Handle<Mesh>
-			let child_entity = commands
-				.spawn((
-					Mesh3d((**mesh_handle).clone()),
-					MeshMaterial3d(material_handle),
-					ContourMaterialApplied,
-				))
-				.id();
-			// Set parent-child relationship
-			commands.entity(entity).add_child(child_entity);
+				placeholder_texture: placeholder_texture.handle.clone(),
+			})
+		})
+		.clone();
+	for index in visible.difference(&existing) {
+		let Some(chunk) = chunks.chunks.get(*index) else {
+			continue;
+		};
+		let child = commands
+			.spawn((
+				Mesh3d(chunk.mesh.clone()),
+				MeshMaterial3d(material.clone()),
+				ContourChunk { index: *index },
+			))
+			.id();
+		commands.entity(*terrain).add_child(child);
+	}
+}
+
+/// Lifts world-space contour lines off the surface so they don't z-fight with it.
+const CONTOUR_LINE_LIFT: f32 = 0.5;
+/// Major contours are this many times thicker than minor ones; keep in sync with the shader.
+const MAJOR_LINE_SCALE: f32 = 2.0;
+/// Opacity of minor contours; keep in sync with the shader.
+const MINOR_LINE_ALPHA: f32 = 0.6;
+
+/// Gizmo group for minor world-space contours.
+#[derive(Default, Reflect, GizmoConfigGroup)]
+struct MinorContourGizmos;
+
+/// Gizmo group for major world-space contours, drawn thicker than the minor ones.
+#[derive(Default, Reflect, GizmoConfigGroup)]
+struct MajorContourGizmos;
+
+/// Marching-squares contours for the current terrain, rebuilt when the terrain or levels change.
+#[derive(Resource, Default)]
+struct ContourLineCache {
+	lines: Vec<ContourLines>,
+	/// Interval and major spacing the lines were traced with.
+	levels: Option<(f32, u32)>,
+	/// Terrain inputs the lines were traced with, so display-only edits don't retrace them.
+	traced_from: Option<terrain::Settings>,
+}
+
+fn rebuild_contour_line_cache(
+	mut cache: ResMut<ContourLineCache>,
+	contour_state: Res<ContourState>,
+	settings: Res<terrain::Settings>,
+	height_map: Single<Ref<terrain::HeightMap>, With<terrain::TerrainMesh>>,
+) {
+	if !contour_state.world_lines {
+		if cache.levels.is_some() {
+			*cache = ContourLineCache::default();
 		}
-		count += 1;
+		return;
 	}
-	if count > 0 {
-		debug!(
-			"Set up contour overlay for {} terrain mesh(es) (enabled: {})",
-			count,
-			contour_state.enabled()
-		);
+	let levels = (
+		contour_state.settings.interval,
+		contour_state.settings.every_nth,
+	);
+	let terrain_changed =
+		settings.is_changed() && cache.traced_from.as_ref() != Some(&settings.terrain_inputs());
+	if cache.levels == Some(levels) && !height_map.is_changed() && !terrain_changed {
+		return;
 	}
+	cache.lines = generate_contour_lines(
+		&height_map,
+		&settings,
+		levels.0,
+		levels.1,
+		&GeometryTolerances::DEFAULT,
+	);
+	cache.levels = Some(levels);
+	cache.traced_from = Some(settings.terrain_inputs());
+	debug!(
+		"Traced {} contour level(s) with marching squares",
+		cache.lines.len()
+	);
 }
 
-/// Marker component to indicate terrain has been updated with contour material
-#[derive(Component)]
-struct ContourMaterialApplied;
+/// Match the contour gizmo line widths to the thickness setting.
+fn configure_contour_gizmos(
+	contour_state: Res<ContourState>,
+	mut config_store: ResMut<GizmoConfigStore>,
+) {
+	if !contour_state.is_changed() {
+		return;
+	}
+	let thickness = contour_state.settings.line_thickness;
+	config_store.config_mut::<MinorContourGizmos>().0.line.width = thickness;
+	config_store.config_mut::<MajorContourGizmos>().0.line.width = thickness * MAJOR_LINE_SCALE;
+}
 
-/// Marker component to indicate terrain has been updated with standard material
-#[derive(Component)]
-struct StandardMaterialApplied;
+fn draw_contour_lines(
+	mut minor_gizmos: Gizmos<MinorContourGizmos>,
+	mut major_gizmos: Gizmos<MajorContourGizmos>,
+	cache: Res<ContourLineCache>,
+	contour_state: Res<ContourState>,
+) {
+	if !contour_state.world_lines {
+		return;
+	}
+	let lift = Vec3::Y * CONTOUR_LINE_LIFT;
+	for lines in &cache.lines {
+		let color = contour_state.line_color_at(lines.elevation);
+		let minor_color = color.with_alpha(MINOR_LINE_ALPHA);
+		for segment in &lines.segments {
+			let (start, end) = (segment.start + lift, segment.end + lift);
+			if lines.is_major {
+				major_gizmos.line(start, end, color);
+			} else {
+				minor_gizmos.line(start, end, minor_color);
+			}
+		}
+	}
+}
 
 fn contour_controls_ui(
 	mut contexts: EguiContexts,
 	mut contour_state: ResMut<ContourState>,
 	ui_shell_state: Res<UiShellState>,
+	units: Res<Units>,
 ) {
 	if ui_shell_state.active_panel != ActivePanel::ContourLines {
 		return;
@@ -141,25 +413,37 @@ fn contour_controls_ui(
 		let mut color_changed = false;
 		let mut interval_changed = false;
 		let mut thickness_changed = false;
+		let mut world_lines = contour_state.world_lines;
+		let mut every_nth = contour_state.settings.every_nth;
+		let mut tint = contour_state.tint.clone();
+		let mut ramp = contour_state.ramp;
 
 		egui::Window::new("Contour Settings")
 			.fixed_pos(egui::pos2(8.0, 8.0))
 			.movable(false)
 			.resizable(false)
 			.show(ctx, |ui| {
+				ui.checkbox(&mut world_lines, "World-space lines")
+					.on_hover_text(
+						"Trace crisp contour lines with marching squares instead of only shading them",
+					);
+
+				contour_ramp_ui(ui, &mut ramp);
 				// Color picker
-				ui.horizontal(|ui| {
-					ui.label("Line Color:");
-					if ui.color_edit_button_rgb(&mut color_array).changed() {
-						color_changed = true;
-					}
+				ui.add_enabled_ui(ramp == ContourRamp::Single, |ui| {
+					ui.horizontal(|ui| {
+						ui.label("Line Color:");
+						if ui.color_edit_button_rgb(&mut color_array).changed() {
+							color_changed = true;
+						}
+					});
 				});
 
 				// Interval slider
 				ui.horizontal(|ui| {
 					ui.label("Interval:");
 					if ui
-						.add(egui::Slider::new(&mut interval, 1.0..=200.0).suffix(" units"))
+						.add(units.length_slider(&mut interval, 1.0..=200.0))
 						.changed()
 					{
 						interval_changed = true;
@@ -176,6 +460,14 @@ fn contour_controls_ui(
 						thickness_changed = true;
 					}
 				});
+
+				ui.horizontal(|ui| {
+					ui.label("Major every:");
+					ui.add(egui::DragValue::new(&mut every_nth).range(0..=20))
+						.on_hover_text("Draw every nth contour bolder; 0 keeps them all minor");
+				});
+
+				ui.collapsing("Elevation tint", |ui| hypsometric_ui(ui, &mut tint, *units));
 			});
 
 		if color_changed {
@@ -187,6 +479,18 @@ fn contour_controls_ui(
 		if thickness_changed {
 			contour_state.settings.line_thickness = line_thickness;
 		}
+		if every_nth != contour_state.settings.every_nth {
+			contour_state.settings.every_nth = every_nth;
+		}
+		if world_lines != contour_state.world_lines {
+			contour_state.world_lines = world_lines;
+		}
+		if tint != contour_state.tint {
+			contour_state.tint = tint;
+		}
+		if ramp != contour_state.ramp {
+			contour_state.ramp = ramp;
+		}
 	}
 }
 
@@ -196,7 +500,7 @@ fn update_contour_materials(
 ) {
 	// Update existing contour materials when settings change (but not when enabling/disabling)
 	// Only update when enabled and when settings actually changed
-	if contour_state.is_changed() && contour_state.enabled() {
+	if contour_state.is_changed() && contour_state.overlay_shown() {
 		let mut count = 0;
 		for (_, material) in materials.iter_mut() {
 			material.settings = contour_state.material_settings();
@@ -214,6 +518,17 @@ fn update_contour_materials(
 	}
 }
 
+/// Stretch the line color ramp over the terrain's height range.
+fn sync_contour_ramp_range(
+	settings: Res<terrain::Settings>,
+	mut contour_state: ResMut<ContourState>,
+) {
+	let (min, max) = settings.world_bounds();
+	if contour_state.ramp_range != (min.y, max.y) {
+		contour_state.ramp_range = (min.y, max.y);
+	}
+}
+
 const CONTOUR_TOGGLE: KeyCode = KeyCode::KeyC;
 
 /// System to toggle contour_state.enabled via keyboard
@@ -228,76 +543,6 @@ fn toggle_material_system(
 	}
 }
 
-/// System to toggle contour overlay visibility and update settings
-/// Runs when contour_state changes (detected automatically by Bevy)
-fn apply_material_from_contour_state(
-	mut commands: Commands,
-	mut contour_materials: ResMut<Assets<ContourMaterial>>,
-	contour_state: Res<ContourState>,
-	placeholder_texture: Res<PlaceholderTextureResource>,
-	terrain_query: Query<(Entity, &Mesh3d), With<terrain::TerrainMesh>>,
-	contour_children: Query<Entity, (With<ContourMaterialApplied>, With<Mesh3d>)>,
-	children_query: Query<&Children>,
-) {
-	// Only react when contour_state actually changed
-	if !contour_state.is_changed() {
-		return;
-	}
-
-	for (terrain_entity, mesh_handle) in &terrain_query {
-		// Find existing contour overlay child entities for this terrain
-		let existing_overlays: Vec<Entity> = children_query
-			.get(terrain_entity)
-			.map(|children| {
-				let mut overlays = Vec::new();
-				for entity_ref in children.iter() {
-					let entity = entity_ref.clone();
-					if contour_children.contains(entity) {
-						overlays.push(entity);
-					}
-				}
-				overlays
-			})
-			.unwrap_or_default();
-
-		if contour_state.enabled() {
-			// Create overlay if it doesn't exist
-			if existing_overlays.is_empty() {
-				let contour_material = ContourMaterial {
-					settings: contour_state.material_settings(),
-					placeholder_texture: placeholder_texture.handle.clone(),
-				};
-
-				let material_handle = contour_materials.add(contour_material);
-
-				// Spawn child entity with contour overlay
-				// mesh_handle is &Mesh3d which derefs to Handle<Mesh>
-				let child_entity = commands
-					.spawn((
-						Mesh3d((**mesh_handle).clone()),
-						MeshMaterial3d(material_handle),
-						ContourMaterialApplied,
-					))
-					.id();
-				// Set parent-child relationship
-				commands.entity(terrain_entity).add_child(child_entity);
-				debug!("Created contour overlay child for terrain entity");
-			}
-			// Material updates happen in update_contour_materials system
-		} else {
-			// Remove contour overlay children
-			for overlay_entity in existing_overlays {
-				commands.entity(overlay_entity).despawn();
-			}
-		}
-	}
-
-	debug!(
-		"Updated contour overlay (enabled: {})",
-		contour_state.enabled()
-	);
-}
-
 /// Standalone material that adds contour lines to terrain based on height
 #[derive(AsBindGroup, Asset, TypePath, Debug, Clone)]
 pub struct ContourMaterial {
@@ -318,7 +563,8 @@ pub struct ContourSettings {
 	pub metallic: f32,
 	/// Perceptual roughness (0.0 = smooth, 1.0 = rough)
 	pub perceptual_roughness: f32,
-	/// Height interval between contour lines in world units
+	/// Meters of world elevation (final Y, height multiplier included) between contour lines;
+	/// the world-space lines use the same spacing
 	pub interval: f32,
 	/// Color of the contour lines (RGB)
 	pub line_color: Vec3,
@@ -326,6 +572,25 @@ pub struct ContourSettings {
 	pub line_thickness: f32,
 	/// Whether contour lines are enabled (1 = enabled, 0 = disabled)
 	pub enabled: u32,
+	/// Every nth contour is a major (thicker, opaque) line; 0 disables major lines
+	pub every_nth: u32,
+	/// Whether the elevation tint is drawn (1 = enabled, 0 = disabled)
+	pub tint_enabled: u32,
+	pub tint_opacity: f32,
+	pub tint_stop_count: u32,
+	/// Packed by [`HypsometricTint::packed_stops`]
+	pub tint_stops: [Vec4; MAX_TINT_STOPS],
+	/// Whether the buildability mask is drawn (1 = enabled, 0 = disabled)
+	pub mask_enabled: u32,
+	/// From [`BuildabilityMask::min_normal_y`]
+	pub mask_min_normal_y: f32,
+	/// Stops of the line color ramp, packed by [`ContourRamp::packed_stops`]; a count of 0 draws
+	/// every line in `line_color`
+	pub ramp_stop_count: u32,
+	pub ramp_stops: [Vec4; MAX_RAMP_STOPS],
+	/// Elevations the ramp starts and ends at
+	pub ramp_min: f32,
+	pub ramp_max: f32,
 }
 
 impl Default for ContourSettings {
@@ -337,6 +602,17 @@ impl Default for ContourSettings {
 			interval: 40.0,
 			line_thickness: 2.0,
 			enabled: 0,
+			every_nth: 5,
+			tint_enabled: 0,
+			tint_opacity: 0.0,
+			tint_stop_count: 0,
+			tint_stops: [Vec4::ZERO; MAX_TINT_STOPS],
+			mask_enabled: 0,
+			mask_min_normal_y: 1.0,
+			ramp_stop_count: 0,
+			ramp_stops: [Vec4::ZERO; MAX_RAMP_STOPS],
+			ramp_min: 0.0,
+			ramp_max: 0.0,
 		}
 	}
 }
@@ -365,6 +641,13 @@ impl Material for ContourMaterial {
 pub struct ContourState {
 	/// The single source of truth for contour settings
 	pub settings: ContourSettings,
+	/// Draw marching-squares contour lines in world space.
+	pub world_lines: bool,
+	pub tint: HypsometricTint,
+	pub buildability: BuildabilityMask,
+	pub ramp: ContourRamp,
+	/// Lowest and highest terrain elevation, which the ramp is stretched over.
+	pub ramp_range: (f32, f32),
 }
 
 impl ContourState {
@@ -392,12 +675,33 @@ impl ContourState {
 		self.settings.line_color = Vec3::new(color[0], color[1], color[2]);
 	}
 
+	/// Color of the contour line at `elevation`: from the ramp, or the single line color.
+	pub fn line_color_at(&self, elevation: f32) -> Color {
+		self
+			.ramp
+			.color_at(ramp_position(elevation, self.ramp_range))
+			.unwrap_or_else(|| Color::srgb_from_array(self.line_color_array()))
+	}
+
+	/// Whether the overlay chunks are needed, for the contour lines, the elevation tint or the
+	/// buildability mask.
+	pub fn overlay_shown(&self) -> bool {
+		self.enabled() || self.tint.enabled || self.buildability.enabled
+	}
+
 	/// Get settings for material creation, ensuring enabled is properly set
 	fn material_settings(&self) -> ContourSettings {
 		let mut settings = self.settings.clone();
 		if self.enabled() {
 			settings.enabled = 1;
 		}
+		settings.tint_enabled = self.tint.enabled as u32;
+		settings.tint_opacity = self.tint.opacity;
+		(settings.tint_stop_count, settings.tint_stops) = self.tint.packed_stops();
+		settings.mask_enabled = self.buildability.enabled as u32;
+		settings.mask_min_normal_y = self.buildability.min_normal_y();
+		(settings.ramp_stop_count, settings.ramp_stops) = self.ramp.packed_stops();
+		(settings.ramp_min, settings.ramp_max) = self.ramp_range;
 		settings
 	}
 }
@@ -406,6 +710,48 @@ impl Default for ContourState {
 	fn default() -> Self {
 		Self {
 			settings: ContourSettings::default(),
+			world_lines: false,
+			tint: HypsometricTint::default(),
+			buildability: BuildabilityMask::default(),
+			ramp: ContourRamp::default(),
+			ramp_range: (0.0, 0.0),
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn chunk_ranges_cover_grid_once() {
+		let ranges = chunk_ranges(70, 40, 32);
+		assert_eq!(ranges.len(), 3 * 2);
+		let cells: usize = ranges.iter().map(|(x, z)| x.len() * z.len()).sum();
+		assert_eq!(cells, 70 * 40);
+	}
+
+	#[test]
+	fn mesh_grid_cells_counts_rows_and_columns() {
+		let positions: Vec<[f32; 3]> = (0..3)
+			.flat_map(|z| (0..5).map(move |x| [x as f32, 0.0, z as f32]))
+			.collect();
+		assert_eq!(mesh_grid_cells(&positions), Some((4, 2)));
+		assert_eq!(mesh_grid_cells(&positions[..7]), None);
+	}
+
+	#[test]
+	fn overlay_count_matches_chunks_inside_frustum() {
+		// Four 100 m chunks around the origin, seen from straight above the first one
+		let chunk_bounds: Vec<Aabb> = [(-100.0, -100.0), (0.0, -100.0), (-100.0, 0.0), (0.0, 0.0)]
+			.into_iter()
+			.map(|(x, z)| Aabb::from_min_max(Vec3::new(x, 0.0, z), Vec3::new(x + 100.0, 20.0, z + 100.0)))
+			.collect();
+		let view = Transform::from_xyz(-50.0, 200.0, -50.0).looking_to(Vec3::NEG_Y, Vec3::NEG_Z);
+		let projection = Mat4::orthographic_rh(-20.0, 20.0, -20.0, 20.0, 0.1, 1000.0);
+		let frustum = Frustum::from_clip_from_world(&(projection * view.to_matrix().inverse()));
+
+		let visible = visible_chunk_indices(&chunk_bounds, &frustum);
+		assert_eq!(visible, vec![0]);
+	}
+}
@@ -0,0 +1,132 @@
+use bevy::prelude::*;
+use bevy_egui::egui;
+
+/// Stops a ramp may have; keep in sync with `MAX_RAMP_STOPS` in the shader.
+pub const MAX_RAMP_STOPS: usize = 4;
+
+/// How contour lines are colored: all in the one line color, or by elevation along a preset ramp
+/// running from the lowest to the highest terrain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ContourRamp {
+	#[default]
+	Single,
+	/// Brown lowlands to white peaks.
+	Earth,
+	/// Blue through green and yellow to red.
+	Spectral,
+}
+
+impl ContourRamp {
+	pub const ALL: [Self; 3] = [Self::Single, Self::Earth, Self::Spectral];
+
+	pub const fn label(self) -> &'static str {
+		match self {
+			Self::Single => "Single color",
+			Self::Earth => "Earth",
+			Self::Spectral => "Spectral",
+		}
+	}
+
+	/// sRGB colors spaced evenly along the ramp; empty for [`Self::Single`].
+	const fn stops(self) -> &'static [[f32; 3]] {
+		match self {
+			Self::Single => &[],
+			Self::Earth => &[
+				[0.4, 0.25, 0.1],
+				[0.6, 0.45, 0.25],
+				[0.85, 0.78, 0.65],
+				[1.0, 1.0, 1.0],
+			],
+			Self::Spectral => &[
+				[0.2, 0.35, 0.85],
+				[0.2, 0.7, 0.3],
+				[0.95, 0.85, 0.2],
+				[0.85, 0.2, 0.15],
+			],
+		}
+	}
+
+	/// Stops for the shader uniform: linear RGB in `xyz`, position along the ramp (0 to 1) in
+	/// `w`. A count of 0 means the lines keep the single line color.
+	pub fn packed_stops(self) -> (u32, [Vec4; MAX_RAMP_STOPS]) {
+		let stops = self.stops();
+		let last = stops.len().saturating_sub(1).max(1) as f32;
+		let mut packed = [Vec4::ZERO; MAX_RAMP_STOPS];
+		for (index, (slot, srgb)) in packed.iter_mut().zip(stops).enumerate() {
+			let linear = Color::srgb_from_array(*srgb).to_linear();
+			*slot = Vec4::new(linear.red, linear.green, linear.blue, index as f32 / last);
+		}
+		(stops.len().min(MAX_RAMP_STOPS) as u32, packed)
+	}
+
+	/// Color of a line `t` of the way up the ramp, blended in linear RGB like the shader does, or
+	/// `None` for [`Self::Single`].
+	pub fn color_at(self, t: f32) -> Option<Color> {
+		let (count, stops) = self.packed_stops();
+		let stops = &stops[..count as usize];
+		let first = stops.first()?;
+		let t = t.clamp(0.0, 1.0);
+		let mut color = first.truncate();
+		for pair in stops.windows(2) {
+			let (low, high) = (pair[0], pair[1]);
+			if t >= high.w {
+				color = high.truncate();
+			} else {
+				if t > low.w {
+					color = low
+						.truncate()
+						.lerp(high.truncate(), (t - low.w) / (high.w - low.w));
+				}
+				break;
+			}
+		}
+		Some(Color::linear_rgb(color.x, color.y, color.z))
+	}
+}
+
+/// How far `elevation` is up the ramp spanning `range`, unclamped.
+pub fn ramp_position(elevation: f32, (low, high): (f32, f32)) -> f32 {
+	if high > low {
+		(elevation - low) / (high - low)
+	} else {
+		0.0
+	}
+}
+
+pub(super) fn contour_ramp_ui(ui: &mut egui::Ui, ramp: &mut ContourRamp) {
+	ui.horizontal(|ui| {
+		ui.label("Line colors:");
+		egui::ComboBox::from_id_salt("contour_ramp")
+			.selected_text(ramp.label())
+			.show_ui(ui, |ui| {
+				for option in ContourRamp::ALL {
+					ui.selectable_value(ramp, option, option.label());
+				}
+			});
+	});
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn ramps_run_from_their_first_to_their_last_stop() {
+		assert_eq!(ContourRamp::Single.color_at(0.5), None);
+		assert_eq!(ContourRamp::Single.packed_stops().0, 0);
+
+		let (count, packed) = ContourRamp::Earth.packed_stops();
+		assert_eq!(count, 4);
+		assert_eq!(packed[0].w, 0.0);
+		assert_eq!(packed[3].w, 1.0);
+		let white = ContourRamp::Earth.color_at(1.0).unwrap().to_linear();
+		assert!((white.red - 1.0).abs() < 1e-6 && (white.blue - 1.0).abs() < 1e-6);
+		assert_eq!(
+			ContourRamp::Earth.color_at(-3.0),
+			ContourRamp::Earth.color_at(0.0)
+		);
+
+		assert_eq!(ramp_position(150.0, (100.0, 300.0)), 0.25);
+		assert_eq!(ramp_position(150.0, (100.0, 100.0)), 0.0);
+	}
+}
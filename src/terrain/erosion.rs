@@ -0,0 +1,140 @@
+use bevy_egui::egui;
+
+use super::{HeightMap, Settings, world_size_for_height};
+
+/// Steepest slope loose material rests at before it slides, in degrees.
+pub const DEFAULT_TALUS_ANGLE_DEG: f32 = 35.0;
+pub const MAX_THERMAL_EROSION_ITERATIONS: u32 = 200;
+/// Share of the excess height moved per iteration. Half evens out a pair of cells in one step
+/// without overshooting.
+const SETTLE_FRACTION: f32 = 0.5;
+
+/// Neighbor offsets with their distance in cells.
+const NEIGHBORS: [(i32, i32, f32); 8] = [
+	(-1, 0, 1.0),
+	(1, 0, 1.0),
+	(0, -1, 1.0),
+	(0, 1, 1.0),
+	(-1, -1, std::f32::consts::SQRT_2),
+	(1, -1, std::f32::consts::SQRT_2),
+	(-1, 1, std::f32::consts::SQRT_2),
+	(1, 1, std::f32::consts::SQRT_2),
+];
+
+/// Thermal (talus) erosion: wherever the slope to a lower neighbor is steeper than
+/// `talus_angle` (radians), material slides down until it isn't. Each iteration reads the
+/// previous heights so the result doesn't depend on scan order, and material is only moved,
+/// never created, so the total stays the same. `settings` supplies the grid spacing and
+/// vertical scale that turn normalized heights into slopes.
+pub fn apply_thermal_erosion(
+	height_map: &mut HeightMap,
+	talus_angle: f32,
+	iterations: u32,
+	settings: &Settings,
+) {
+	let height_scale = world_size_for_height(settings) * settings.height_multiplier;
+	let cell_size = settings.base_world_size / settings.base_grid_resolution.max(1) as f32;
+	if height_scale <= 0.0 || cell_size <= 0.0 {
+		return;
+	}
+	// Largest normalized height difference one cell apart that is still at rest
+	let talus = talus_angle.clamp(0.0, std::f32::consts::FRAC_PI_2).tan() * cell_size / height_scale;
+
+	let (length_x, length_z) = (height_map.length_x as i32, height_map.length_z() as i32);
+	let mut deltas = vec![0.0; height_map.heights.len()];
+	for _ in 0..iterations {
+		deltas.fill(0.0);
+		let mut moved = false;
+		for z in 0..=length_z {
+			for x in 0..=length_x {
+				let height = height_map.get(x as u32, z as u32);
+				let mut excess = [0.0; NEIGHBORS.len()];
+				for (k, (dx, dz, distance)) in NEIGHBORS.iter().enumerate() {
+					let (nx, nz) = (x + dx, z + dz);
+					if !(0..=length_x).contains(&nx) || !(0..=length_z).contains(&nz) {
+						continue;
+					}
+					let drop = height - height_map.get(nx as u32, nz as u32);
+					excess[k] = (drop - talus * distance).max(0.0);
+				}
+				let total: f32 = excess.iter().sum();
+				if total <= 0.0 {
+					continue;
+				}
+				let max_excess = excess.iter().copied().fold(0.0, f32::max);
+				let amount = SETTLE_FRACTION * max_excess;
+				let index = (z * (length_x + 1) + x) as usize;
+				deltas[index] -= amount;
+				for (k, (dx, dz, _)) in NEIGHBORS.iter().enumerate() {
+					if excess[k] > 0.0 {
+						let neighbor = ((z + dz) * (length_x + 1) + x + dx) as usize;
+						deltas[neighbor] += amount * excess[k] / total;
+					}
+				}
+				moved = true;
+			}
+		}
+		if !moved {
+			break;
+		}
+		for (height, delta) in height_map.heights.iter_mut().zip(&deltas) {
+			*height += delta;
+		}
+	}
+}
+
+pub(super) fn thermal_erosion_ui(ui: &mut egui::Ui, settings: &mut Settings) {
+	ui.label("Iterations (0 disables):");
+	ui.add(
+		egui::Slider::new(
+			&mut settings.thermal_erosion_iterations,
+			0..=MAX_THERMAL_EROSION_ITERATIONS,
+		)
+		.step_by(1.0),
+	);
+	ui.label("Talus angle:");
+	ui.add(egui::Slider::new(&mut settings.talus_angle_deg, 5.0..=85.0).suffix("°"));
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn spike_map() -> (HeightMap, Settings) {
+		let settings = Settings {
+			base_grid_resolution: 4,
+			..Settings::default()
+		};
+		let mut heights = vec![0.0; 25];
+		heights[2 * 5 + 2] = 1.0;
+		(
+			HeightMap {
+				length_x: 4,
+				heights,
+			},
+			settings,
+		)
+	}
+
+	#[test]
+	fn thermal_erosion_knocks_down_steep_slopes_and_keeps_mass() {
+		let (mut height_map, settings) = spike_map();
+		apply_thermal_erosion(&mut height_map, 30f32.to_radians(), 50, &settings);
+
+		let total: f32 = height_map.heights.iter().sum();
+		assert!((total - 1.0).abs() < 1e-4, "mass changed to {total}");
+		assert!(height_map.get(2, 2) < 1.0, "peak should have slumped");
+		assert!(
+			height_map.get(1, 2) > 0.0,
+			"neighbors should have caught material"
+		);
+	}
+
+	#[test]
+	fn slopes_below_the_talus_angle_are_left_alone() {
+		let (mut height_map, settings) = spike_map();
+		let before = height_map.heights.clone();
+		apply_thermal_erosion(&mut height_map, 89f32.to_radians(), 10, &settings);
+		assert_eq!(height_map.heights, before);
+	}
+}
@@ -0,0 +1,107 @@
+use bevy::prelude::*;
+use bevy_egui::egui;
+
+use crate::units::Units;
+
+/// Stops the overlay shader has room for; keep in sync with `MAX_TINT_STOPS` in the shader.
+pub const MAX_TINT_STOPS: usize = 8;
+
+/// Topo-map style shading: the terrain is tinted by a color ramp over world elevation, blended
+/// over the lit surface by the same overlay chunks that draw the contour lines.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HypsometricTint {
+	pub enabled: bool,
+	/// How much the ramp covers the lit terrain; lines are drawn on top either way.
+	pub opacity: f32,
+	/// Elevation in meters of world Y and the color there; colors between stops are blended.
+	pub stops: Vec<(f32, Color)>,
+}
+
+impl Default for HypsometricTint {
+	fn default() -> Self {
+		Self {
+			enabled: false,
+			opacity: 0.8,
+			stops: vec![
+				(0.0, Color::srgb(0.2, 0.45, 0.25)),
+				(100.0, Color::srgb(0.55, 0.65, 0.35)),
+				(200.0, Color::srgb(0.8, 0.7, 0.45)),
+				(350.0, Color::srgb(0.55, 0.4, 0.3)),
+				(500.0, Color::srgb(0.95, 0.95, 0.95)),
+			],
+		}
+	}
+}
+
+impl HypsometricTint {
+	/// Stops for the shader uniform, lowest first: linear RGB in `xyz`, elevation in `w`. Only the
+	/// lowest [`MAX_TINT_STOPS`] fit; the rest of the array is padding.
+	pub fn packed_stops(&self) -> (u32, [Vec4; MAX_TINT_STOPS]) {
+		let mut stops = self.stops.clone();
+		stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+		stops.truncate(MAX_TINT_STOPS);
+		let mut packed = [Vec4::ZERO; MAX_TINT_STOPS];
+		for (slot, (elevation, color)) in packed.iter_mut().zip(&stops) {
+			let linear = color.to_linear();
+			*slot = Vec4::new(linear.red, linear.green, linear.blue, *elevation);
+		}
+		(stops.len() as u32, packed)
+	}
+}
+
+pub(super) fn hypsometric_ui(ui: &mut egui::Ui, tint: &mut HypsometricTint, units: Units) {
+	ui.checkbox(&mut tint.enabled, "Tint by elevation");
+	ui.add_enabled_ui(tint.enabled, |ui| {
+		ui.horizontal(|ui| {
+			ui.label("Opacity:");
+			ui.add(egui::Slider::new(&mut tint.opacity, 0.0..=1.0));
+		});
+		let mut removed = None;
+		for (index, (elevation, color)) in tint.stops.iter_mut().enumerate() {
+			ui.horizontal(|ui| {
+				ui.add(units.length_drag_value(elevation).speed(1.0));
+				let mut rgb = color.to_srgba().to_f32_array_no_alpha();
+				if ui.color_edit_button_rgb(&mut rgb).changed() {
+					*color = Color::srgb(rgb[0], rgb[1], rgb[2]);
+				}
+				if ui.small_button("✖").clicked() {
+					removed = Some(index);
+				}
+			});
+		}
+		if let Some(index) = removed {
+			tint.stops.remove(index);
+		}
+		if tint.stops.len() < MAX_TINT_STOPS && ui.button("Add stop").clicked() {
+			let (elevation, color) = tint
+				.stops
+				.iter()
+				.max_by(|a, b| a.0.total_cmp(&b.0))
+				.map_or((0.0, Color::WHITE), |(elevation, color)| {
+					(elevation + 100.0, *color)
+				});
+			tint.stops.push((elevation, color));
+		}
+	});
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn stops_are_packed_lowest_first_and_capped() {
+		let tint = HypsometricTint {
+			stops: (0..10)
+				.rev()
+				.map(|i| (i as f32 * 10.0, Color::WHITE))
+				.collect(),
+			..Default::default()
+		};
+		let (count, packed) = tint.packed_stops();
+		assert_eq!(count, MAX_TINT_STOPS as u32);
+		let elevations: Vec<f32> = packed.iter().map(|stop| stop.w).collect();
+		assert_eq!(elevations, [0.0, 10.0, 20.0, 30.0, 40.0, 50.0, 60.0, 70.0]);
+		assert_eq!(packed[0].truncate(), Vec3::ONE);
+	}
+}
@@ -0,0 +1,171 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::f32::consts::SQRT_2;
+
+use bevy::prelude::*;
+
+use super::HeightMap;
+
+/// Which terrain feature a traced path should follow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceKind {
+	Valley,
+	Ridge,
+}
+
+/// Added to every cell so flat ground still favours the shorter route.
+const BASE_CELL_COST: f32 = 0.05;
+
+const NEIGHBOURS: [(i32, i32, f32); 8] = [
+	(-1, 0, 1.0),
+	(1, 0, 1.0),
+	(0, -1, 1.0),
+	(0, 1, 1.0),
+	(-1, -1, SQRT_2),
+	(1, -1, SQRT_2),
+	(-1, 1, SQRT_2),
+	(1, 1, SQRT_2),
+];
+
+impl TraceKind {
+	pub const fn label(self) -> &'static str {
+		match self {
+			Self::Valley => "Valley",
+			Self::Ridge => "Ridge",
+		}
+	}
+
+	/// Cost of passing through a cell with normalized `height`; squared so the extremes dominate.
+	fn cell_cost(self, height: f32) -> f32 {
+		let distance_from_feature = match self {
+			Self::Valley => height,
+			Self::Ridge => 1.0 - height,
+		}
+		.clamp(0.0, 1.0);
+		distance_from_feature.mul_add(distance_from_feature, BASE_CELL_COST)
+	}
+}
+
+#[derive(PartialEq)]
+struct Candidate {
+	cost: f32,
+	index: usize,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+	// Reversed so the binary heap pops the cheapest candidate first
+	fn cmp(&self, other: &Self) -> Ordering {
+		other
+			.cost
+			.total_cmp(&self.cost)
+			.then_with(|| other.index.cmp(&self.index))
+	}
+}
+
+impl PartialOrd for Candidate {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+/// Cheapest 8-connected route between two grid vertices, following low (valley) or high (ridge)
+/// ground. Returns the visited grid coordinates from `start` to `end`, inclusive.
+pub fn least_cost_path(
+	height_map: &HeightMap,
+	start: UVec2,
+	end: UVec2,
+	kind: TraceKind,
+) -> Option<Vec<UVec2>> {
+	let width = height_map.length_x + 1;
+	let depth = height_map.length_z() + 1;
+	if start.x >= width || start.y >= depth || end.x >= width || end.y >= depth {
+		return None;
+	}
+	let index_of = |cell: UVec2| (cell.y * width + cell.x) as usize;
+	let cell_of = |index: usize| UVec2::new(index as u32 % width, index as u32 / width);
+
+	let mut costs = vec![f32::INFINITY; height_map.heights.len()];
+	let mut previous = vec![None; height_map.heights.len()];
+	let mut queue = BinaryHeap::new();
+	costs[index_of(start)] = 0.0;
+	queue.push(Candidate {
+		cost: 0.0,
+		index: index_of(start),
+	});
+
+	let goal = index_of(end);
+	while let Some(Candidate { cost, index }) = queue.pop() {
+		if index == goal {
+			break;
+		}
+		if cost > costs[index] {
+			continue;
+		}
+		let cell = cell_of(index);
+		let here = kind.cell_cost(height_map.heights[index]);
+		for (dx, dz, step) in NEIGHBOURS {
+			let Some(x) = cell.x.checked_add_signed(dx).filter(|x| *x < width) else {
+				continue;
+			};
+			let Some(z) = cell.y.checked_add_signed(dz).filter(|z| *z < depth) else {
+				continue;
+			};
+			let next = index_of(UVec2::new(x, z));
+			let there = kind.cell_cost(height_map.heights[next]);
+			let next_cost = cost + step * 0.5 * (here + there);
+			if next_cost < costs[next] {
+				costs[next] = next_cost;
+				previous[next] = Some(index);
+				queue.push(Candidate {
+					cost: next_cost,
+					index: next,
+				});
+			}
+		}
+	}
+
+	if !costs[goal].is_finite() {
+		return None;
+	}
+	let mut path = vec![end];
+	let mut index = goal;
+	while let Some(prev) = previous[index] {
+		path.push(cell_of(prev));
+		index = prev;
+	}
+	path.reverse();
+	Some(path)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn valley_path_runs_through_the_low_cells() {
+		// 10x10 cells with a straight valley along x = 5
+		let length = 10;
+		let heights = (0..=length)
+			.flat_map(|_| (0..=length).map(|x: i32| (x - 5).abs() as f32 / 5.0))
+			.collect();
+		let height_map = HeightMap {
+			length_x: length as u32,
+			heights,
+		};
+
+		let path = least_cost_path(
+			&height_map,
+			UVec2::new(2, 0),
+			UVec2::new(8, 10),
+			TraceKind::Valley,
+		)
+		.unwrap();
+		assert_eq!(path.first(), Some(&UVec2::new(2, 0)));
+		assert_eq!(path.last(), Some(&UVec2::new(8, 10)));
+		for cell in path.iter().filter(|cell| (3..=7).contains(&cell.y)) {
+			assert_eq!(cell.x, 5, "path left the valley at {cell:?}: {path:?}");
+		}
+	}
+}
@@ -0,0 +1,121 @@
+use bevy::prelude::*;
+use bevy_egui::egui;
+
+use super::Settings;
+use crate::camera::PrimaryCamera3d;
+
+/// Coarsest stride through the height map; the mesh keeps at least every 4th vertex.
+pub const MAX_LOD_STEP: u32 = 4;
+/// Grid cells drawn smaller than this on screen are merged with their neighbors.
+const MIN_CELL_PIXELS: f32 = 3.0;
+/// How long the camera has to settle on a new level before the mesh is rebuilt.
+const LOD_DEBOUNCE_SECS: f32 = 0.4;
+
+/// Which stride the terrain mesh is built with. The height map always stays at full
+/// resolution, so raycasts and pins are unaffected.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct TerrainLod {
+	pub enabled: bool,
+	/// Every `step`th height map vertex ends up in the mesh; 1 is full resolution.
+	pub step: u32,
+}
+
+impl Default for TerrainLod {
+	fn default() -> Self {
+		Self {
+			enabled: true,
+			step: 1,
+		}
+	}
+}
+
+/// Largest power-of-two stride, up to [`MAX_LOD_STEP`], that still leaves each merged cell
+/// at least [`MIN_CELL_PIXELS`] across.
+pub fn lod_step(cell_pixels: f32) -> u32 {
+	let mut step = 1;
+	while step < MAX_LOD_STEP && (step * 2) as f32 * cell_pixels <= MIN_CELL_PIXELS {
+		step *= 2;
+	}
+	step
+}
+
+/// Width of the world visible around the terrain, or `None` for projections this doesn't know.
+fn visible_width(
+	projection: &Projection,
+	camera: &GlobalTransform,
+	settings: &Settings,
+) -> Option<f32> {
+	match projection {
+		Projection::Perspective(perspective) => {
+			// Measured where the view is sharpest: the terrain point nearest the camera
+			let half = settings.world_bounds().1.xz();
+			let position = camera.translation();
+			let nearest = Vec2::new(position.x, position.z).clamp(-half, half);
+			let distance = position.distance(Vec3::new(nearest.x, 0.0, nearest.y));
+			Some(2.0 * distance * (perspective.fov / 2.0).tan() * perspective.aspect_ratio)
+		}
+		Projection::Orthographic(orthographic) => Some(orthographic.area.width()),
+		Projection::Custom(_) => None,
+	}
+}
+
+pub(super) fn choose_terrain_lod(
+	time: Res<Time>,
+	mut lod: ResMut<TerrainLod>,
+	settings: Res<Settings>,
+	camera: Query<(&Camera, &GlobalTransform, &Projection), With<PrimaryCamera3d>>,
+	mut pending: Local<Option<(u32, Timer)>>,
+) {
+	let desired = if lod.enabled {
+		let Ok((camera, transform, projection)) = camera.single() else {
+			return;
+		};
+		let (Some(viewport), Some(width)) = (
+			camera.logical_viewport_size(),
+			visible_width(projection, transform, &settings),
+		) else {
+			return;
+		};
+		let cell_size = settings.world_x() / settings.grid_x().max(1) as f32;
+		lod_step(cell_size * viewport.x / width.max(f32::EPSILON))
+	} else {
+		1
+	};
+
+	if desired == lod.step {
+		*pending = None;
+		return;
+	}
+	// Restart the wait whenever the target level changes, e.g. mid-tween
+	if pending.as_ref().is_none_or(|(step, _)| *step != desired) {
+		*pending = Some((
+			desired,
+			Timer::from_seconds(LOD_DEBOUNCE_SECS, TimerMode::Once),
+		));
+	}
+	if let Some((_, timer)) = pending.as_mut()
+		&& timer.tick(time.delta()).just_finished()
+	{
+		lod.step = desired;
+		*pending = None;
+	}
+}
+
+pub(super) fn lod_ui(ui: &mut egui::Ui, lod: &mut TerrainLod) {
+	ui.checkbox(&mut lod.enabled, "Coarser mesh when zoomed out");
+	ui.label(format!("Mesh stride: {} (1 is full resolution)", lod.step));
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn lod_step_coarsens_only_tiny_cells() {
+		assert_eq!(lod_step(10.0), 1);
+		assert_eq!(lod_step(MIN_CELL_PIXELS), 1);
+		assert_eq!(lod_step(MIN_CELL_PIXELS / 2.0), 2);
+		assert_eq!(lod_step(MIN_CELL_PIXELS / 4.0), 4);
+		assert_eq!(lod_step(0.01), MAX_LOD_STEP);
+	}
+}
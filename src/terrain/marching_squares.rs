@@ -0,0 +1,288 @@
+use alignment_path::GeometryTolerances;
+use bevy::prelude::*;
+use rayon::prelude::*;
+
+use super::{HeightMap, Settings, grid_to_world, world_size_for_height};
+
+/// Upper bound on contour levels, so a tiny interval can't stall the frame.
+pub const MAX_CONTOUR_LEVELS: usize = 512;
+
+/// One straight piece of a contour line, in world space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineSegment {
+	pub start: Vec3,
+	pub end: Vec3,
+}
+
+/// Every segment of one contour level.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContourLines {
+	pub elevation: f32,
+	/// Index contour (every `every_nth` level counted from zero elevation), drawn bolder.
+	pub is_major: bool,
+	pub segments: Vec<LineSegment>,
+}
+
+/// Whether contour `level` (elevation divided by the interval) is a major one. `every_nth` of 0
+/// makes every level minor.
+pub fn is_major_level(level: i64, every_nth: u32) -> bool {
+	every_nth > 0 && level.rem_euclid(i64::from(every_nth)) == 0
+}
+
+/// Cell corners in marching-squares order: bottom-left, bottom-right, top-right, top-left.
+const CORNERS: [(u32, u32); 4] = [(0, 0), (1, 0), (1, 1), (0, 1)];
+
+/// Trace contours every `interval` meters of world elevation with marching squares, marking every
+/// `every_nth` level as major. Levels are traced in parallel; the result is in elevation order
+/// either way.
+pub fn generate_contour_lines(
+	height_map: &HeightMap,
+	settings: &Settings,
+	interval: f32,
+	every_nth: u32,
+	tolerances: &GeometryTolerances,
+) -> Vec<ContourLines> {
+	if interval <= 0.0 || height_map.heights.is_empty() {
+		return Vec::new();
+	}
+	let scale = world_size_for_height(settings) * settings.height_multiplier;
+	let (min, max) = height_map
+		.heights
+		.iter()
+		.fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), h| {
+			(min.min(*h), max.max(*h))
+		});
+	let (min, max) = (min * scale, max * scale);
+	let first_level = (min / interval).ceil() as i64;
+	let last_level = (max / interval).floor() as i64;
+	if last_level < first_level {
+		return Vec::new();
+	}
+	let last_level = last_level.min(first_level + MAX_CONTOUR_LEVELS as i64 - 1);
+
+	(first_level..=last_level)
+		.into_par_iter()
+		.map(|level| {
+			let elevation = level as f32 * interval;
+			ContourLines {
+				elevation,
+				is_major: is_major_level(level, every_nth),
+				segments: level_segments(height_map, settings, scale, elevation, tolerances),
+			}
+		})
+		.filter(|lines| !lines.segments.is_empty())
+		.collect()
+}
+
+fn level_segments(
+	height_map: &HeightMap,
+	settings: &Settings,
+	scale: f32,
+	elevation: f32,
+	tolerances: &GeometryTolerances,
+) -> Vec<LineSegment> {
+	let mut segments = Vec::new();
+	for z in 0..height_map.length_z() {
+		for x in 0..height_map.length_x {
+			let corners = CORNERS.map(|(dx, dz)| {
+				let (cx, cz) = (x + dx, z + dz);
+				let position = grid_to_world(cx, cz, settings);
+				Vec3::new(position.x, height_map.get(cx, cz) * scale, position.z)
+			});
+			cell_segments(corners, elevation, tolerances, &mut segments);
+		}
+	}
+	segments
+}
+
+/// Point on the edge between corners `a` and `b` where the surface crosses `elevation`. Nearly
+/// level edges are split in the middle rather than divided by a vanishing height difference.
+fn edge_crossing(a: Vec3, b: Vec3, elevation: f32, tolerances: &GeometryTolerances) -> Vec3 {
+	let rise = b.y - a.y;
+	let t = if tolerances.is_negligible(rise) {
+		0.5
+	} else {
+		((elevation - a.y) / rise).clamp(0.0, 1.0)
+	};
+	let mut point = a.lerp(b, t);
+	point.y = elevation;
+	point
+}
+
+fn cell_segments(
+	corners: [Vec3; 4],
+	elevation: f32,
+	tolerances: &GeometryTolerances,
+	segments: &mut Vec<LineSegment>,
+) {
+	let case = corners
+		.iter()
+		.enumerate()
+		.filter(|(_, corner)| corner.y >= elevation)
+		.fold(0u8, |case, (i, _)| case | (1 << i));
+	// Edge i runs from corner i to corner i + 1
+	let edge = |i: usize| edge_crossing(corners[i], corners[(i + 1) % 4], elevation, tolerances);
+	let mut connect = |a: usize, b: usize| {
+		segments.push(LineSegment {
+			start: edge(a),
+			end: edge(b),
+		});
+	};
+	match case {
+		0 | 15 => {}
+		1 | 14 => connect(3, 0),
+		2 | 13 => connect(0, 1),
+		3 | 12 => connect(3, 1),
+		4 | 11 => connect(1, 2),
+		6 | 9 => connect(0, 2),
+		7 | 8 => connect(2, 3),
+		// Saddles: the cell centre decides which diagonal pair is connected
+		5 | 10 => {
+			let centre = corners.iter().map(|corner| corner.y).sum::<f32>() / 4.0;
+			if (centre >= elevation) == (case == 5) {
+				connect(0, 1);
+				connect(2, 3);
+			} else {
+				connect(3, 0);
+				connect(1, 2);
+			}
+		}
+		_ => unreachable!("marching squares case out of range"),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// What the overlay shader computes per fragment: the contour level nearest `world_height` and
+	/// how far it is as a share of `interval`, 0 on the line and 0.5 midway between two.
+	fn nearest_contour_level(world_height: f32, interval: f32) -> (i64, f32) {
+		let t = world_height / interval;
+		// WGSL's fract floors, unlike f32::fract, which truncates below zero
+		let fraction = t - t.floor();
+		(t.round() as i64, fraction.min(1.0 - fraction))
+	}
+
+	fn cone() -> (HeightMap, Settings) {
+		let settings = Settings {
+			base_grid_resolution: 20,
+			aspect_x: 1,
+			aspect_z: 1,
+			height_multiplier: 1.0,
+			..Settings::default()
+		};
+		let length = settings.grid_x();
+		let centre = length as f32 / 2.0;
+		let heights = (0..=length)
+			.flat_map(|z| {
+				(0..=length).map(move |x| {
+					let r = Vec2::new(x as f32 - centre, z as f32 - centre).length();
+					(1.0 - r / centre).max(0.0)
+				})
+			})
+			.collect();
+		let height_map = HeightMap {
+			length_x: length,
+			heights,
+		};
+		(height_map, settings)
+	}
+
+	#[test]
+	fn cone_contours_are_closed_rings_at_the_right_elevation() {
+		let (height_map, settings) = cone();
+		let peak = world_size_for_height(&settings);
+
+		// Chosen so no level passes exactly through a grid vertex
+		let contours = generate_contour_lines(
+			&height_map,
+			&settings,
+			peak * 0.33,
+			0,
+			&GeometryTolerances::DEFAULT,
+		);
+		assert_eq!(contours.len(), 3);
+		for contour in &contours {
+			assert!(contour.elevation > 0.0 && contour.elevation <= peak);
+			for segment in &contour.segments {
+				assert_eq!(segment.start.y, contour.elevation);
+				assert_eq!(segment.end.y, contour.elevation);
+			}
+			// A closed ring uses every endpoint exactly twice
+			let endpoints: Vec<Vec3> = contour
+				.segments
+				.iter()
+				.flat_map(|segment| [segment.start, segment.end])
+				.collect();
+			for point in &endpoints {
+				let uses = endpoints
+					.iter()
+					.filter(|other| other.distance(*point) < 1e-3)
+					.count();
+				assert_eq!(uses, 2, "open contour end at {point}");
+			}
+		}
+	}
+
+	#[test]
+	fn every_nth_level_is_major_including_below_zero() {
+		let majors: Vec<i64> = (-10..=10)
+			.filter(|level| is_major_level(*level, 5))
+			.collect();
+		assert_eq!(majors, [-10, -5, 0, 5, 10]);
+		assert!((-10..=10).all(|level| !is_major_level(level, 0)));
+	}
+
+	#[test]
+	fn parallel_levels_match_serial_tracing() {
+		let (height_map, settings) = cone();
+		let scale = world_size_for_height(&settings) * settings.height_multiplier;
+		// Stays clear of the peak, so both sides agree on the top level
+		let interval = scale * 0.0097;
+		let tolerances = GeometryTolerances::DEFAULT;
+
+		let serial: Vec<ContourLines> = (0..=103)
+			.map(|level| {
+				let elevation = level as f32 * interval;
+				ContourLines {
+					elevation,
+					is_major: is_major_level(level, 5),
+					segments: level_segments(&height_map, &settings, scale, elevation, &tolerances),
+				}
+			})
+			.filter(|lines| !lines.segments.is_empty())
+			.collect();
+		let parallel = generate_contour_lines(&height_map, &settings, interval, 5, &tolerances);
+		assert!(parallel.len() > 90);
+		assert_eq!(parallel, serial);
+	}
+
+	#[test]
+	fn traced_crossings_sit_on_the_shader_lines() {
+		let (height_map, settings) = cone();
+		let interval = world_size_for_height(&settings) * 0.33;
+		let contours = generate_contour_lines(
+			&height_map,
+			&settings,
+			interval,
+			2,
+			&GeometryTolerances::DEFAULT,
+		);
+		assert!(!contours.is_empty());
+		for contour in &contours {
+			for point in contour.segments.iter().flat_map(|s| [s.start, s.end]) {
+				let (level, distance) = nearest_contour_level(point.y, interval);
+				assert!(
+					distance < 1e-4,
+					"crossing at {point} is off the shader line"
+				);
+				assert_eq!(is_major_level(level, 2), contour.is_major);
+			}
+		}
+		// Halfway between levels is as far from a line as the shader gets
+		let (level, distance) = nearest_contour_level(-1.5 * interval, interval);
+		assert_eq!(level, -2);
+		assert!((distance - 0.5).abs() < 1e-6);
+	}
+}
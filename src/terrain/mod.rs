@@ -1,23 +1,47 @@
 use bevy::{
 	asset::RenderAssetUsages,
+	image::{CompressedImageFormats, ImageSampler, ImageType},
 	mesh::{Indices, PrimitiveTopology},
 	prelude::*,
 	render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+	tasks::{AsyncComputeTaskPool, Task, block_on, futures_lite::future},
 };
+use std::path::{Path, PathBuf};
 
+pub mod least_cost;
+pub mod marching_squares;
 pub mod spatial;
 
 // Re-export commonly used spatial helpers at the crate root
-pub use spatial::{calculate_terrain_height, grid_to_world, world_size_for_height};
+pub use spatial::{
+	calculate_terrain_height, clamp_to_terrain_bounds, grid_to_world, line_of_sight,
+	world_size_for_height, world_to_grid,
+};
 
 use bevy_egui::{EguiContexts, egui};
-use noise::{HybridMulti, MultiFractal, NoiseFn, OpenSimplex};
+use noise::{Billow, Fbm, HybridMulti, MultiFractal, NoiseFn, OpenSimplex, RidgedMulti};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 
+mod background_grid;
+mod bounds;
+mod buildability;
 mod contour_lines;
+mod contour_ramp;
+mod erosion;
+mod hypsometric;
+mod lod;
+mod secondary_layer;
+mod water;
+use crate::saveable::{FileDialogPlugin, PendingFileDialog, SaveableSettings};
+use crate::terrain::contour_lines::ContourLinePlugin;
 use crate::ui_shell::{ActivePanel, UiShellState};
-use crate::{saveable::SaveableSettings, terrain::contour_lines::ContourLinePlugin};
+use crate::units::Units;
+use background_grid::BackgroundGridSettings;
 pub use contour_lines::ContourState;
+pub use erosion::apply_thermal_erosion;
+pub use lod::TerrainLod;
+pub use secondary_layer::SecondaryLayerSettings;
 
 /// Public plugin to generate and visualize terrain. Self-contained with no external app deps.
 pub struct TerrainPlugin;
@@ -30,13 +54,77 @@ impl Plugin for TerrainPlugin {
 	fn build(&self, app: &mut App) {
 		app
 			.add_plugins(ContourLinePlugin)
+			.add_plugins(FileDialogPlugin::<Settings>::default())
+			.add_plugins(background_grid::BackgroundGridPlugin)
+			.add_plugins(bounds::TerrainBoundsPlugin)
+			.add_plugins(secondary_layer::SecondaryLayerPlugin)
+			.add_plugins(water::WaterPlugin)
 			.insert_resource(Settings::load_or_default())
+			.insert_resource(SurfaceSettings::load_or_default())
 			.add_systems(Startup, setup_terrain)
-			.add_systems(Update, update_terrain.in_set(TerrainUpdateSet))
+			.init_resource::<DeferredMeshRemovals>()
+			.init_resource::<TerrainLod>()
+			.add_systems(
+				Update,
+				(
+					lod::choose_terrain_lod.before(TerrainUpdateSet),
+					update_terrain.in_set(TerrainUpdateSet),
+					update_terrain_material.run_if(resource_changed::<SurfaceSettings>),
+					remove_deferred_meshes.after(TerrainUpdateSet),
+				),
+			)
 			.add_systems(bevy_egui::EguiPrimaryContextPass, ui_system);
 	}
 }
 
+/// Replaced terrain meshes are kept alive here until their replacement has had time to upload,
+/// so the terrain never renders without a mesh for a frame.
+#[derive(Resource, Debug)]
+pub struct DeferredMeshRemovals {
+	/// Frames an old mesh outlives its replacement; 0 removes it in the same frame.
+	pub delay_frames: u32,
+	pending: Vec<(Handle<Mesh>, u32)>,
+}
+
+impl Default for DeferredMeshRemovals {
+	fn default() -> Self {
+		Self {
+			delay_frames: 1,
+			pending: Vec::new(),
+		}
+	}
+}
+
+impl DeferredMeshRemovals {
+	/// Hold on to a replaced mesh, so it isn't freed along with the last entity using it.
+	pub fn defer(&mut self, mesh: Handle<Mesh>) {
+		self.pending.push((mesh, 0));
+	}
+
+	/// Age every pending mesh by a frame and return the ones that are now safe to drop.
+	fn take_expired(&mut self) -> Vec<Handle<Mesh>> {
+		let delay_frames = self.delay_frames;
+		let mut expired = Vec::new();
+		self.pending.retain_mut(|(mesh, age)| {
+			*age += 1;
+			let keep = *age <= delay_frames;
+			if !keep {
+				expired.push(mesh.clone());
+			}
+			keep
+		});
+		expired
+	}
+}
+
+fn remove_deferred_meshes(mut removals: ResMut<DeferredMeshRemovals>) {
+	if removals.pending.is_empty() {
+		return;
+	}
+	// Dropping the last strong handle frees the mesh
+	drop(removals.take_expired());
+}
+
 /// Terrain configuration/settings used by the generator.
 #[derive(Resource, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Settings {
@@ -46,8 +134,17 @@ pub struct Settings {
 	pub aspect_z: u32,
 	pub base_world_size: f32,
 	pub height_multiplier: f32,
+	/// Cells along the longer side of the noise preview texture, sampled independently of the
+	/// mesh grid.
+	#[serde(default = "default_texture_resolution")]
+	pub texture_resolution: u32,
+
+	#[serde(default)]
+	pub source: TerrainSource,
 
 	// Noise settings
+	#[serde(default)]
+	pub noise_kind: NoiseKind,
 	pub seed: u32,
 	pub offset_x: f32,
 	pub offset_z: f32,
@@ -57,6 +154,65 @@ pub struct Settings {
 	pub lacunarity: f64,
 	pub valley_exponent: f32,
 	pub height_roughness: f64,
+	/// Debug: keep the raw noise output, skipping normalization and the valley exponent.
+	#[serde(default)]
+	pub raw_noise: bool,
+
+	/// Thermal erosion passes applied after generation; 0 leaves the terrain untouched.
+	#[serde(default)]
+	pub thermal_erosion_iterations: u32,
+	/// Repose angle in degrees that thermal erosion knocks steeper slopes down to.
+	#[serde(default = "default_talus_angle_deg")]
+	pub talus_angle_deg: f32,
+
+	/// Normalized (0-1, like the height map) elevation of the water plane; 0 hides it.
+	#[serde(default)]
+	pub water_level: f32,
+}
+
+const fn default_talus_angle_deg() -> f32 {
+	erosion::DEFAULT_TALUS_ANGLE_DEG
+}
+
+const fn default_texture_resolution() -> u32 {
+	512
+}
+
+/// Where terrain heights come from.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TerrainSource {
+	#[default]
+	Noise,
+	/// Grayscale (8 or 16 bit) image stretched over the terrain, e.g. an exported DEM.
+	Image(PathBuf),
+}
+
+/// Fractal noise generator used for the height map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum NoiseKind {
+	#[default]
+	HybridMulti,
+	Fbm,
+	RidgedMulti,
+	Billow,
+}
+
+impl NoiseKind {
+	pub const ALL: [Self; 4] = [
+		Self::HybridMulti,
+		Self::Fbm,
+		Self::RidgedMulti,
+		Self::Billow,
+	];
+
+	pub const fn label(self) -> &'static str {
+		match self {
+			Self::HybridMulti => "Hybrid multifractal",
+			Self::Fbm => "fBm",
+			Self::RidgedMulti => "Ridged multifractal",
+			Self::Billow => "Billow",
+		}
+	}
 }
 
 impl Default for Settings {
@@ -68,8 +224,12 @@ impl Default for Settings {
 			aspect_z: 1,
 			base_world_size: 1000.0,
 			height_multiplier: 0.5,
+			texture_resolution: default_texture_resolution(),
+
+			source: TerrainSource::Noise,
 
 			// Noise defaults
+			noise_kind: NoiseKind::HybridMulti,
 			seed: 0,
 			offset_x: 0.0,
 			offset_z: 0.0,
@@ -79,6 +239,10 @@ impl Default for Settings {
 			lacunarity: 2.3,
 			valley_exponent: 10.5,
 			height_roughness: 1.9,
+			raw_noise: false,
+			thermal_erosion_iterations: 0,
+			talus_angle_deg: erosion::DEFAULT_TALUS_ANGLE_DEG,
+			water_level: 0.0,
 		}
 	}
 }
@@ -99,14 +263,81 @@ impl Settings {
 	pub fn world_z(&self) -> f32 {
 		self.base_world_size * self.aspect_z as f32
 	}
+
+	/// Min and max corners of the box the terrain fits in: centered on the origin in X/Z, from
+	/// zero up to the height a fully raised cell reaches.
+	pub fn world_bounds(&self) -> (Vec3, Vec3) {
+		let half = Vec3::new(self.world_x(), 0.0, self.world_z()) / 2.0;
+		let max_height = world_size_for_height(self) * self.height_multiplier;
+		(-half, half.with_y(max_height))
+	}
+
+	/// These settings without the display-only water level, for telling whether the terrain itself
+	/// needs regenerating.
+	fn terrain_inputs(&self) -> Self {
+		Self {
+			water_level: Self::default().water_level,
+			..self.clone()
+		}
+	}
+
+	/// Whether everything that differs from `previous` is a one-off pick (seed, noise kind,
+	/// source, raw noise) rather than something dragged along a slider.
+	fn differs_only_discretely(&self, previous: &Self) -> bool {
+		let with_previous_picks = Self {
+			seed: previous.seed,
+			noise_kind: previous.noise_kind,
+			source: previous.source.clone(),
+			raw_noise: previous.raw_noise,
+			..self.clone()
+		};
+		with_previous_picks == *previous
+	}
 }
 
+/// How long slider-driven settings must stay put before the terrain is rebuilt.
+const REGENERATE_DEBOUNCE_SECS: f32 = 0.15;
+
 impl SaveableSettings for Settings {
 	fn filename() -> &'static str {
 		"terrain_settings.json"
 	}
 }
 
+/// Look of the terrain material. Kept out of [`Settings`] so restyling never regenerates the
+/// terrain or anything traced from it.
+#[derive(Resource, Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SurfaceSettings {
+	pub base_color: Color,
+	pub metallic: f32,
+	pub perceptual_roughness: f32,
+}
+
+impl Default for SurfaceSettings {
+	fn default() -> Self {
+		Self {
+			base_color: Color::srgb(0.3, 0.5, 0.3),
+			metallic: 0.0,
+			perceptual_roughness: 0.5,
+		}
+	}
+}
+
+impl SurfaceSettings {
+	fn apply_to_material(&self, material: &mut StandardMaterial) {
+		material.base_color = self.base_color;
+		material.metallic = self.metallic;
+		material.perceptual_roughness = self.perceptual_roughness;
+	}
+}
+
+impl SaveableSettings for SurfaceSettings {
+	fn filename() -> &'static str {
+		"terrain_surface.json"
+	}
+}
+
 /// Marker for the spawned terrain entity.
 #[derive(Component)]
 pub struct TerrainMesh;
@@ -119,8 +350,55 @@ pub struct HeightMap {
 }
 
 impl HeightMap {
+	/// Resamples a grayscale image to `length_x` cells across, keeping the image's aspect ratio.
+	/// The terrain itself resamples to its configured grid, so only callers outside it use this.
+	#[allow(dead_code)]
+	pub fn from_image(image: &Image, length_x: u32) -> Self {
+		let aspect = image.height().saturating_sub(1).max(1) as f32
+			/ image.width().saturating_sub(1).max(1) as f32;
+		let length_z = ((length_x as f32 * aspect).round() as u32).max(1);
+		Self::from_image_sized(image, length_x, length_z)
+	}
+
+	/// Bilinearly resamples a grayscale image to the given grid and normalizes it to 0..1.
+	pub fn from_image_sized(image: &Image, length_x: u32, length_z: u32) -> Self {
+		let max_px = image.width().saturating_sub(1) as f32;
+		let max_py = image.height().saturating_sub(1) as f32;
+		let mut heights = Vec::with_capacity(((length_x + 1) * (length_z + 1)) as usize);
+		for z in 0..=length_z {
+			for x in 0..=length_x {
+				let px = x as f32 / length_x.max(1) as f32 * max_px;
+				let py = z as f32 / length_z.max(1) as f32 * max_py;
+				heights.push(sample_image_bilinear(image, px, py));
+			}
+		}
+
+		let (min_height, max_height) = heights.iter().fold(
+			(f32::INFINITY, f32::NEG_INFINITY),
+			|(min_height, max_height), &height| (min_height.min(height), max_height.max(height)),
+		);
+		let height_range = max_height - min_height;
+		for height in &mut heights {
+			// A perfectly flat image stays flat instead of dividing by zero.
+			*height = if height_range > 0.0 {
+				((*height - min_height) / height_range).clamp(0.0, 1.0)
+			} else {
+				0.0
+			};
+		}
+
+		Self { length_x, heights }
+	}
+
+	/// Number of cells along Z, derived from the sample count.
+	pub fn length_z(&self) -> u32 {
+		(self.heights.len() / (self.length_x as usize + 1)).saturating_sub(1) as u32
+	}
+
 	pub fn get(&self, x: u32, z: u32) -> f32 {
-		// Rectangular terrain: index = z * (length_x + 1) + x
+		// Rectangular terrain: index = z * (length_x + 1) + x. An x past the row would silently
+		// read the next row, shearing everything built on it.
+		debug_assert!(x <= self.length_x, "x {x} outside 0..={}", self.length_x);
 		let index = (z * (self.length_x + 1) + x) as usize;
 		*self
 			.heights
@@ -128,7 +406,9 @@ impl HeightMap {
 			.expect("Index out of bounds in HeightMap::get")
 	}
 
+	#[cfg(test)]
 	fn set(&mut self, x: u32, z: u32, height: f32) {
+		debug_assert!(x <= self.length_x, "x {x} outside 0..={}", self.length_x);
 		let index = (z * (self.length_x + 1) + x) as usize;
 		*self
 			.heights
@@ -137,6 +417,72 @@ impl HeightMap {
 	}
 }
 
+fn sample_image_bilinear(image: &Image, px: f32, py: f32) -> f32 {
+	let max_x = image.width().saturating_sub(1);
+	let max_y = image.height().saturating_sub(1);
+	let x0 = (px.floor() as u32).min(max_x);
+	let y0 = (py.floor() as u32).min(max_y);
+	let x1 = (x0 + 1).min(max_x);
+	let y1 = (y0 + 1).min(max_y);
+	let tx = px - x0 as f32;
+	let ty = py - y0 as f32;
+
+	let lerp = |a: f32, b: f32, t: f32| (b - a).mul_add(t, a);
+	let top = lerp(
+		image_pixel_value(image, x0, y0),
+		image_pixel_value(image, x1, y0),
+		tx,
+	);
+	let bottom = lerp(
+		image_pixel_value(image, x0, y1),
+		image_pixel_value(image, x1, y1),
+		tx,
+	);
+	lerp(top, bottom, ty)
+}
+
+/// Raw grayscale value of a pixel in 0..1. Color images use their red channel.
+fn image_pixel_value(image: &Image, x: u32, y: u32) -> f32 {
+	let index = (y * image.width() + x) as usize;
+	let data = image.data.as_deref().unwrap_or_default();
+	let read_u8 = |offset: usize| {
+		data
+			.get(offset)
+			.map_or(0.0, |&v| f32::from(v) / f32::from(u8::MAX))
+	};
+	let read_u16 = |offset: usize| {
+		data.get(offset..offset + 2).map_or(0.0, |b| {
+			f32::from(u16::from_le_bytes([b[0], b[1]])) / f32::from(u16::MAX)
+		})
+	};
+	match image.texture_descriptor.format {
+		TextureFormat::R8Unorm => read_u8(index),
+		TextureFormat::R16Unorm | TextureFormat::R16Uint => read_u16(index * 2),
+		TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => read_u8(index * 4),
+		TextureFormat::Rgba16Unorm | TextureFormat::Rgba16Uint => read_u16(index * 8),
+		_ => image
+			.get_color_at(x, y)
+			.map_or(0.0, |color| color.to_srgba().red),
+	}
+}
+
+fn load_height_image(path: &Path) -> anyhow::Result<Image> {
+	let bytes = std::fs::read(path)?;
+	let extension = path
+		.extension()
+		.and_then(|ext| ext.to_str())
+		.unwrap_or("png");
+	// Not sRGB: pixel values are elevations, not colors.
+	Ok(Image::from_buffer(
+		&bytes,
+		ImageType::Extension(extension),
+		CompressedImageFormats::NONE,
+		false,
+		ImageSampler::Default,
+		RenderAssetUsages::default(),
+	)?)
+}
+
 /// Contains computed terrain dimensions and generation methods
 struct TerrainGenerator {
 	grid_x: u32,
@@ -147,73 +493,164 @@ struct TerrainGenerator {
 	height_map: HeightMap,
 }
 
+/// Base grid resolution of the reference pass that noise is normalized against.
+const NORMALIZATION_REFERENCE_RESOLUTION: u32 = 64;
+
+/// Raw noise min/max over the reference grid, or `None` if the noise is flat. Depends on every
+/// setting except the grid resolution.
+fn normalization_range(settings: &Settings) -> Option<(f32, f32)> {
+	let reference_settings = Settings {
+		base_grid_resolution: NORMALIZATION_REFERENCE_RESOLUTION,
+		..settings.clone()
+	};
+	let (min_height, max_height) = TerrainGenerator::from_settings(&reference_settings)
+		.sample_noise(&reference_settings)
+		.into_iter()
+		.fold(
+			(f32::INFINITY, f32::NEG_INFINITY),
+			|(min_height, max_height), height| (min_height.min(height), max_height.max(height)),
+		);
+	(max_height > min_height).then_some((min_height, max_height))
+}
+
+fn normalize_height(
+	height: f32,
+	(min_height, max_height): (f32, f32),
+	valley_exponent: f32,
+) -> f32 {
+	let normalized_height = ((height - min_height) / (max_height - min_height)).clamp(0.0, 1.0);
+	normalized_height.powf(valley_exponent)
+}
+
 impl TerrainGenerator {
 	fn from_settings(settings: &Settings) -> Self {
-		let grid_x = settings.grid_x();
-		let grid_z = settings.grid_z();
-		let world_x = settings.world_x();
-		let world_z = settings.world_z();
-
+		let (grid_x, grid_z) = (settings.grid_x(), settings.grid_z());
 		let height_map = HeightMap {
 			length_x: grid_x,
 			heights: vec![0.0; ((grid_z + 1) * (grid_x + 1)) as usize],
 		};
+		Self::with_height_map(settings, height_map)
+	}
 
+	/// Generator around an already generated height map, for meshing or texturing it again.
+	fn with_height_map(settings: &Settings, height_map: HeightMap) -> Self {
 		Self {
-			grid_x,
-			grid_z,
-			world_x,
-			world_z,
+			grid_x: settings.grid_x(),
+			grid_z: settings.grid_z(),
+			world_x: settings.world_x(),
+			world_z: settings.world_z(),
 			height_multiplier: settings.height_multiplier,
 			height_map,
 		}
 	}
 
-	fn generate_height_map(&mut self, settings: &Settings) {
-		let noise = HybridMulti::<OpenSimplex>::new(settings.seed)
-			.set_octaves(settings.octaves as usize)
-			.set_frequency(settings.frequency)
-			.set_lacunarity(settings.lacunarity)
-			.set_persistence(settings.persistence);
-
-		// Values for normalization
-		let mut min_height = f32::INFINITY;
-		let mut max_height = f32::NEG_INFINITY;
-
-		for x in 0..=self.grid_x {
-			for z in 0..=self.grid_z {
-				// Use spatial utilities for world coordinate conversion
-				let world_pos = grid_to_world(x, z, settings);
-				let height = self.calculate_height_at_position(
-					f64::from(world_pos.x),
-					f64::from(world_pos.z),
-					settings,
-					&noise,
-				) as f32;
-				self.height_map.set(x, z, height);
+	/// Generator with its height map filled from `settings.source` and eroded.
+	fn populated(settings: &Settings) -> Self {
+		let mut generator = Self::sampled(settings);
+		if settings.thermal_erosion_iterations > 0 {
+			apply_thermal_erosion(
+				&mut generator.height_map,
+				settings.talus_angle_deg.to_radians(),
+				settings.thermal_erosion_iterations,
+				settings,
+			);
+		}
+		generator
+	}
 
-				min_height = min_height.min(height);
-				max_height = max_height.max(height);
-			}
+	/// Generator with its height map filled from `settings.source`, falling back to noise if the
+	/// height image can't be loaded.
+	fn sampled(settings: &Settings) -> Self {
+		let mut generator = Self::from_settings(settings);
+		match &settings.source {
+			TerrainSource::Noise => generator.generate_height_map(settings),
+			TerrainSource::Image(path) => match load_height_image(path) {
+				Ok(image) => {
+					generator.height_map =
+						HeightMap::from_image_sized(&image, generator.grid_x, generator.grid_z);
+				}
+				Err(e) => {
+					error!(
+						"Failed to load height map image {}: {}. Using noise instead.",
+						path.display(),
+						e
+					);
+					generator.generate_height_map(settings);
+				}
+			},
+		}
+		generator
+	}
+
+	/// Fills the height map with normalized noise. Heights are normalized against the range of a
+	/// fixed-resolution reference pass rather than the grid's own min/max, so the same seed gives
+	/// the same landscape at any `base_grid_resolution`, only with more detail. The tradeoff: a
+	/// finer grid can sample peaks and pits the reference missed, and those are clamped flat at
+	/// 0 or 1 instead of stretching the whole range.
+	fn generate_height_map(&mut self, settings: &Settings) {
+		let mut heights = self.sample_noise(settings);
+		if settings.raw_noise {
+			self.height_map.heights = heights;
+			return;
 		}
 
 		// Normalize all values to 0-1 range and apply valley exponent
-		let height_range = max_height - min_height;
-		assert!(
-			height_range > 0.0,
-			"Height range is zero; bug in terrain generation parameters"
-		);
+		if let Some(range) = normalization_range(settings) {
+			heights.par_iter_mut().for_each(|height| {
+				*height = normalize_height(*height, range, settings.valley_exponent);
+			});
+		} else {
+			// Constant noise output is a valid (if boring) configuration
+			warn!("Terrain noise produced no height variation; using flat terrain");
+			heights.fill(0.0);
+		}
+		self.height_map.heights = heights;
+	}
 
-		for x in 0..=self.grid_x {
-			for z in 0..=self.grid_z {
-				let height = self.height_map.get(x, z);
-				let normalized_height = (height - min_height) / height_range;
-				let final_height = normalized_height.powf(settings.valley_exponent);
-				self.height_map.set(x, z, final_height);
-			}
+	/// Raw noise at every vertex of this generator's grid.
+	fn sample_noise(&self, settings: &Settings) -> Vec<f32> {
+		let seed = settings.seed;
+		match settings.noise_kind {
+			NoiseKind::HybridMulti => self.sample_heights(
+				settings,
+				&configure_fractal(HybridMulti::<OpenSimplex>::new(seed), settings),
+			),
+			NoiseKind::Fbm => self.sample_heights(
+				settings,
+				&configure_fractal(Fbm::<OpenSimplex>::new(seed), settings),
+			),
+			NoiseKind::RidgedMulti => self.sample_heights(
+				settings,
+				&configure_fractal(RidgedMulti::<OpenSimplex>::new(seed), settings),
+			),
+			NoiseKind::Billow => self.sample_heights(
+				settings,
+				&configure_fractal(Billow::<OpenSimplex>::new(seed), settings),
+			),
 		}
 	}
 
+	fn sample_heights(&self, settings: &Settings, noise: &(impl NoiseFn<f64, 2> + Sync)) -> Vec<f32> {
+		// Each vertex is independent, so sample them in parallel into the flattened
+		// `z * (grid_x + 1) + x` layout used by `HeightMap`.
+		let row_length = self.grid_x + 1;
+		let vertex_count = (self.grid_z + 1) * row_length;
+		let mut heights = Vec::new();
+		(0..vertex_count)
+			.into_par_iter()
+			.map(|index| {
+				let world_pos = grid_to_world(index % row_length, index / row_length, settings);
+				self.calculate_height_at_position(
+					f64::from(world_pos.x),
+					f64::from(world_pos.z),
+					settings,
+					noise,
+				) as f32
+			})
+			.collect_into_vec(&mut heights);
+		heights
+	}
+
 	fn calculate_height_at_position(
 		&self,
 		x_pos: f64,
@@ -223,21 +660,45 @@ impl TerrainGenerator {
 	) -> f64 {
 		let base_world_size = f64::from(self.world_x.max(self.world_z));
 
-		// Scale offset inversely with frequency to keep relative position stable when frequency changes
-		let sample_x = (x_pos / base_world_size) + (f64::from(settings.offset_x) / settings.frequency);
-		let sample_z = (z_pos / base_world_size) + (f64::from(settings.offset_z) / settings.frequency);
+		// Scale offset inversely with frequency to keep relative position stable when frequency
+		// changes. At zero frequency the noise is constant anyway, so skip the offset instead of
+		// making NaNs.
+		let frequency_offset = |offset: f32| {
+			if settings.frequency == 0.0 {
+				0.0
+			} else {
+				f64::from(offset) / settings.frequency
+			}
+		};
+		let sample_x = (x_pos / base_world_size) + frequency_offset(settings.offset_x);
+		let sample_z = (z_pos / base_world_size) + frequency_offset(settings.offset_z);
 
 		noise.get([sample_x, sample_z])
 	}
 
-	fn generate_mesh(&self, settings: &Settings) -> Mesh {
-		let mut positions = Vec::with_capacity(((self.grid_x + 1) * (self.grid_z + 1)) as usize);
-		let mut uvs = Vec::with_capacity(((self.grid_x + 1) * (self.grid_z + 1)) as usize);
-		let mut indices = Vec::with_capacity((self.grid_x * self.grid_z * 6) as usize);
+	/// Mesh through every `step`th height map vertex, always keeping the last row and column so
+	/// the edges stay put.
+	fn generate_mesh(&self, settings: &Settings, step: u32) -> Mesh {
+		let (positions, uvs, indices) = self.mesh_buffers(settings, step);
+		terrain_mesh(positions, uvs, indices)
+	}
+
+	/// Vertex positions, UVs and triangle indices shared by the mesh and OBJ export.
+	fn mesh_buffers(
+		&self,
+		settings: &Settings,
+		step: u32,
+	) -> (Vec<[f32; 3]>, Vec<[f32; 2]>, Vec<u32>) {
+		let xs = strided(self.grid_x, step);
+		let zs = strided(self.grid_z, step);
+		let (columns, rows) = (xs.len() as u32, zs.len() as u32);
+		let mut positions = Vec::with_capacity((columns * rows) as usize);
+		let mut uvs = Vec::with_capacity((columns * rows) as usize);
+		let mut indices = Vec::with_capacity(((columns - 1) * (rows - 1) * 6) as usize);
 
 		// Generate vertices
-		for z in 0..=self.grid_z {
-			for x in 0..=self.grid_x {
+		for &z in &zs {
+			for &x in &xs {
 				let world_pos = grid_to_world(x, z, settings);
 				let y_pos =
 					self.height_map.get(x, z) * world_size_for_height(settings) * self.height_multiplier;
@@ -248,11 +709,11 @@ impl TerrainGenerator {
 		}
 
 		// Generate triangle indices
-		for z in 0..self.grid_z {
-			for x in 0..self.grid_x {
-				let current = z * (self.grid_x + 1) + x;
+		for z in 0..rows - 1 {
+			for x in 0..columns - 1 {
+				let current = z * columns + x;
 				let next_x = current + 1;
-				let next_z = (z + 1) * (self.grid_x + 1) + x;
+				let next_z = (z + 1) * columns + x;
 				let next_both = next_z + 1;
 
 				// First triangle (counter-clockwise winding)
@@ -262,11 +723,40 @@ impl TerrainGenerator {
 			}
 		}
 
-		Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::all())
-			.with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
-			.with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
-			.with_inserted_indices(Indices::U32(indices))
-			.with_computed_normals()
+		(positions, uvs, indices)
+	}
+
+	/// Wavefront OBJ text for the terrain mesh, with the same normals as the rendered mesh.
+	fn to_obj(&self, settings: &Settings) -> String {
+		use std::fmt::Write as _;
+
+		let mesh = self.generate_mesh(settings, 1);
+		let (positions, uvs, indices) = self.mesh_buffers(settings, 1);
+		let normals = mesh
+			.attribute(Mesh::ATTRIBUTE_NORMAL)
+			.and_then(|normals| normals.as_float3())
+			.unwrap_or_default();
+
+		let mut obj = String::from("# track_geometry terrain\no terrain\n");
+		for [x, y, z] in &positions {
+			let _ = writeln!(obj, "v {x} {y} {z}");
+		}
+		for [u, v] in &uvs {
+			let _ = writeln!(obj, "vt {u} {v}");
+		}
+		for [x, y, z] in normals {
+			let _ = writeln!(obj, "vn {x} {y} {z}");
+		}
+		// OBJ indices are 1-based; position, uv and normal share the same index
+		for triangle in indices.as_chunks::<3>().0 {
+			let [a, b, c] = triangle.map(|index| index + 1);
+			let _ = writeln!(obj, "f {a}/{a}/{a} {b}/{b}/{b} {c}/{c}/{c}");
+		}
+		obj
+	}
+
+	fn export_obj(&self, settings: &Settings, path: &Path) -> std::io::Result<()> {
+		std::fs::write(path, self.to_obj(settings))
 	}
 
 	fn generate_texture(&self) -> Image {
@@ -293,6 +783,47 @@ impl TerrainGenerator {
 		)
 	}
 
+	/// Grade (rise over run) at a grid vertex from central differences, one-sided at the edges.
+	fn slope_at(&self, x: u32, z: u32) -> f32 {
+		let height_scale = self.world_x.min(self.world_z) * self.height_multiplier;
+		let step_x = self.world_x / self.grid_x.max(1) as f32;
+		let step_z = self.world_z / self.grid_z.max(1) as f32;
+		let derivative = |lo: (u32, u32), hi: (u32, u32), step: f32, steps: u32| {
+			if steps == 0 {
+				return 0.0;
+			}
+			(self.height_map.get(hi.0, hi.1) - self.height_map.get(lo.0, lo.1)) * height_scale
+				/ (step * steps as f32)
+		};
+		let (x_lo, x_hi) = (x.saturating_sub(1), (x + 1).min(self.grid_x));
+		let (z_lo, z_hi) = (z.saturating_sub(1), (z + 1).min(self.grid_z));
+		let dx = derivative((x_lo, z), (x_hi, z), step_x, x_hi - x_lo);
+		let dz = derivative((x, z_lo), (x, z_hi), step_z, z_hi - z_lo);
+		dx.hypot(dz)
+	}
+
+	/// Per-vertex steepness ramped from green (flat) to red (at or above [`MAX_RAMP_SLOPE`]).
+	fn generate_slope_texture(&self) -> Image {
+		let mut texture_data = Vec::with_capacity(((self.grid_x + 1) * (self.grid_z + 1) * 4) as usize);
+		for z in 0..=self.grid_z {
+			for x in 0..=self.grid_x {
+				texture_data.extend_from_slice(&slope_color(self.slope_at(x, z)));
+			}
+		}
+
+		Image::new_fill(
+			Extent3d {
+				width: self.grid_x + 1,
+				height: self.grid_z + 1,
+				depth_or_array_layers: 1,
+			},
+			TextureDimension::D2,
+			&texture_data,
+			TextureFormat::Rgba8UnormSrgb,
+			RenderAssetUsages::all(),
+		)
+	}
+
 	fn calculate_preview_dimensions(&self) -> (f32, f32) {
 		let src_width = self.grid_x + 1;
 		let src_height = self.grid_z + 1;
@@ -308,25 +839,150 @@ impl TerrainGenerator {
 	}
 }
 
+const TERRAIN_OBJ_FILENAME: &str = "terrain.obj";
+
+/// Everything terrain generation produces, as plain data with no engine assets attached.
+#[derive(Debug, Clone)]
+pub struct GeneratedTerrain {
+	pub height_map: HeightMap,
+	pub positions: Vec<[f32; 3]>,
+	pub uvs: Vec<[f32; 2]>,
+	/// Counter-clockwise triangle list into `positions`.
+	pub indices: Vec<u32>,
+}
+
+/// Height map and full-resolution mesh buffers for `settings`.
+pub fn generate_terrain(settings: &Settings) -> GeneratedTerrain {
+	generate_strided_terrain(settings, 1)
+}
+
+/// Writes `height_map` to `path`, picking the format from the extension: a Wavefront OBJ mesh for
+/// `.obj`, or an 8-bit grayscale height map for `.png`.
+pub fn export_height_map(
+	settings: &Settings,
+	height_map: &HeightMap,
+	path: &Path,
+) -> anyhow::Result<()> {
+	let generator = TerrainGenerator::with_height_map(settings, height_map.clone());
+	let extension = path
+		.extension()
+		.and_then(|ext| ext.to_str())
+		.map(str::to_ascii_lowercase);
+	match extension.as_deref() {
+		Some("obj") => generator.export_obj(settings, path)?,
+		Some("png") => generator
+			.generate_texture()
+			.try_into_dynamic()?
+			.save(path)?,
+		_ => anyhow::bail!(
+			"can't export {}: use a .obj or .png extension",
+			path.display()
+		),
+	}
+	Ok(())
+}
+
+fn generate_strided_terrain(settings: &Settings, step: u32) -> GeneratedTerrain {
+	let generator = TerrainGenerator::populated(settings);
+	let (positions, uvs, indices) = generator.mesh_buffers(settings, step);
+	GeneratedTerrain {
+		height_map: generator.height_map,
+		positions,
+		uvs,
+		indices,
+	}
+}
+
+/// Wraps terrain buffers in a mesh, with normals computed from the triangles.
+fn terrain_mesh(positions: Vec<[f32; 3]>, uvs: Vec<[f32; 2]>, indices: Vec<u32>) -> Mesh {
+	Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::all())
+		.with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+		.with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+		.with_inserted_indices(Indices::U32(indices))
+		.with_computed_normals()
+}
+
+/// `settings` with the grid resized so its longer side has `texture_resolution` cells.
+fn texture_settings(settings: &Settings) -> Settings {
+	let longer_aspect = settings.aspect_x.max(settings.aspect_z).max(1);
+	Settings {
+		base_grid_resolution: (settings.texture_resolution / longer_aspect).max(1),
+		..settings.clone()
+	}
+}
+
+/// Noise preview texture and its display size, sampled at `settings.texture_resolution`. Eroded
+/// terrain is previewed from the mesh's height map instead, since eroding a second, finer grid
+/// would take far longer than the terrain itself.
+fn noise_preview(settings: &Settings, terrain: &TerrainGenerator) -> (Image, (f32, f32)) {
+	if settings.thermal_erosion_iterations > 0 {
+		return (
+			terrain.generate_texture(),
+			terrain.calculate_preview_dimensions(),
+		);
+	}
+	let preview = TerrainGenerator::sampled(&texture_settings(settings));
+	(
+		preview.generate_texture(),
+		preview.calculate_preview_dimensions(),
+	)
+}
+
+/// Grid indices `0, step, 2 * step, ...` up to and always including `last`.
+fn strided(last: u32, step: u32) -> Vec<u32> {
+	let mut indices: Vec<u32> = (0..last).step_by(step.max(1) as usize).collect();
+	indices.push(last);
+	indices
+}
+
+fn configure_fractal<N: MultiFractal>(noise: N, settings: &Settings) -> N {
+	noise
+		.set_octaves(settings.octaves as usize)
+		.set_frequency(settings.frequency)
+		.set_lacunarity(settings.lacunarity)
+		.set_persistence(settings.persistence)
+}
+
+/// Grade shown fully red on the slope map (1.0 = 45°).
+const MAX_RAMP_SLOPE: f32 = 1.0;
+
+/// RGBA for a grade on the green to red slope ramp.
+fn slope_color(slope: f32) -> [u8; 4] {
+	let t = (slope / MAX_RAMP_SLOPE).clamp(0.0, 1.0);
+	[(t * 255.0) as u8, ((1.0 - t) * 255.0) as u8, 0, 255]
+}
+
 /// Creates mesh and texture handles from current settings
 fn create_terrain_assets(
 	settings: &Settings,
 	meshes: &mut ResMut<Assets<Mesh>>,
 	images: &mut ResMut<Assets<Image>>,
-) -> (Handle<Mesh>, Handle<Image>, HeightMap, f32, f32) {
-	let mut generator = TerrainGenerator::from_settings(settings);
-	generator.generate_height_map(settings);
+) -> (
+	Handle<Mesh>,
+	Handle<Image>,
+	Handle<Image>,
+	HeightMap,
+	f32,
+	f32,
+) {
+	let terrain = generate_terrain(settings);
+	let mesh_handle = meshes.add(terrain_mesh(
+		terrain.positions,
+		terrain.uvs,
+		terrain.indices,
+	));
 
-	let terrain_mesh = generator.generate_mesh(settings);
-	let noise_texture = generator.generate_texture();
-	let (preview_width, preview_height) = generator.calculate_preview_dimensions();
+	let generator = TerrainGenerator::with_height_map(settings, terrain.height_map);
+	let (noise_texture, (preview_width, preview_height)) = noise_preview(settings, &generator);
+	let slope_texture = generator.generate_slope_texture();
 
-	let mesh_handle = meshes.add(terrain_mesh);
 	let texture_handle = images.add(noise_texture);
+	let slope_handle = images.add(slope_texture);
 
 	(
 		mesh_handle,
 		texture_handle,
+		slope_handle,
 		generator.height_map,
 		preview_width,
 		preview_height,
@@ -364,12 +1020,59 @@ fn add_info_label(ui: &mut egui::Ui, label: &str, args: std::fmt::Arguments) {
 	ui.label(format!("{label}: {args}"));
 }
 
-fn render_terrain_config_ui(ui: &mut egui::Ui, settings: &mut Settings) {
+fn render_surface_ui(ui: &mut egui::Ui, settings: &mut SurfaceSettings) {
+	ui.horizontal(|ui| {
+		ui.label("Base Color:");
+		let mut rgb = settings.base_color.to_srgba().to_f32_array_no_alpha();
+		if ui.color_edit_button_rgb(&mut rgb).changed() {
+			settings.base_color = Color::srgb(rgb[0], rgb[1], rgb[2]);
+		}
+	});
+	add_labeled_slider(ui, "Metallic", &mut settings.metallic, 0.0..=1.0);
+	add_labeled_slider(
+		ui,
+		"Roughness",
+		&mut settings.perceptual_roughness,
+		0.0..=1.0,
+	);
+}
+
+const MAX_BASE_GRID_RESOLUTION: u32 = 512;
+/// Full-resolution meshes with more triangles than this get a warning in the controls.
+const TRIANGLE_WARNING_THRESHOLD: u64 = 500_000;
+
+/// Size of the full-resolution terrain mesh `settings` would build.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct MeshStats {
+	vertices: u64,
+	triangles: u64,
+	/// Position, normal and UV attributes plus the `u32` index buffer.
+	buffer_bytes: u64,
+}
+
+impl MeshStats {
+	fn of(settings: &Settings) -> Self {
+		let (grid_x, grid_z) = (u64::from(settings.grid_x()), u64::from(settings.grid_z()));
+		let vertices = (grid_x + 1) * (grid_z + 1);
+		let triangles = 2 * grid_x * grid_z;
+		let vertex_bytes = (3 + 3 + 2) * size_of::<f32>() as u64;
+		let index_bytes = 3 * size_of::<u32>() as u64;
+		Self {
+			vertices,
+			triangles,
+			buffer_bytes: vertices * vertex_bytes + triangles * index_bytes,
+		}
+	}
+}
+
+fn render_terrain_config_ui(ui: &mut egui::Ui, settings: &mut Settings, units: Units) {
+	render_terrain_source_ui(ui, &mut settings.source);
+
 	add_labeled_int_slider(
 		ui,
 		"Base Grid Resolution",
 		&mut settings.base_grid_resolution,
-		1..=128,
+		1..=MAX_BASE_GRID_RESOLUTION,
 	);
 	add_labeled_int_slider(ui, "Aspect Ratio X", &mut settings.aspect_x, 1..=8);
 	add_labeled_int_slider(ui, "Aspect Ratio Z", &mut settings.aspect_z, 1..=8);
@@ -379,13 +1082,36 @@ fn render_terrain_config_ui(ui: &mut egui::Ui, settings: &mut Settings) {
 		"Grid Size",
 		format_args!("{}x{}", settings.grid_x(), settings.grid_z()),
 	);
+	let stats = MeshStats::of(settings);
+	add_info_label(
+		ui,
+		"Mesh",
+		format_args!(
+			"{} vertices, {} triangles, ~{:.1} MiB",
+			stats.vertices,
+			stats.triangles,
+			stats.buffer_bytes as f64 / (1024.0 * 1024.0)
+		),
+	);
+	if stats.triangles > TRIANGLE_WARNING_THRESHOLD {
+		ui.colored_label(
+			egui::Color32::YELLOW,
+			"That many triangles can make regenerating and rendering slow",
+		);
+	}
+	add_labeled_int_slider(
+		ui,
+		"Preview Texture Resolution",
+		&mut settings.texture_resolution,
+		16..=2048,
+	);
 	add_info_label(
 		ui,
 		"World Size",
 		format_args!(
-			"{:.1}x{:.1} (meters)",
-			settings.world_x(),
-			settings.world_z()
+			"{} x {}",
+			units.format_distance(f64::from(settings.world_x())),
+			units.format_distance(f64::from(settings.world_z()))
 		),
 	);
 
@@ -402,7 +1128,48 @@ fn render_terrain_config_ui(ui: &mut egui::Ui, settings: &mut Settings) {
 	);
 }
 
+fn render_terrain_source_ui(ui: &mut egui::Ui, source: &mut TerrainSource) {
+	ui.label("Height Source");
+	ui.horizontal(|ui| {
+		if ui
+			.radio(matches!(source, TerrainSource::Noise), "Noise")
+			.clicked()
+		{
+			*source = TerrainSource::Noise;
+		}
+		if ui
+			.radio(matches!(source, TerrainSource::Image(_)), "Image")
+			.clicked()
+			&& !matches!(source, TerrainSource::Image(_))
+		{
+			*source = TerrainSource::Image(PathBuf::new());
+		}
+	});
+
+	if let TerrainSource::Image(path) = source {
+		// Edit a draft so the terrain isn't regenerated on every keystroke
+		let draft_id = ui.id().with("height_image_path");
+		let mut draft = ui
+			.data_mut(|d| d.get_temp::<String>(draft_id))
+			.unwrap_or_else(|| path.display().to_string());
+		let response = ui.text_edit_singleline(&mut draft);
+		if response.lost_focus() {
+			*path = PathBuf::from(&draft);
+		}
+		ui.data_mut(|d| d.insert_temp(draft_id, draft));
+	}
+}
+
 fn render_noise_config_ui(ui: &mut egui::Ui, settings: &mut Settings) {
+	ui.label("Noise type");
+	egui::ComboBox::from_id_salt("noise_kind")
+		.selected_text(settings.noise_kind.label())
+		.show_ui(ui, |ui| {
+			for kind in NoiseKind::ALL {
+				ui.selectable_value(&mut settings.noise_kind, kind, kind.label());
+			}
+		});
+
 	ui.label("Seed");
 	ui.add(egui::DragValue::new(&mut settings.seed).speed(1));
 
@@ -418,6 +1185,8 @@ fn render_noise_config_ui(ui: &mut egui::Ui, settings: &mut Settings) {
 		&mut settings.valley_exponent,
 		0.0..=20.0,
 	);
+	ui.checkbox(&mut settings.raw_noise, "Raw noise (debug)")
+		.on_hover_text("Skip normalization and the valley exponent to see the noise itself");
 }
 
 /// Image handle and dimensions for noise preview
@@ -428,21 +1197,45 @@ pub struct NoiseTextureResource {
 	pub height: f32,
 }
 
+/// Slope map preview; same dimensions as the noise preview.
+#[derive(Resource)]
+pub struct SlopeTextureResource {
+	pub handle: Handle<Image>,
+}
+
 fn ui_system(
 	mut contexts: EguiContexts,
 	mut settings: ResMut<Settings>,
 	noise_texture_res: Res<NoiseTextureResource>,
+	slope_texture_res: Res<SlopeTextureResource>,
 	ui_shell_state: Res<UiShellState>,
+	mut grid_settings: ResMut<BackgroundGridSettings>,
+	mut secondary_layer: ResMut<SecondaryLayerSettings>,
+	terrain_height_map: Query<&HeightMap, With<TerrainMesh>>,
+	mut lod: ResMut<TerrainLod>,
+	mut contour_state: ResMut<ContourState>,
+	units: Res<Units>,
+	mut file_dialog: ResMut<PendingFileDialog<Settings>>,
+	mut surface: ResMut<SurfaceSettings>,
 ) {
+	let units = *units;
 	// Get the texture_id before borrowing ctx_mut
 	let texture_id = contexts.add_image(bevy_egui::EguiTextureHandle::Weak(
 		noise_texture_res.handle.id(),
 	));
+	let slope_texture_id = contexts.add_image(bevy_egui::EguiTextureHandle::Weak(
+		slope_texture_res.handle.id(),
+	));
 
 	if let Ok(ctx) = contexts.ctx_mut() {
 		// snapshot settigns for change detection
 		let before = settings.clone();
 		let settings_ptr = settings.bypass_change_detection();
+		let layer_before = secondary_layer.clone();
+		let layer_ptr = secondary_layer.bypass_change_detection();
+		let surface_before = surface.clone();
+		let surface_ptr = surface.bypass_change_detection();
+		let mut buildability = contour_state.buildability;
 
 		if ui_shell_state.active_panel == ActivePanel::TerrainControls {
 			egui::Window::new("Terrain Controls")
@@ -451,11 +1244,30 @@ fn ui_system(
 				.resizable(false)
 				.show(ctx, |ui| {
 					ui.collapsing("Terrain Configuration", |ui| {
-						render_terrain_config_ui(ui, settings_ptr);
+						render_terrain_config_ui(ui, settings_ptr, units);
 					});
 					ui.collapsing("Noise Parameters:", |ui| {
 						render_noise_config_ui(ui, settings_ptr);
 					});
+					ui.collapsing("Thermal Erosion", |ui| {
+						erosion::thermal_erosion_ui(ui, settings_ptr);
+					});
+					ui.collapsing("Surface", |ui| {
+						render_surface_ui(ui, surface_ptr);
+						surface_ptr.handle_save_operation_ui(ui, "Save Surface");
+					});
+					ui.collapsing("Buildability", |ui| {
+						buildability::buildability_ui(ui, &mut buildability);
+					});
+					ui.collapsing("Water", |ui| {
+						water::water_ui(ui, settings_ptr, terrain_height_map.single().ok(), units);
+					});
+					ui.collapsing("Secondary Layer", |ui| {
+						secondary_layer::secondary_layer_ui(ui, layer_ptr, units);
+					});
+					ui.collapsing("Level of Detail", |ui| {
+						lod::lod_ui(ui, &mut lod);
+					});
 
 					ui.separator();
 
@@ -472,6 +1284,20 @@ fn ui_system(
 							}
 						}
 					}
+
+					if let Some(loaded) = settings_ptr.file_dialog_ui(ui, &mut file_dialog) {
+						*settings_ptr = loaded;
+					}
+
+					if ui.button("Export Terrain OBJ").clicked()
+						&& let Ok(height_map) = terrain_height_map.single()
+					{
+						let generator = TerrainGenerator::with_height_map(settings_ptr, height_map.clone());
+						match generator.export_obj(settings_ptr, Path::new(TERRAIN_OBJ_FILENAME)) {
+							Ok(()) => debug!("Exported {TERRAIN_OBJ_FILENAME}"),
+							Err(e) => error!("Failed to export {TERRAIN_OBJ_FILENAME}: {e}"),
+						}
+					}
 				});
 		}
 
@@ -479,6 +1305,15 @@ fn ui_system(
 		if *settings_ptr != before {
 			settings.set_changed();
 		}
+		if *surface_ptr != surface_before {
+			surface.set_changed();
+		}
+		if *layer_ptr != layer_before {
+			secondary_layer.set_changed();
+		}
+		if buildability != contour_state.buildability {
+			contour_state.buildability = buildability;
+		}
 
 		let image_width = noise_texture_res.width;
 		let image_height = noise_texture_res.height;
@@ -491,6 +1326,17 @@ fn ui_system(
 				.resizable(false)
 				.vscroll(true)
 				.show(ctx, |ui| {
+					ui.horizontal(|ui| {
+						ui.checkbox(&mut grid_settings.enabled, "Background grid");
+						ui.add_enabled(
+							grid_settings.enabled,
+							units
+								.length_drag_value(&mut grid_settings.cell_size)
+								.range(1.0..=10_000.0),
+						);
+					});
+					ui.separator();
+
 					let available = ui.available_size();
 
 					// Always fit by width since vertical scrolling is enabled
@@ -500,6 +1346,8 @@ fn ui_system(
 
 					ui.label("Noise Texture");
 					ui.image((texture_id, egui::vec2(draw_width, draw_height)));
+					ui.label("Slope (green flat, red 45° or steeper)");
+					ui.image((slope_texture_id, egui::vec2(draw_width, draw_height)));
 				});
 		}
 	}
@@ -511,14 +1359,19 @@ fn setup_terrain(
 	mut materials: ResMut<Assets<StandardMaterial>>,
 	mut images: ResMut<Assets<Image>>,
 	settings: Res<Settings>,
+	surface: Res<SurfaceSettings>,
 ) {
-	let (mesh_handle, texture_handle, height_map, preview_width, preview_height) =
+	let (mesh_handle, texture_handle, slope_handle, height_map, preview_width, preview_height) =
 		create_terrain_assets(&settings, &mut meshes, &mut images);
 
 	// Spawn terrain mesh
 	commands.spawn((
 		Mesh3d(mesh_handle),
-		MeshMaterial3d(materials.add(Color::srgb(0.3, 0.5, 0.3))),
+		MeshMaterial3d(materials.add({
+			let mut material = StandardMaterial::default();
+			surface.apply_to_material(&mut material);
+			material
+		})),
 		TerrainMesh,
 		height_map,
 	));
@@ -529,41 +1382,520 @@ fn setup_terrain(
 		width: preview_width,
 		height: preview_height,
 	});
+	commands.insert_resource(SlopeTextureResource {
+		handle: slope_handle,
+	});
+}
+
+/// Terrain built off the main thread, waiting to be swapped in.
+struct RegeneratedTerrain {
+	/// The settings (minus the water level) the terrain was generated from.
+	inputs: Settings,
+	step: u32,
+	height_map: HeightMap,
+	mesh: Mesh,
+	texture: Image,
+	slope_texture: Image,
+	preview_size: (f32, f32),
+}
+
+fn spawn_terrain_generation(inputs: Settings, step: u32) -> Task<RegeneratedTerrain> {
+	AsyncComputeTaskPool::get().spawn(async move {
+		let terrain = generate_strided_terrain(&inputs, step);
+		let mesh = terrain_mesh(terrain.positions, terrain.uvs, terrain.indices);
+
+		// Slopes come from the same height map the mesh was built from
+		let generator = TerrainGenerator::with_height_map(&inputs, terrain.height_map);
+		let (texture, preview_size) = noise_preview(&inputs, &generator);
+		let slope_texture = generator.generate_slope_texture();
+		RegeneratedTerrain {
+			inputs,
+			step,
+			height_map: generator.height_map,
+			mesh,
+			texture,
+			slope_texture,
+			preview_size,
+		}
+	})
+}
+
+/// Restyle the terrain's material in place; the look never needs the mesh rebuilt.
+fn update_terrain_material(
+	surface: Res<SurfaceSettings>,
+	terrain: Single<&MeshMaterial3d<StandardMaterial>, With<TerrainMesh>>,
+	mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+	if let Some(material) = materials.get_mut(&terrain.0) {
+		surface.apply_to_material(material);
+	}
 }
 
 fn update_terrain(
+	time: Res<Time>,
 	mut images: ResMut<Assets<Image>>,
 	terrain_query: Single<(&mut Mesh3d, &mut HeightMap), With<TerrainMesh>>,
 	mut meshes: ResMut<Assets<Mesh>>,
 	mut noise_texture_res: ResMut<NoiseTextureResource>,
+	slope_texture_res: Res<SlopeTextureResource>,
+	mut removals: ResMut<DeferredMeshRemovals>,
 	settings: Res<Settings>,
+	lod: Res<TerrainLod>,
+	mut generated_from: Local<Option<Settings>>,
+	mut meshed_step: Local<Option<u32>>,
+	mut debounce: Local<Option<Timer>>,
+	mut generation: Local<Option<Task<RegeneratedTerrain>>>,
 ) {
+	let (mut mesh_handle, mut height_map) = terrain_query.into_inner();
+	// Moving the water plane alone doesn't touch the terrain
+	let inputs = settings.terrain_inputs();
+	let mut regenerate = false;
 	if settings.is_changed() {
-		// Create generator and generate height map once
+		match generated_from.as_ref() {
+			Some(previous) if *previous == inputs => {
+				// Back where we started: nothing in flight is wanted any more
+				*debounce = None;
+				*generation = None;
+			}
+			// Restart the wait on every change so a dragged slider rebuilds once, on release
+			Some(previous) if !inputs.differs_only_discretely(previous) => {
+				*debounce = Some(Timer::from_seconds(
+					REGENERATE_DEBOUNCE_SECS,
+					TimerMode::Once,
+				));
+			}
+			_ => regenerate = true,
+		}
+	}
+	if let Some(timer) = debounce.as_mut()
+		&& timer.tick(time.delta()).just_finished()
+	{
+		regenerate = true;
+	}
+	if regenerate {
+		*debounce = None;
+		// Dropping the previous task cancels it, so a stale result never lands
+		*generation = Some(spawn_terrain_generation(inputs, lod.step));
+	}
+
+	let finished = generation
+		.as_mut()
+		.and_then(|task| block_on(future::poll_once(task)));
+	let new_mesh = if let Some(terrain) = finished {
+		*generation = None;
+		*generated_from = Some(terrain.inputs);
+		*meshed_step = Some(terrain.step);
+		*height_map = terrain.height_map;
+
+		// Update the noise texture resource in place
+		if let Some(img) = images.get_mut(&noise_texture_res.handle) {
+			*img = terrain.texture;
+		}
+		if let Some(img) = images.get_mut(&slope_texture_res.handle) {
+			*img = terrain.slope_texture;
+		}
+		(noise_texture_res.width, noise_texture_res.height) = terrain.preview_size;
+		terrain.mesh
+	} else if let Some(generated) = generated_from.as_ref()
+		&& *meshed_step != Some(lod.step)
+	{
+		// Same terrain at a different level of detail: restride the existing height map. Use the
+		// settings it was generated from, since newer ones may still be on their way.
+		let generator = TerrainGenerator::with_height_map(generated, height_map.clone());
+		*meshed_step = Some(lod.step);
+		generator.generate_mesh(generated, lod.step)
+	} else {
+		return;
+	};
+
+	// Replace the terrain mesh entity, keeping the old mesh alive until the new one has uploaded
+	let old_mesh = std::mem::replace(&mut *mesh_handle, Mesh3d(meshes.add(new_mesh)));
+	removals.defer(old_mesh.0);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parallel_height_map_matches_serial_generation() {
+		let settings = Settings {
+			aspect_x: 2,
+			..Settings::default()
+		};
 		let mut generator = TerrainGenerator::from_settings(&settings);
 		generator.generate_height_map(&settings);
 
-		// Generate mesh and texture from the populated height map
-		let new_mesh = generator.generate_mesh(&settings);
-		let new_texture = generator.generate_texture();
-		let (preview_width, preview_height) = generator.calculate_preview_dimensions();
+		let noise = configure_fractal(HybridMulti::<OpenSimplex>::new(settings.seed), &settings);
+		let mut serial = Vec::new();
+		for z in 0..=generator.grid_z {
+			for x in 0..=generator.grid_x {
+				let world_pos = grid_to_world(x, z, &settings);
+				serial.push(generator.calculate_height_at_position(
+					f64::from(world_pos.x),
+					f64::from(world_pos.z),
+					&settings,
+					&noise,
+				) as f32);
+			}
+		}
+		let range = normalization_range(&settings).unwrap();
+		for height in &mut serial {
+			*height = normalize_height(*height, range, settings.valley_exponent);
+		}
 
-		// Replace the terrain mesh entity
-		let (mut mesh_handle, mut height_map) = terrain_query.into_inner();
-		let old_mesh_id = mesh_handle.id();
+		let parallel: Vec<u32> = generator
+			.height_map
+			.heights
+			.iter()
+			.map(|h| h.to_bits())
+			.collect();
+		let serial: Vec<u32> = serial.iter().map(|h| h.to_bits()).collect();
+		assert_eq!(parallel, serial);
+	}
 
-		// Create new mesh and update the handle
-		*mesh_handle = Mesh3d(meshes.add(new_mesh));
-		*height_map = generator.height_map;
+	#[test]
+	fn slope_texture_reflects_a_uniform_ramp() {
+		let settings = Settings {
+			height_multiplier: 1.0,
+			..Settings::default()
+		};
+		let mut generator = TerrainGenerator::from_settings(&settings);
+		// Rises one height unit across X, i.e. a grade of 1 over the square terrain
+		for z in 0..=generator.grid_z {
+			for x in 0..=generator.grid_x {
+				generator
+					.height_map
+					.set(x, z, x as f32 / generator.grid_x as f32);
+			}
+		}
+		for (x, z) in [(0, 0), (3, 4), (generator.grid_x, generator.grid_z)] {
+			assert!((generator.slope_at(x, z) - 1.0).abs() < 1e-4);
+		}
+		assert_eq!(slope_color(0.0), [0, 255, 0, 255]);
+		assert_eq!(slope_color(2.0), [255, 0, 0, 255]);
 
-		// Remove the old mesh asset before creating a new one
-		meshes.remove(old_mesh_id);
+		let texture = generator.generate_slope_texture();
+		assert_eq!(texture.width(), generator.grid_x + 1);
+	}
 
-		// Update the noise texture resource in place
-		if let Some(img) = images.get_mut(&noise_texture_res.handle) {
-			*img = new_texture;
+	#[test]
+	fn replaced_mesh_outlives_its_replacement_by_the_delay() {
+		let old = Handle::<Mesh>::default();
+		let mut removals = DeferredMeshRemovals::default();
+		removals.defer(old.clone());
+		assert!(
+			removals.take_expired().is_empty(),
+			"removed in the frame it was replaced"
+		);
+		assert_eq!(removals.take_expired(), std::slice::from_ref(&old));
+		assert!(removals.take_expired().is_empty());
+
+		let mut immediate = DeferredMeshRemovals {
+			delay_frames: 0,
+			..default()
+		};
+		immediate.defer(old.clone());
+		assert_eq!(immediate.take_expired(), [old]);
+	}
+
+	#[test]
+	fn deferred_mesh_stays_loaded_after_its_entity_lets_go() {
+		let mut app = App::new();
+		app
+			.add_plugins((MinimalPlugins, AssetPlugin::default()))
+			.init_asset::<Mesh>()
+			.init_resource::<DeferredMeshRemovals>()
+			.add_systems(Update, remove_deferred_meshes);
+		let old = app
+			.world_mut()
+			.resource_mut::<Assets<Mesh>>()
+			.add(Mesh::from(Cuboid::default()));
+		let id = old.id();
+		// The removals now hold the only handle, as after the entity was given a new mesh
+		app
+			.world_mut()
+			.resource_mut::<DeferredMeshRemovals>()
+			.defer(old);
+
+		app.update();
+		assert!(
+			app.world().resource::<Assets<Mesh>>().contains(id),
+			"freed in the frame it was replaced"
+		);
+		for _ in 0..3 {
+			app.update();
+		}
+		assert!(!app.world().resource::<Assets<Mesh>>().contains(id));
+	}
+
+	#[test]
+	fn non_square_height_map_indexes_rows_along_z() {
+		// 3 cells along X, 7 along Z
+		let settings = Settings {
+			base_grid_resolution: 1,
+			aspect_x: 3,
+			aspect_z: 7,
+			height_multiplier: 1.0,
+			..Settings::default()
+		};
+		let known = |x: u32, z: u32| (x * 10 + z) as f32 / 100.0;
+		let mut height_map = HeightMap {
+			length_x: settings.grid_x(),
+			heights: vec![0.0; 4 * 8],
+		};
+		for z in 0..=7 {
+			for x in 0..=3 {
+				height_map.set(x, z, known(x, z));
+			}
+		}
+
+		assert_eq!(height_map.length_z(), 7);
+		let scale = world_size_for_height(&settings) * settings.height_multiplier;
+		for z in 0..=7 {
+			for x in 0..=3 {
+				assert_eq!(height_map.get(x, z), known(x, z));
+				let world = grid_to_world(x, z, &settings);
+				let sampled = calculate_terrain_height(world, &height_map, &settings);
+				assert!(
+					(sampled - known(x, z) * scale).abs() < 1e-2,
+					"({x}, {z}) sampled {sampled}"
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn raw_noise_skips_normalization_and_valley_exponent() {
+		let settings = Settings {
+			raw_noise: true,
+			..Settings::default()
+		};
+		let mut generator = TerrainGenerator::from_settings(&settings);
+		generator.generate_height_map(&settings);
+
+		let noise = configure_fractal(HybridMulti::<OpenSimplex>::new(settings.seed), &settings);
+		let raw = generator.sample_heights(&settings, &noise);
+		assert_eq!(generator.height_map.heights, raw);
+	}
+
+	#[test]
+	fn every_noise_kind_produces_normalized_heights() {
+		for noise_kind in NoiseKind::ALL {
+			let settings = Settings {
+				noise_kind,
+				..Settings::default()
+			};
+			let mut generator = TerrainGenerator::from_settings(&settings);
+			generator.generate_height_map(&settings);
+			assert!(
+				generator
+					.height_map
+					.heights
+					.iter()
+					.all(|h| (0.0..=1.0).contains(h)),
+				"{noise_kind:?} heights should be normalized to 0..=1"
+			);
+		}
+	}
+
+	#[test]
+	fn height_map_from_image_is_resampled_and_normalized() {
+		// 2x2 8-bit image: dark left column, bright right column
+		let image = Image::new(
+			Extent3d {
+				width: 2,
+				height: 2,
+				depth_or_array_layers: 1,
+			},
+			TextureDimension::D2,
+			vec![50, 150, 50, 150],
+			TextureFormat::R8Unorm,
+			RenderAssetUsages::default(),
+		);
+		let height_map = HeightMap::from_image_sized(&image, 2, 1);
+
+		assert_eq!(height_map.heights.len(), 6);
+		assert!((height_map.get(0, 0) - 0.0).abs() < 1e-5);
+		assert!((height_map.get(1, 1) - 0.5).abs() < 1e-5);
+		assert!((height_map.get(2, 0) - 1.0).abs() < 1e-5);
+	}
+
+	#[test]
+	fn height_map_from_image_keeps_aspect_ratio() {
+		let image = Image::new(
+			Extent3d {
+				width: 5,
+				height: 3,
+				depth_or_array_layers: 1,
+			},
+			TextureDimension::D2,
+			(0..15).collect(),
+			TextureFormat::R8Unorm,
+			RenderAssetUsages::default(),
+		);
+		let height_map = HeightMap::from_image(&image, 8);
+		assert_eq!(height_map.length_x, 8);
+		assert_eq!(height_map.heights.len(), 9 * 5);
+	}
+
+	#[test]
+	fn obj_export_has_one_based_faces_and_normals() {
+		let settings = Settings {
+			base_grid_resolution: 1,
+			..Settings::default()
+		};
+		let generator = TerrainGenerator::from_settings(&settings);
+		let obj = generator.to_obj(&settings);
+
+		let count = |prefix: &str| obj.lines().filter(|line| line.starts_with(prefix)).count();
+		assert_eq!(count("v "), 4);
+		assert_eq!(count("vt "), 4);
+		assert_eq!(count("vn "), 4);
+		assert_eq!(count("f "), 2);
+		assert!(obj.contains("f 1/1/1 3/3/3 2/2/2"));
+		assert!(!obj.contains(" 0/"), "OBJ indices must be 1-based");
+	}
+
+	#[test]
+	fn strided_mesh_keeps_the_far_edges() {
+		// 3 cells along X, 7 along Z, neither a multiple of the stride
+		let settings = Settings {
+			base_grid_resolution: 1,
+			aspect_x: 3,
+			aspect_z: 7,
+			..Settings::default()
+		};
+		let generator = TerrainGenerator::from_settings(&settings);
+		assert_eq!(strided(7, 2), vec![0, 2, 4, 6, 7]);
+
+		let (full, _, _) = generator.mesh_buffers(&settings, 1);
+		let (positions, uvs, indices) = generator.mesh_buffers(&settings, 2);
+		assert_eq!(positions.len(), 3 * 5);
+		assert_eq!(indices.len(), 2 * 4 * 6);
+		assert_eq!(positions.first(), full.first());
+		assert_eq!(positions.last(), full.last());
+		assert_eq!(uvs.last(), Some(&[1.0, 1.0]));
+	}
+
+	#[test]
+	fn seed_and_noise_kind_are_discrete_changes() {
+		let previous = Settings::default();
+		let picked = Settings {
+			seed: 7,
+			noise_kind: NoiseKind::Billow,
+			..Settings::default()
+		};
+		assert!(picked.differs_only_discretely(&previous));
+		let dragged = Settings {
+			seed: 7,
+			frequency: 4.0,
+			..Settings::default()
+		};
+		assert!(!dragged.differs_only_discretely(&previous));
+	}
+
+	#[test]
+	fn water_level_is_not_a_terrain_input() {
+		let flooded = Settings {
+			water_level: 0.4,
+			..Settings::default()
+		};
+		assert!(flooded.terrain_inputs() == Settings::default().terrain_inputs());
+	}
+
+	#[test]
+	fn generated_terrain_has_a_vertex_per_sample_within_bounds() {
+		let settings = Settings {
+			base_grid_resolution: 4,
+			aspect_x: 2,
+			..Settings::default()
+		};
+		let terrain = generate_terrain(&settings);
+
+		assert_eq!(terrain.positions.len(), terrain.height_map.heights.len());
+		assert_eq!(terrain.positions.len(), 9 * 5);
+		assert_eq!(terrain.uvs.len(), terrain.positions.len());
+		assert_eq!(terrain.indices.len(), 8 * 4 * 6);
+		let max_height = world_size_for_height(&settings) * settings.height_multiplier;
+		for [x, y, z] in &terrain.positions {
+			assert!(x.abs() <= settings.world_x() / 2.0 + 1e-3);
+			assert!(z.abs() <= settings.world_z() / 2.0 + 1e-3);
+			assert!((0.0..=max_height + 1e-3).contains(y), "height {y}");
 		}
-		noise_texture_res.width = preview_width;
-		noise_texture_res.height = preview_height;
+	}
+
+	#[test]
+	fn constant_noise_produces_flat_height_map() {
+		let settings = Settings {
+			frequency: 0.0,
+			offset_x: 1.0,
+			..Settings::default()
+		};
+		let mut generator = TerrainGenerator::from_settings(&settings);
+		generator.generate_height_map(&settings);
+		assert!(
+			generator.height_map.heights.iter().all(|&h| h == 0.0),
+			"constant noise should give a flat height map"
+		);
+	}
+
+	#[test]
+	fn grid_resolution_adds_detail_without_reshaping_terrain() {
+		let coarse = Settings {
+			base_grid_resolution: 16,
+			..Settings::default()
+		};
+		let fine = Settings {
+			base_grid_resolution: 64,
+			..Settings::default()
+		};
+		let coarse_map = TerrainGenerator::populated(&coarse).height_map;
+		let fine_map = TerrainGenerator::populated(&fine).height_map;
+		// Every coarse vertex lies on every fourth fine vertex
+		for z in 0..=coarse_map.length_z() {
+			for x in 0..=coarse_map.length_x {
+				let (a, b) = (coarse_map.get(x, z), fine_map.get(x * 4, z * 4));
+				assert!((a - b).abs() < 1e-4, "({x}, {z}): {a} vs {b}");
+			}
+		}
+	}
+
+	#[test]
+	fn mesh_stats_count_the_full_resolution_grid() {
+		let settings = Settings {
+			base_grid_resolution: 4,
+			aspect_x: 2,
+			..Settings::default()
+		};
+		let stats = MeshStats::of(&settings);
+		assert_eq!(stats.vertices, 9 * 5);
+		assert_eq!(stats.triangles, 2 * 8 * 4);
+		assert_eq!(stats.buffer_bytes, 45 * 32 + 64 * 12);
+
+		let terrain = generate_terrain(&settings);
+		assert_eq!(terrain.positions.len() as u64, stats.vertices);
+		assert_eq!(terrain.indices.len() as u64, 3 * stats.triangles);
+	}
+
+	#[test]
+	fn preview_texture_resolution_is_independent_of_the_mesh_grid() {
+		let settings = Settings {
+			base_grid_resolution: 4,
+			aspect_x: 2,
+			texture_resolution: 64,
+			..Settings::default()
+		};
+		let terrain = TerrainGenerator::populated(&settings);
+		let (texture, _) = noise_preview(&settings, &terrain);
+		assert_eq!((texture.width(), texture.height()), (65, 33));
+
+		let eroded = Settings {
+			thermal_erosion_iterations: 5,
+			..settings
+		};
+		let terrain = TerrainGenerator::populated(&eroded);
+		let (texture, _) = noise_preview(&eroded, &terrain);
+		assert_eq!((texture.width(), texture.height()), (9, 5));
 	}
 }
@@ -0,0 +1,169 @@
+use bevy::prelude::*;
+use bevy_egui::egui;
+
+use super::{
+	DeferredMeshRemovals, Settings, TerrainGenerator, TerrainSource, TerrainUpdateSet,
+	render_terrain_source_ui,
+};
+use crate::units::Units;
+
+/// Optional second terrain surface drawn as a translucent overlay or underlay. Pins and
+/// alignments keep following the primary terrain.
+pub struct SecondaryLayerPlugin;
+
+impl Plugin for SecondaryLayerPlugin {
+	fn build(&self, app: &mut App) {
+		app.init_resource::<SecondaryLayerSettings>().add_systems(
+			Update,
+			update_secondary_layer
+				.in_set(TerrainUpdateSet)
+				.after(super::update_terrain),
+		);
+	}
+}
+
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct SecondaryLayerSettings {
+	pub enabled: bool,
+	pub seed: u32,
+	pub source: TerrainSource,
+	/// Vertical shift of the layer in meters; negative values sink it below the primary terrain.
+	pub vertical_offset: f32,
+}
+
+impl Default for SecondaryLayerSettings {
+	fn default() -> Self {
+		Self {
+			enabled: false,
+			seed: 1,
+			source: TerrainSource::Noise,
+			vertical_offset: 0.0,
+		}
+	}
+}
+
+impl SecondaryLayerSettings {
+	/// Primary terrain settings with this layer's height source swapped in, so both surfaces
+	/// share one grid and footprint.
+	pub fn layer_settings(&self, primary: &Settings) -> Settings {
+		Settings {
+			seed: self.seed,
+			source: self.source.clone(),
+			..primary.clone()
+		}
+	}
+}
+
+/// Marker for the secondary layer mesh. It deliberately has no `HeightMap` so terrain queries
+/// keep resolving to the primary surface.
+#[derive(Component)]
+pub struct SecondaryTerrainMesh;
+
+const LAYER_COLOR: Color = Color::srgba(0.55, 0.4, 0.3, 0.5);
+
+fn build_layer_mesh(layer: &SecondaryLayerSettings, primary: &Settings) -> Mesh {
+	let settings = layer.layer_settings(primary);
+	TerrainGenerator::populated(&settings).generate_mesh(&settings, 1)
+}
+
+fn update_secondary_layer(
+	mut commands: Commands,
+	mut meshes: ResMut<Assets<Mesh>>,
+	mut materials: ResMut<Assets<StandardMaterial>>,
+	settings: Res<Settings>,
+	layer: Res<SecondaryLayerSettings>,
+	mut removals: ResMut<DeferredMeshRemovals>,
+	existing: Query<(Entity, &Mesh3d), With<SecondaryTerrainMesh>>,
+	mut built_from: Local<Option<Settings>>,
+) {
+	// Moving the water plane alone doesn't touch the terrain
+	let primary_changed =
+		settings.is_changed() && built_from.as_ref() != Some(&settings.terrain_inputs());
+	if !primary_changed && !layer.is_changed() {
+		return;
+	}
+	*built_from = Some(settings.terrain_inputs());
+	for (entity, mesh) in &existing {
+		removals.defer(mesh.0.clone());
+		commands.entity(entity).despawn();
+	}
+	if !layer.enabled {
+		return;
+	}
+	commands.spawn((
+		Mesh3d(meshes.add(build_layer_mesh(&layer, &settings))),
+		MeshMaterial3d(materials.add(StandardMaterial {
+			base_color: LAYER_COLOR,
+			alpha_mode: AlphaMode::Blend,
+			double_sided: true,
+			cull_mode: None,
+			..default()
+		})),
+		Transform::from_xyz(0.0, layer.vertical_offset, 0.0),
+		SecondaryTerrainMesh,
+	));
+}
+
+pub(super) fn secondary_layer_ui(
+	ui: &mut egui::Ui,
+	layer: &mut SecondaryLayerSettings,
+	units: Units,
+) {
+	ui.checkbox(&mut layer.enabled, "Show secondary layer");
+	ui.add_enabled_ui(layer.enabled, |ui| {
+		ui.push_id("secondary_layer_source", |ui| {
+			render_terrain_source_ui(ui, &mut layer.source);
+		});
+		ui.label("Seed");
+		ui.add(egui::DragValue::new(&mut layer.seed).speed(1));
+		ui.label("Vertical offset");
+		ui.add(
+			units
+				.length_drag_value(&mut layer.vertical_offset)
+				.speed(1.0),
+		);
+	});
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bevy::mesh::VertexAttributeValues;
+
+	fn positions(mesh: &Mesh) -> Vec<[f32; 3]> {
+		match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+			Some(VertexAttributeValues::Float32x3(positions)) => positions.clone(),
+			_ => panic!("terrain mesh has no positions"),
+		}
+	}
+
+	#[test]
+	fn secondary_layer_heights_are_independent_of_primary() {
+		let primary = Settings::default();
+		let layer = SecondaryLayerSettings {
+			enabled: true,
+			seed: primary.seed.wrapping_add(7),
+			..default()
+		};
+
+		let primary_mesh = TerrainGenerator::populated(&primary).generate_mesh(&primary, 1);
+		let layer_mesh = build_layer_mesh(&layer, &primary);
+		let (primary_positions, layer_positions) = (positions(&primary_mesh), positions(&layer_mesh));
+
+		assert_eq!(primary_positions.len(), layer_positions.len());
+		assert!(
+			primary_positions
+				.iter()
+				.zip(&layer_positions)
+				.all(|(a, b)| a[0] == b[0] && a[2] == b[2]),
+			"layers should share the primary grid footprint"
+		);
+		assert!(
+			primary_positions
+				.iter()
+				.zip(&layer_positions)
+				.any(|(a, b)| a[1] != b[1]),
+			"a different seed should give the layer its own heights"
+		);
+	}
+}
@@ -3,41 +3,56 @@ use bevy::prelude::*;
 
 /// Convert grid coordinates to world coordinates
 pub fn grid_to_world(grid_x: u32, grid_z: u32, settings: &Settings) -> Vec3 {
-	let world_x = settings.world_x();
-	let world_z = settings.world_z();
+	let (min, max) = settings.world_bounds();
 	let grid_x_count = settings.grid_x();
 	let grid_z_count = settings.grid_z();
 
-	let x_step = world_x / grid_x_count as f32;
-	let z_step = world_z / grid_z_count as f32;
+	let x_step = (max.x - min.x) / grid_x_count as f32;
+	let z_step = (max.z - min.z) / grid_z_count as f32;
 
-	let x_pos = (grid_x as f32).mul_add(x_step, -(world_x / 2.0));
-	let z_pos = (grid_z as f32).mul_add(z_step, -(world_z / 2.0));
+	let x_pos = (grid_x as f32).mul_add(x_step, min.x);
+	let z_pos = (grid_z as f32).mul_add(z_step, min.z);
 
 	Vec3::new(x_pos, 0.0, z_pos)
 }
 
+/// Nearest grid vertex to a world position, clamped to the terrain.
+pub fn world_to_grid(world_pos: Vec3, settings: &Settings) -> UVec2 {
+	let (min, max) = settings.world_bounds();
+	let grid_x = settings.grid_x();
+	let grid_z = settings.grid_z();
+
+	let gx_f = (world_pos.x - min.x) / (max.x - min.x) * grid_x as f32;
+	let gz_f = (world_pos.z - min.z) / (max.z - min.z) * grid_z as f32;
+
+	UVec2::new(
+		(gx_f.round().max(0.0) as u32).min(grid_x),
+		(gz_f.round().max(0.0) as u32).min(grid_z),
+	)
+}
+
 /// Calculate terrain height at given world coordinates using bilinear interpolation.
 pub fn calculate_terrain_height(
 	world_pos: Vec3,
 	heightmap: &super::HeightMap,
 	settings: &Settings,
 ) -> f32 {
-	let world_x = settings.world_x();
-	let world_z = settings.world_z();
-	let grid_x = settings.grid_x();
-	let grid_z = settings.grid_z();
+	let (min, max) = settings.world_bounds();
+	// Index with the height map's own dimensions so X and Z can't be mixed up on non-square grids
+	let grid_x = heightmap.length_x;
+	let grid_z = heightmap.length_z();
 
-	let gx_f = (world_pos.x + world_x / 2.0) / world_x * grid_x as f32;
-	let gz_f = (world_pos.z + world_z / 2.0) / world_z * grid_z as f32;
+	let gx_f = (world_pos.x - min.x) / (max.x - min.x) * grid_x as f32;
+	let gz_f = (world_pos.z - min.z) / (max.z - min.z) * grid_z as f32;
 
-	let x0 = (gx_f.floor() as u32).min(grid_x.saturating_sub(1));
-	let z0 = (gz_f.floor() as u32).min(grid_z.saturating_sub(1));
-	let x1 = (x0 + 1).min(grid_x.saturating_sub(1));
-	let z1 = (z0 + 1).min(grid_z.saturating_sub(1));
+	// The last cell spans vertices `grid - 1` to `grid`, so the far edge interpolates too
+	let x0 = (gx_f.max(0.0).floor() as u32).min(grid_x.saturating_sub(1));
+	let z0 = (gz_f.max(0.0).floor() as u32).min(grid_z.saturating_sub(1));
+	let x1 = (x0 + 1).min(grid_x);
+	let z1 = (z0 + 1).min(grid_z);
 
-	let tx = gx_f.fract();
-	let tz = gz_f.fract();
+	let tx = (gx_f - x0 as f32).clamp(0.0, 1.0);
+	let tz = (gz_f - z0 as f32).clamp(0.0, 1.0);
 
 	let h00 = heightmap.get(x0, z0);
 	let h10 = heightmap.get(x1, z0);
@@ -49,6 +64,54 @@ pub fn calculate_terrain_height(
 	base_height * world_size_for_height(settings) * settings.height_multiplier
 }
 
+impl super::HeightMap {
+	/// World height at `pos` (Y ignored), bilinearly interpolated and clamped to the terrain edge.
+	pub fn sample_world(&self, pos: Vec3, settings: &Settings) -> f32 {
+		calculate_terrain_height(pos, self, settings)
+	}
+
+	/// World-space slope `(dh/dx, dh/dz)` at `pos`, from central differences half a cell apart.
+	/// Falls back to one-sided differences at the terrain edge.
+	#[allow(dead_code)]
+	pub fn sample_world_gradient(&self, pos: Vec3, settings: &Settings) -> Vec2 {
+		let half_extent = settings.world_bounds().1.xz();
+		let half_cell = Vec2::new(
+			settings.world_x() / self.length_x.max(1) as f32,
+			settings.world_z() / self.length_z().max(1) as f32,
+		) / 2.0;
+		let along = |offset: Vec2| {
+			let lo = (pos.xz() - offset).clamp(-half_extent, half_extent);
+			let hi = (pos.xz() + offset).clamp(-half_extent, half_extent);
+			let run = hi.distance(lo);
+			if run <= 0.0 {
+				return 0.0;
+			}
+			let height = |xz: Vec2| self.sample_world(Vec3::new(xz.x, 0.0, xz.y), settings);
+			(height(hi) - height(lo)) / run
+		};
+		Vec2::new(
+			along(Vec2::new(half_cell.x, 0.0)),
+			along(Vec2::new(0.0, half_cell.y)),
+		)
+	}
+
+	/// Unit surface normal at grid vertex `(x, z)`, from central differences of its neighbours in
+	/// world units. Border vertices have no neighbour on one side and return `Vec3::Y`.
+	#[allow(dead_code)]
+	pub fn normal_at(&self, x: u32, z: u32, settings: &Settings) -> Vec3 {
+		let length_z = self.length_z();
+		if x == 0 || z == 0 || x >= self.length_x || z >= length_z {
+			return Vec3::Y;
+		}
+		let height_scale = world_size_for_height(settings) * settings.height_multiplier;
+		let cell_x = settings.world_x() / self.length_x as f32;
+		let cell_z = settings.world_z() / length_z as f32;
+		let slope_x = (self.get(x + 1, z) - self.get(x - 1, z)) * height_scale / (2.0 * cell_x);
+		let slope_z = (self.get(x, z + 1) - self.get(x, z - 1)) * height_scale / (2.0 * cell_z);
+		Vec3::new(-slope_x, 1.0, -slope_z).normalize()
+	}
+}
+
 /// Get the world size (commonly used calculation)
 pub fn world_size(settings: &Settings) -> f32 {
 	settings.world_x().max(settings.world_z())
@@ -59,16 +122,136 @@ pub fn world_size_for_height(settings: &Settings) -> f32 {
 	settings.world_x().min(settings.world_z())
 }
 
-/*
 /// Clamp world position to terrain bounds
 pub fn clamp_to_terrain_bounds(world_pos: Vec3, settings: &Settings) -> Vec3 {
-	let half_world_x = settings.world_x() / 2.0;
-	let half_world_z = settings.world_z() / 2.0;
+	let (min, max) = settings.world_bounds();
+	world_pos.clamp(min.with_y(world_pos.y), max.with_y(world_pos.y))
+}
 
-	Vec3::new(
-		world_pos.x.clamp(-half_world_x, half_world_x),
-		world_pos.y,
-		world_pos.z.clamp(-half_world_z, half_world_z),
-	)
+/// Whether eyes `eye_height` above `a` and `b` can see each other: the straight line between
+/// them is stepped every half cell and blocked wherever the terrain rises above it.
+pub fn line_of_sight(
+	a: Vec3,
+	b: Vec3,
+	heightmap: &super::HeightMap,
+	settings: &Settings,
+	eye_height: f32,
+) -> bool {
+	let (from, to) = (a + Vec3::Y * eye_height, b + Vec3::Y * eye_height);
+	let half_cell = (settings.world_x() / heightmap.length_x.max(1) as f32)
+		.min(settings.world_z() / heightmap.length_z().max(1) as f32)
+		/ 2.0;
+	let steps = (from.xz().distance(to.xz()) / half_cell).ceil().max(1.0) as u32;
+	// The ends themselves sit on whatever ground they were placed on, so only test between them
+	(1..steps).all(|i| {
+		let point = from.lerp(to, i as f32 / steps as f32);
+		calculate_terrain_height(point, heightmap, settings) <= point.y
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::terrain::HeightMap;
+
+	#[test]
+	fn world_sampling_interpolates_clamps_and_reports_slope() {
+		let settings = Settings {
+			base_grid_resolution: 2,
+			..Settings::default()
+		};
+		// Height rises linearly along X: 0, 0.5, 1 in every row
+		let height_map = HeightMap {
+			length_x: 2,
+			heights: [0.0, 0.5, 1.0].repeat(3),
+		};
+		let scale = world_size_for_height(&settings) * settings.height_multiplier;
+		let half = settings.world_x() / 2.0;
+
+		let middle = height_map.sample_world(Vec3::new(half / 2.0, 0.0, 0.0), &settings);
+		assert!((middle - 0.75 * scale).abs() < 1e-3);
+		let outside = height_map.sample_world(Vec3::new(2.0 * half, 0.0, 0.0), &settings);
+		assert!(
+			(outside - scale).abs() < 1e-3,
+			"far side should clamp to the edge"
+		);
+
+		let expected_slope = scale / settings.world_x();
+		for x in [-half, 0.0, half] {
+			let gradient = height_map.sample_world_gradient(Vec3::new(x, 0.0, 0.0), &settings);
+			assert!(
+				(gradient.x - expected_slope).abs() < 1e-4,
+				"gradient {gradient} at x {x}"
+			);
+			assert!(gradient.y.abs() < 1e-6);
+		}
+	}
+
+	#[test]
+	fn normal_tilts_away_from_the_uphill_direction() {
+		let settings = Settings {
+			base_grid_resolution: 2,
+			..Settings::default()
+		};
+		// Rises along +X, flat along Z
+		let height_map = HeightMap {
+			length_x: 2,
+			heights: [0.0, 0.5, 1.0].repeat(3),
+		};
+		let normal = height_map.normal_at(1, 1, &settings);
+		let slope = world_size_for_height(&settings) * settings.height_multiplier / settings.world_x();
+		assert!((normal - Vec3::new(-slope, 1.0, 0.0).normalize()).length() < 1e-5);
+		assert!(normal.x < 0.0 && normal.y > 0.0);
+		assert_eq!(height_map.normal_at(0, 1, &settings), Vec3::Y);
+		assert_eq!(height_map.normal_at(1, 2, &settings), Vec3::Y);
+	}
+
+	#[test]
+	fn far_outside_points_clamp_to_the_terrain_edge() {
+		let settings = Settings::default();
+		let (half_x, half_z) = (settings.world_x() / 2.0, settings.world_z() / 2.0);
+		let clamped = clamp_to_terrain_bounds(Vec3::new(1e6, 12.0, -1e6), &settings);
+		assert_eq!(clamped, Vec3::new(half_x, 12.0, -half_z));
+
+		let grid = world_to_grid(clamped, &settings);
+		assert_eq!(grid, UVec2::new(settings.grid_x(), 0));
+		assert!(grid_to_world(grid.x, grid.y, &settings).distance(clamped.with_y(0.0)) < 1e-3);
+	}
+
+	#[test]
+	fn world_bounds_span_the_terrain_and_its_height_range() {
+		let settings = Settings {
+			aspect_x: 2,
+			..Settings::default()
+		};
+		let (min, max) = settings.world_bounds();
+		assert_eq!(max.x - min.x, settings.world_x());
+		assert_eq!(max.z - min.z, settings.world_z());
+		assert_eq!(min.xz(), -max.xz());
+		assert_eq!(min.y, 0.0);
+		assert_eq!(
+			max.y,
+			world_size_for_height(&settings) * settings.height_multiplier
+		);
+	}
+
+	#[test]
+	fn a_ridge_blocks_the_sight_line_until_eyes_clear_it() {
+		let settings = Settings::default();
+		let ridge = HeightMap {
+			length_x: 2,
+			heights: [0.0, 1.0, 0.0].repeat(3),
+		};
+		let scale = world_size_for_height(&settings) * settings.height_multiplier;
+		let half = settings.world_x() / 2.0;
+		let (a, b) = (Vec3::new(-half, 0.0, 0.0), Vec3::new(half, 0.0, 0.0));
+
+		assert!(!line_of_sight(a, b, &ridge, &settings, 0.5 * scale));
+		assert!(line_of_sight(a, b, &ridge, &settings, 1.01 * scale));
+		let flat = HeightMap {
+			length_x: 2,
+			heights: vec![0.0; 9],
+		};
+		assert!(line_of_sight(a, b, &flat, &settings, 0.0));
+	}
 }
-*/
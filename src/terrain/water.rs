@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+use bevy_egui::egui;
+
+use super::{HeightMap, Settings, TerrainUpdateSet, world_size_for_height};
+use crate::units::Units;
+
+/// Flat translucent plane at `Settings::water_level`, for seeing which ground floods.
+pub struct WaterPlugin;
+
+impl Plugin for WaterPlugin {
+	fn build(&self, app: &mut App) {
+		app
+			.add_systems(Startup, spawn_water_plane)
+			.add_systems(Update, update_water_plane.in_set(TerrainUpdateSet));
+	}
+}
+
+#[derive(Component)]
+pub struct WaterPlane;
+
+const WATER_COLOR: Color = Color::srgba(0.15, 0.4, 0.85, 0.45);
+
+/// World Y of a normalized water level, using the same vertical scale as the terrain mesh.
+pub fn water_elevation(settings: &Settings) -> f32 {
+	settings.water_level * world_size_for_height(settings) * settings.height_multiplier
+}
+
+/// Share of height map vertices strictly below the normalized `level`.
+pub fn submerged_fraction(height_map: &HeightMap, level: f32) -> f32 {
+	if height_map.heights.is_empty() {
+		return 0.0;
+	}
+	let submerged = height_map.heights.iter().filter(|h| **h < level).count();
+	submerged as f32 / height_map.heights.len() as f32
+}
+
+fn water_transform(settings: &Settings) -> Transform {
+	// The mesh is a unit square, stretched to cover the terrain footprint
+	Transform::from_xyz(0.0, water_elevation(settings), 0.0).with_scale(Vec3::new(
+		settings.world_x(),
+		1.0,
+		settings.world_z(),
+	))
+}
+
+fn water_visibility(settings: &Settings) -> Visibility {
+	if settings.water_level > 0.0 {
+		Visibility::Inherited
+	} else {
+		Visibility::Hidden
+	}
+}
+
+fn spawn_water_plane(
+	mut commands: Commands,
+	mut meshes: ResMut<Assets<Mesh>>,
+	mut materials: ResMut<Assets<StandardMaterial>>,
+	settings: Res<Settings>,
+) {
+	commands.spawn((
+		Mesh3d(meshes.add(Plane3d::default().mesh().size(1.0, 1.0))),
+		MeshMaterial3d(materials.add(StandardMaterial {
+			base_color: WATER_COLOR,
+			alpha_mode: AlphaMode::Blend,
+			perceptual_roughness: 0.1,
+			double_sided: true,
+			cull_mode: None,
+			..default()
+		})),
+		water_transform(&settings),
+		water_visibility(&settings),
+		WaterPlane,
+	));
+}
+
+fn update_water_plane(
+	settings: Res<Settings>,
+	water: Single<(&mut Transform, &mut Visibility), With<WaterPlane>>,
+) {
+	if !settings.is_changed() {
+		return;
+	}
+	let (mut transform, mut visibility) = water.into_inner();
+	*transform = water_transform(&settings);
+	*visibility = water_visibility(&settings);
+}
+
+pub(super) fn water_ui(
+	ui: &mut egui::Ui,
+	settings: &mut Settings,
+	height_map: Option<&HeightMap>,
+	units: Units,
+) {
+	ui.label("Water Level (0 hides the water):");
+	ui.add(egui::Slider::new(&mut settings.water_level, 0.0..=1.0));
+	ui.label(format!(
+		"Elevation: {}",
+		units.format_length(f64::from(water_elevation(settings)))
+	));
+	if let Some(height_map) = height_map {
+		let fraction = submerged_fraction(height_map, settings.water_level);
+		ui.label(format!("Submerged: {:.1}%", fraction * 100.0));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn submerged_fraction_counts_vertices_below_the_level() {
+		let height_map = HeightMap {
+			length_x: 3,
+			heights: vec![0.0, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7],
+		};
+		assert_eq!(submerged_fraction(&height_map, 0.0), 0.0);
+		assert_eq!(submerged_fraction(&height_map, 0.35), 0.5);
+		assert_eq!(submerged_fraction(&height_map, 1.0), 1.0);
+	}
+}
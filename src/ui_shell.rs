@@ -2,19 +2,26 @@ use bevy::prelude::*;
 use bevy::window::{PresentMode, PrimaryWindow};
 use bevy_egui::{EguiContexts, egui};
 
+use crate::alignment::{GeoOrigin, PinOrderSettings, TangentSnapSettings, TrackBuildingMode};
 use crate::alignment::{MAX_SNAP_ANGLE_DEGREES, MIN_SNAP_ANGLE_DEGREES};
-use crate::alignment::{TangentSnapSettings, TrackBuildingMode};
+use crate::autosave::{AutosaveSettings, autosave_settings_ui};
 use crate::debug_frame_limiter::FrameLimiterState;
+use crate::pin::{PinDisplaySettings, SnapSettings};
 use crate::terrain::ContourState;
+use crate::units::{DisplayPrecision, Units};
 
 pub struct UiShellPlugin;
 
 impl Plugin for UiShellPlugin {
 	fn build(&self, app: &mut App) {
-		app.init_resource::<UiShellState>().add_systems(
-			bevy_egui::EguiPrimaryContextPass,
-			(bottom_bar_ui, settings_ui),
-		);
+		app
+			.init_resource::<UiShellState>()
+			.init_resource::<Units>()
+			.init_resource::<DisplayPrecision>()
+			.add_systems(
+				bevy_egui::EguiPrimaryContextPass,
+				(bottom_bar_ui, settings_ui),
+			);
 	}
 }
 
@@ -101,6 +108,13 @@ fn settings_ui(
 	mut snap_settings: ResMut<TangentSnapSettings>,
 	mut windows: Query<&mut Window, With<PrimaryWindow>>,
 	mut frame_limiter: ResMut<FrameLimiterState>,
+	mut units: ResMut<Units>,
+	mut precision: ResMut<DisplayPrecision>,
+	mut pin_snap: ResMut<SnapSettings>,
+	mut autosave: ResMut<AutosaveSettings>,
+	mut pin_display: ResMut<PinDisplaySettings>,
+	mut pin_order: ResMut<PinOrderSettings>,
+	mut geo_origin: ResMut<GeoOrigin>,
 ) {
 	if ui_shell_state.active_panel != ActivePanel::Settings {
 		return;
@@ -154,6 +168,62 @@ fn settings_ui(
 							ui.end_row();
 						}
 					}
+					ui.label("Units");
+					egui::ComboBox::from_id_salt("units")
+						.selected_text(units.label())
+						.show_ui(ui, |ui| {
+							for option in Units::ALL {
+								ui.selectable_value(&mut *units, option, option.label());
+							}
+						});
+					ui.end_row();
+					ui.label("Decimal places");
+					ui.add(egui::DragValue::new(&mut precision.0).range(0..=DisplayPrecision::MAX));
+					ui.end_row();
+					ui.label("Grid snap");
+					ui.horizontal(|ui| {
+						ui.checkbox(&mut pin_snap.enabled, "")
+							.on_hover_text("Hold Alt while dragging to place freely");
+						ui.add_enabled(
+							pin_snap.enabled,
+							units
+								.length_drag_value(&mut pin_snap.spacing)
+								.range(1.0..=1000.0),
+						);
+					});
+					ui.end_row();
+					ui.label("Keep pin order");
+					ui.checkbox(&mut pin_order.maintain_order, "")
+						.on_hover_text("Stop dragged vertices at their neighbours along the alignment");
+					ui.end_row();
+					ui.label("Pin size");
+					ui.add(
+						egui::Slider::new(&mut pin_display.base_size, 0.1..=10.0)
+							.logarithmic(true)
+							.suffix("x"),
+					);
+					ui.end_row();
+					ui.label("GIS origin");
+					ui.horizontal(|ui| {
+						ui.add(
+							egui::DragValue::new(&mut geo_origin.easting)
+								.prefix("E ")
+								.suffix(" m"),
+						);
+						ui.add(
+							egui::DragValue::new(&mut geo_origin.northing)
+								.prefix("N ")
+								.suffix(" m"),
+						);
+						ui.checkbox(&mut geo_origin.flip_z, "Flip Z")
+							.on_hover_text("World +Z points south");
+					})
+					.response
+					.on_hover_text("Real-world position of the world origin in GeoJSON exports");
+					ui.end_row();
+					ui.label("Autosave");
+					autosave_settings_ui(ui, &mut autosave);
+					ui.end_row();
 					ui.label("Snap angle");
 					ui.add(
 						egui::Slider::new(
@@ -0,0 +1,253 @@
+use bevy::prelude::*;
+use bevy_egui::egui;
+
+// Everything is stored in meters; these helpers only affect what the user sees.
+pub(crate) const FEET_PER_METER: f64 = 1.0 / 0.3048;
+const FEET_PER_MILE: f64 = 5280.0;
+const FEET_PER_CHAIN: f64 = 66.0;
+const METERS_PER_KILOMETER: f64 = 1000.0;
+const SQUARE_METERS_PER_HECTARE: f64 = 10_000.0;
+const SQUARE_FEET_PER_ACRE: f64 = 43_560.0;
+
+pub(crate) fn meters_to_feet(meters: f64) -> f64 {
+	meters * FEET_PER_METER
+}
+
+pub(crate) fn feet_to_meters(feet: f64) -> f64 {
+	feet / FEET_PER_METER
+}
+
+/// Decimal places shown in coordinate and angle readouts.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct DisplayPrecision(pub usize);
+
+impl Default for DisplayPrecision {
+	fn default() -> Self {
+		Self(2)
+	}
+}
+
+impl DisplayPrecision {
+	pub(crate) const MAX: usize = 6;
+
+	pub(crate) fn format(self, value: f64) -> String {
+		format!("{value:.*}", self.0)
+	}
+
+	pub(crate) fn format_degrees(self, radians: f64) -> String {
+		format!("{}°", self.format(radians.to_degrees()))
+	}
+}
+
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum Units {
+	#[default]
+	Metric,
+	Imperial,
+}
+
+impl Units {
+	pub(crate) const ALL: [Self; 2] = [Self::Metric, Self::Imperial];
+
+	pub(crate) const fn label(self) -> &'static str {
+		match self {
+			Self::Metric => "Metric",
+			Self::Imperial => "Imperial",
+		}
+	}
+
+	pub(crate) const fn length_suffix(self) -> &'static str {
+		match self {
+			Self::Metric => " m",
+			Self::Imperial => " ft",
+		}
+	}
+
+	/// Converts a length in meters to this unit system's base length unit.
+	pub(crate) fn to_display(self, meters: f64) -> f64 {
+		match self {
+			Self::Metric => meters,
+			Self::Imperial => meters_to_feet(meters),
+		}
+	}
+
+	/// Converts a value typed by the user back to meters.
+	pub(crate) fn meters_from_display(self, value: f64) -> f64 {
+		match self {
+			Self::Metric => value,
+			Self::Imperial => feet_to_meters(value),
+		}
+	}
+
+	pub(crate) fn format_length(self, meters: f64) -> String {
+		format!("{:.2}{}", self.to_display(meters), self.length_suffix())
+	}
+
+	/// Long distances switch to kilometers or miles.
+	pub(crate) fn format_distance(self, meters: f64) -> String {
+		match self {
+			Self::Metric if meters.abs() >= METERS_PER_KILOMETER => {
+				format!("{:.3} km", meters / METERS_PER_KILOMETER)
+			}
+			Self::Imperial if meters_to_feet(meters).abs() >= FEET_PER_MILE => {
+				format!("{:.3} mi", meters_to_feet(meters) / FEET_PER_MILE)
+			}
+			_ => self.format_length(meters),
+		}
+	}
+
+	/// Station in surveyors' notation: kilometers+meters, or hundreds of feet+feet.
+	pub(crate) fn format_station(self, meters: f64) -> String {
+		let (value, per_station, digits) = match self {
+			Self::Metric => (meters, 1000, 3),
+			Self::Imperial => (meters_to_feet(meters), 100, 2),
+		};
+		let value = value.max(0.0).round() as u64;
+		format!("{}+{:0digits$}", value / per_station, value % per_station)
+	}
+
+	/// Large areas switch to hectares or acres.
+	pub(crate) fn format_area(self, square_meters: f64) -> String {
+		match self {
+			Self::Metric if square_meters >= SQUARE_METERS_PER_HECTARE => {
+				format!("{:.2} ha", square_meters / SQUARE_METERS_PER_HECTARE)
+			}
+			Self::Metric => format!("{square_meters:.0} m²"),
+			Self::Imperial => {
+				let square_feet = meters_to_feet(meters_to_feet(square_meters));
+				// Rounded so an area that would print as 43560 ft² shows as an acre instead
+				if square_feet.round() >= SQUARE_FEET_PER_ACRE {
+					format!("{:.2} ac", square_feet / SQUARE_FEET_PER_ACRE)
+				} else {
+					format!("{square_feet:.0} ft²")
+				}
+			}
+		}
+	}
+
+	/// Imperial radii are also given in chains, as is customary for railway curves.
+	pub(crate) fn format_radius(self, meters: f64) -> String {
+		match self {
+			Self::Metric => format!("{meters:.0} m"),
+			Self::Imperial => {
+				let feet = meters_to_feet(meters);
+				format!("{feet:.0} ft ({:.2} ch)", feet / FEET_PER_CHAIN)
+			}
+		}
+	}
+
+	/// `grade` is rise over run. Imperial uses the "1 in N" notation, negative for a downgrade.
+	pub(crate) fn format_grade(self, grade: f64) -> String {
+		match self {
+			Self::Metric => format!("{:.2} %", grade * 100.0),
+			Self::Imperial if grade == 0.0 => "level".to_string(),
+			Self::Imperial => {
+				let sign = if grade < 0.0 { "-" } else { "" };
+				format!("{sign}1 in {:.0}", 1.0 / grade.abs())
+			}
+		}
+	}
+
+	pub(crate) fn format_position(self, position: Vec3, precision: DisplayPrecision) -> String {
+		format!(
+			"({}, {}, {}){}",
+			precision.format(self.to_display(f64::from(position.x))),
+			precision.format(self.to_display(f64::from(position.y))),
+			precision.format(self.to_display(f64::from(position.z))),
+			self.length_suffix()
+		)
+	}
+
+	/// A drag value that edits a length stored in meters in this unit system.
+	pub(crate) fn length_drag_value(self, meters: &mut f32) -> egui::DragValue<'_> {
+		egui::DragValue::new(meters)
+			.custom_formatter(move |value, decimals| {
+				egui::emath::format_with_decimals_in_range(self.to_display(value), decimals)
+			})
+			.custom_parser(move |text| {
+				text
+					.parse::<f64>()
+					.ok()
+					.map(|v| self.meters_from_display(v))
+			})
+			.suffix(self.length_suffix())
+	}
+
+	/// A slider over a length stored in meters, shown in this unit system.
+	pub(crate) fn length_slider(
+		self,
+		meters: &mut f32,
+		range: std::ops::RangeInclusive<f32>,
+	) -> egui::Slider<'_> {
+		egui::Slider::new(meters, range)
+			.custom_formatter(move |value, decimals| {
+				egui::emath::format_with_decimals_in_range(self.to_display(value), decimals)
+			})
+			.custom_parser(move |text| {
+				text
+					.parse::<f64>()
+					.ok()
+					.map(|v| self.meters_from_display(v))
+			})
+			.suffix(self.length_suffix())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn meters_and_feet_round_trip() {
+		assert!((meters_to_feet(0.3048) - 1.0).abs() < 1e-12);
+		assert!((feet_to_meters(5280.0) - 1609.344).abs() < 1e-9);
+		assert!((feet_to_meters(meters_to_feet(123.456)) - 123.456).abs() < 1e-9);
+	}
+
+	#[test]
+	fn display_precision_controls_decimal_places() {
+		let position = Vec3::new(1.0, 2.25, -3.1418);
+		assert_eq!(
+			Units::Metric.format_position(position, DisplayPrecision(0)),
+			"(1, 2, -3) m"
+		);
+		assert_eq!(
+			Units::Metric.format_position(position, DisplayPrecision(3)),
+			"(1.000, 2.250, -3.142) m"
+		);
+		assert_eq!(
+			DisplayPrecision(1).format_degrees(std::f64::consts::FRAC_PI_4),
+			"45.0°"
+		);
+	}
+
+	#[test]
+	fn station_uses_plus_notation() {
+		assert_eq!(Units::Metric.format_station(0.0), "0+000");
+		assert_eq!(Units::Metric.format_station(12_345.4), "12+345");
+		assert_eq!(
+			Units::Imperial.format_station(feet_to_meters(4_101.0)),
+			"41+01"
+		);
+	}
+
+	#[test]
+	fn imperial_grade_keeps_its_sign() {
+		assert_eq!(Units::Imperial.format_grade(0.025), "1 in 40");
+		assert_eq!(Units::Imperial.format_grade(-0.025), "-1 in 40");
+		assert_eq!(Units::Metric.format_grade(-0.025), "-2.50 %");
+	}
+
+	#[test]
+	fn area_switches_to_hectares_and_acres() {
+		assert_eq!(Units::Metric.format_area(500.0), "500 m²");
+		assert_eq!(Units::Metric.format_area(25_000.0), "2.50 ha");
+		assert_eq!(Units::Imperial.format_area(4_046.856_422_4), "1.00 ac");
+	}
+
+	#[test]
+	fn radius_is_formatted_per_unit_system() {
+		assert_eq!(Units::Metric.format_radius(500.0), "500 m");
+		assert_eq!(Units::Imperial.format_radius(201.168), "660 ft (10.00 ch)");
+	}
+}